@@ -0,0 +1,629 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::f32::consts::PI;
+
+use crate::stream;
+use crate::stream::Stream;
+use crate::error::Error;
+
+/// Dimension unit.
+#[derive(Clone,Copy,PartialEq,Debug)]
+#[allow(missing_docs)]
+pub enum Unit {
+    // Absolute lengths.
+    Px,
+    Cm,
+    Mm,
+    Q,
+    In,
+    Pt,
+    Pc,
+    // Font-relative lengths.
+    Em,
+    Rem,
+    Ex,
+    Ch,
+    // Viewport-relative lengths.
+    Vw,
+    Vh,
+    Vmin,
+    Vmax,
+    // Angles.
+    Deg,
+    Rad,
+    Grad,
+    Turn,
+    // Time.
+    S,
+    Ms,
+}
+
+impl Unit {
+    /// Parses a unit name. Case-insensitive.
+    pub fn from_name(text: &str) -> Option<Unit> {
+        let unit = match text.to_ascii_lowercase().as_str() {
+            "px" => Unit::Px,
+            "cm" => Unit::Cm,
+            "mm" => Unit::Mm,
+            "q" => Unit::Q,
+            "in" => Unit::In,
+            "pt" => Unit::Pt,
+            "pc" => Unit::Pc,
+            "em" => Unit::Em,
+            "rem" => Unit::Rem,
+            "ex" => Unit::Ex,
+            "ch" => Unit::Ch,
+            "vw" => Unit::Vw,
+            "vh" => Unit::Vh,
+            "vmin" => Unit::Vmin,
+            "vmax" => Unit::Vmax,
+            "deg" => Unit::Deg,
+            "rad" => Unit::Rad,
+            "grad" => Unit::Grad,
+            "turn" => Unit::Turn,
+            "s" => Unit::S,
+            "ms" => Unit::Ms,
+            _ => return None,
+        };
+
+        Some(unit)
+    }
+
+    /// Returns the type of values with this unit.
+    pub fn value_type(&self) -> CalcType {
+        match *self {
+            Unit::Deg | Unit::Rad | Unit::Grad | Unit::Turn => CalcType::Angle,
+            Unit::S | Unit::Ms => CalcType::Time,
+            _ => CalcType::Length,
+        }
+    }
+
+    /// Returns a factor to the canonical unit (`px`, `deg` or `s`),
+    /// if this unit doesn't depend on the context.
+    pub(crate) fn absolute_factor(&self) -> Option<f32> {
+        let f = match *self {
+            Unit::Px => 1.0,
+            Unit::In => 96.0,
+            Unit::Cm => 96.0 / 2.54,
+            Unit::Mm => 96.0 / 25.4,
+            Unit::Q => 96.0 / 101.6,
+            Unit::Pt => 96.0 / 72.0,
+            Unit::Pc => 16.0,
+            Unit::Deg => 1.0,
+            Unit::Rad => 180.0 / PI,
+            Unit::Grad => 0.9,
+            Unit::Turn => 360.0,
+            Unit::S => 1.0,
+            Unit::Ms => 0.001,
+            _ => return None,
+        };
+
+        Some(f)
+    }
+}
+
+/// Type of a math expression.
+///
+/// https://www.w3.org/TR/css-values-4/#calc-type-checking
+#[derive(Clone,Copy,PartialEq,Debug)]
+#[allow(missing_docs)]
+pub enum CalcType {
+    Number,
+    Length,
+    Percentage,
+    /// A mix of lengths and percentages, like `100% - 10px`.
+    LengthPercentage,
+    Angle,
+    Time,
+}
+
+impl CalcType {
+    /// Returns the type of a sum of two values, if they can be added.
+    fn add(self, other: CalcType) -> Option<CalcType> {
+        use self::CalcType::*;
+
+        match (self, other) {
+            (a, b) if a == b => Some(a),
+            (Length, Percentage) | (Percentage, Length)
+            | (LengthPercentage, Length) | (Length, LengthPercentage)
+            | (LengthPercentage, Percentage) | (Percentage, LengthPercentage) => {
+                Some(LengthPercentage)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A node of a math expression tree.
+///
+/// https://www.w3.org/TR/css-values-4/#math
+#[derive(Clone,PartialEq,Debug)]
+pub enum CalcNode {
+    /// Plain number.
+    Number(f32),
+    /// Percentage, like `50%`. Value is stored without the `%`.
+    Percentage(f32),
+    /// Number with a unit, like `10px`.
+    Dimension(f32, Unit),
+    /// `a + b`
+    Add(Box<CalcNode>, Box<CalcNode>),
+    /// `a - b`
+    Sub(Box<CalcNode>, Box<CalcNode>),
+    /// `a * b`
+    Mul(Box<CalcNode>, Box<CalcNode>),
+    /// `a / b`
+    Div(Box<CalcNode>, Box<CalcNode>),
+    /// `min(a, b, ...)`
+    Min(Vec<CalcNode>),
+    /// `max(a, b, ...)`
+    Max(Vec<CalcNode>),
+    /// `clamp(min, value, max)`
+    Clamp(Box<CalcNode>, Box<CalcNode>, Box<CalcNode>),
+}
+
+/// Values required to resolve relative units and percentages.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct CalcContext {
+    /// The length percentages are resolved against, in `px`.
+    ///
+    /// If `None`, percentages mixed with lengths can't be resolved.
+    pub percentage_basis: Option<f32>,
+    /// Font size of the element, in `px`.
+    pub font_size: f32,
+    /// Font size of the root element, in `px`.
+    pub root_font_size: f32,
+    /// Viewport width, in `px`.
+    pub viewport_width: f32,
+    /// Viewport height, in `px`.
+    pub viewport_height: f32,
+}
+
+impl Default for CalcContext {
+    fn default() -> Self {
+        CalcContext {
+            percentage_basis: None,
+            font_size: 16.0,
+            root_font_size: 16.0,
+            viewport_width: 0.0,
+            viewport_height: 0.0,
+        }
+    }
+}
+
+/// A computed math expression value.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum CalcValue {
+    /// Plain number.
+    Number(f32),
+    /// Length, in `px`.
+    Length(f32),
+    /// Unresolved percentage.
+    ///
+    /// Only returned when `CalcContext::percentage_basis` is not set.
+    Percentage(f32),
+    /// Angle, in degrees.
+    Angle(f32),
+    /// Time, in seconds.
+    Time(f32),
+}
+
+impl CalcNode {
+    /// Parses a math expression from a declaration value.
+    ///
+    /// Accepts `calc()`, `min()`, `max()` and `clamp()` functions,
+    /// as well as a plain number, percentage or dimension.
+    /// The expression is type-checked, so `calc(1px + 2s)` is an error.
+    ///
+    /// `var()` references are not supported and must be substituted beforehand
+    /// using `CustomProperties::substitute`.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &str) -> Result<CalcNode, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+        let node = consume_value(&mut s)?;
+        s.skip_spaces();
+
+        if !s.at_end() {
+            return Err(Error::UnknownToken(s.gen_error_pos()));
+        }
+
+        Ok(node)
+    }
+
+    /// Returns the type of the expression.
+    ///
+    /// Returns `None` if the expression is invalid, like `1px + 2s`.
+    pub fn value_type(&self) -> Option<CalcType> {
+        match *self {
+            CalcNode::Number(_) => Some(CalcType::Number),
+            CalcNode::Percentage(_) => Some(CalcType::Percentage),
+            CalcNode::Dimension(_, unit) => Some(unit.value_type()),
+            CalcNode::Add(ref a, ref b) | CalcNode::Sub(ref a, ref b) => {
+                a.value_type()?.add(b.value_type()?)
+            }
+            CalcNode::Mul(ref a, ref b) => {
+                match (a.value_type()?, b.value_type()?) {
+                    (CalcType::Number, t) | (t, CalcType::Number) => Some(t),
+                    _ => None,
+                }
+            }
+            CalcNode::Div(ref a, ref b) => {
+                match b.value_type()? {
+                    CalcType::Number => a.value_type(),
+                    _ => None,
+                }
+            }
+            CalcNode::Min(ref list) | CalcNode::Max(ref list) => {
+                let mut t = list.first()?.value_type()?;
+                for node in &list[1..] {
+                    t = t.add(node.value_type()?)?;
+                }
+                Some(t)
+            }
+            CalcNode::Clamp(ref min, ref value, ref max) => {
+                min.value_type()?.add(value.value_type()?)?.add(max.value_type()?)
+            }
+        }
+    }
+
+    /// Evaluates the expression.
+    ///
+    /// Returns `None` if the expression is invalid or if percentages
+    /// can't be resolved, because `CalcContext::percentage_basis` is not set.
+    pub fn eval(&self, ctx: &CalcContext) -> Option<CalcValue> {
+        use self::CalcValue::*;
+
+        match *self {
+            CalcNode::Number(n) => Some(Number(n)),
+            CalcNode::Percentage(n) => match ctx.percentage_basis {
+                Some(basis) => Some(Length(n / 100.0 * basis)),
+                None => Some(Percentage(n)),
+            },
+            CalcNode::Dimension(n, unit) => Some(eval_dimension(n, unit, ctx)),
+            CalcNode::Add(ref a, ref b) => {
+                combine(a.eval(ctx)?, b.eval(ctx)?, |a, b| a + b)
+            }
+            CalcNode::Sub(ref a, ref b) => {
+                combine(a.eval(ctx)?, b.eval(ctx)?, |a, b| a - b)
+            }
+            CalcNode::Mul(ref a, ref b) => {
+                match (a.eval(ctx)?, b.eval(ctx)?) {
+                    (Number(n), v) | (v, Number(n)) => Some(v.map(|v| v * n)),
+                    _ => None,
+                }
+            }
+            CalcNode::Div(ref a, ref b) => {
+                match b.eval(ctx)? {
+                    Number(n) => Some(a.eval(ctx)?.map(|v| v / n)),
+                    _ => None,
+                }
+            }
+            CalcNode::Min(ref list) => {
+                let mut v = list.first()?.eval(ctx)?;
+                for node in &list[1..] {
+                    v = combine(v, node.eval(ctx)?, f32::min)?;
+                }
+                Some(v)
+            }
+            CalcNode::Max(ref list) => {
+                let mut v = list.first()?.eval(ctx)?;
+                for node in &list[1..] {
+                    v = combine(v, node.eval(ctx)?, f32::max)?;
+                }
+                Some(v)
+            }
+            CalcNode::Clamp(ref min, ref value, ref max) => {
+                let v = combine(min.eval(ctx)?, value.eval(ctx)?, f32::max)?;
+                combine(v, max.eval(ctx)?, f32::min)
+            }
+        }
+    }
+
+    /// Simplifies the expression by folding all parts that don't depend on the context.
+    ///
+    /// Absolute lengths are converted to `px`, angles to `deg` and time to `s`.
+    /// If the whole expression is absolute, a single leaf node is returned.
+    pub fn simplify(&self) -> CalcNode {
+        match *self {
+            CalcNode::Number(_) | CalcNode::Percentage(_) => self.clone(),
+            CalcNode::Dimension(n, unit) => {
+                match unit.absolute_factor() {
+                    Some(f) => CalcNode::Dimension(n * f, canonical_unit(unit)),
+                    None => self.clone(),
+                }
+            }
+            CalcNode::Add(ref a, ref b) => {
+                let (a, b) = (a.simplify(), b.simplify());
+                fold(&a, &b, |a, b| a + b)
+                    .unwrap_or_else(|| CalcNode::Add(Box::new(a), Box::new(b)))
+            }
+            CalcNode::Sub(ref a, ref b) => {
+                let (a, b) = (a.simplify(), b.simplify());
+                fold(&a, &b, |a, b| a - b)
+                    .unwrap_or_else(|| CalcNode::Sub(Box::new(a), Box::new(b)))
+            }
+            CalcNode::Mul(ref a, ref b) => {
+                let (a, b) = (a.simplify(), b.simplify());
+                match (&a, &b) {
+                    (&CalcNode::Number(n), v) | (v, &CalcNode::Number(n)) if v.is_leaf() => {
+                        v.map_leaf(|v| v * n)
+                    }
+                    _ => CalcNode::Mul(Box::new(a), Box::new(b)),
+                }
+            }
+            CalcNode::Div(ref a, ref b) => {
+                let (a, b) = (a.simplify(), b.simplify());
+                match (&a, &b) {
+                    (v, &CalcNode::Number(n)) if v.is_leaf() => v.map_leaf(|v| v / n),
+                    _ => CalcNode::Div(Box::new(a), Box::new(b)),
+                }
+            }
+            CalcNode::Min(ref list) => {
+                let list: Vec<_> = list.iter().map(|n| n.simplify()).collect();
+                fold_list(&list, f32::min).unwrap_or(CalcNode::Min(list))
+            }
+            CalcNode::Max(ref list) => {
+                let list: Vec<_> = list.iter().map(|n| n.simplify()).collect();
+                fold_list(&list, f32::max).unwrap_or(CalcNode::Max(list))
+            }
+            CalcNode::Clamp(ref min, ref value, ref max) => {
+                let (min, value, max) = (min.simplify(), value.simplify(), max.simplify());
+                match fold(&min, &value, f32::max).and_then(|v| fold(&v, &max, f32::min)) {
+                    Some(v) => v,
+                    None => CalcNode::Clamp(Box::new(min), Box::new(value), Box::new(max)),
+                }
+            }
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        matches!(*self, CalcNode::Number(_) | CalcNode::Percentage(_) | CalcNode::Dimension(..))
+    }
+
+    fn map_leaf<F: Fn(f32) -> f32>(&self, f: F) -> CalcNode {
+        match *self {
+            CalcNode::Number(n) => CalcNode::Number(f(n)),
+            CalcNode::Percentage(n) => CalcNode::Percentage(f(n)),
+            CalcNode::Dimension(n, unit) => CalcNode::Dimension(f(n), unit),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl CalcValue {
+    fn map<F: Fn(f32) -> f32>(self, f: F) -> CalcValue {
+        match self {
+            CalcValue::Number(n) => CalcValue::Number(f(n)),
+            CalcValue::Length(n) => CalcValue::Length(f(n)),
+            CalcValue::Percentage(n) => CalcValue::Percentage(f(n)),
+            CalcValue::Angle(n) => CalcValue::Angle(f(n)),
+            CalcValue::Time(n) => CalcValue::Time(f(n)),
+        }
+    }
+}
+
+fn canonical_unit(unit: Unit) -> Unit {
+    match unit.value_type() {
+        CalcType::Angle => Unit::Deg,
+        CalcType::Time => Unit::S,
+        _ => Unit::Px,
+    }
+}
+
+fn eval_dimension(n: f32, unit: Unit, ctx: &CalcContext) -> CalcValue {
+    if let Some(f) = unit.absolute_factor() {
+        return match unit.value_type() {
+            CalcType::Angle => CalcValue::Angle(n * f),
+            CalcType::Time => CalcValue::Time(n * f),
+            _ => CalcValue::Length(n * f),
+        };
+    }
+
+    let px = match unit {
+        Unit::Em => ctx.font_size,
+        Unit::Rem => ctx.root_font_size,
+        // Without font metrics, `ex` and `ch` are approximated as `0.5em`.
+        Unit::Ex | Unit::Ch => ctx.font_size * 0.5,
+        Unit::Vw => ctx.viewport_width / 100.0,
+        Unit::Vh => ctx.viewport_height / 100.0,
+        Unit::Vmin => ctx.viewport_width.min(ctx.viewport_height) / 100.0,
+        Unit::Vmax => ctx.viewport_width.max(ctx.viewport_height) / 100.0,
+        _ => unreachable!(),
+    };
+
+    CalcValue::Length(n * px)
+}
+
+fn combine<F: Fn(f32, f32) -> f32>(a: CalcValue, b: CalcValue, f: F) -> Option<CalcValue> {
+    use self::CalcValue::*;
+
+    match (a, b) {
+        (Number(a), Number(b)) => Some(Number(f(a, b))),
+        (Length(a), Length(b)) => Some(Length(f(a, b))),
+        (Percentage(a), Percentage(b)) => Some(Percentage(f(a, b))),
+        (Angle(a), Angle(b)) => Some(Angle(f(a, b))),
+        (Time(a), Time(b)) => Some(Time(f(a, b))),
+        _ => None,
+    }
+}
+
+/// Folds two leaves of the same kind.
+fn fold<F: Fn(f32, f32) -> f32>(a: &CalcNode, b: &CalcNode, f: F) -> Option<CalcNode> {
+    match (a, b) {
+        (&CalcNode::Number(a), &CalcNode::Number(b)) => Some(CalcNode::Number(f(a, b))),
+        (&CalcNode::Percentage(a), &CalcNode::Percentage(b)) => {
+            Some(CalcNode::Percentage(f(a, b)))
+        }
+        (&CalcNode::Dimension(a, ua), &CalcNode::Dimension(b, ub)) if ua == ub => {
+            Some(CalcNode::Dimension(f(a, b), ua))
+        }
+        _ => None,
+    }
+}
+
+fn fold_list<F: Fn(f32, f32) -> f32 + Copy>(list: &[CalcNode], f: F) -> Option<CalcNode> {
+    let mut v = list.first()?.clone();
+    for node in &list[1..] {
+        v = fold(&v, node, f)?;
+    }
+    Some(v)
+}
+
+/// Consumes a number, a dimension, a parenthesized sum or a math function.
+fn consume_value(s: &mut Stream) -> Result<CalcNode, Error> {
+    let start = s.gen_error_pos();
+
+    match s.curr_char()? {
+        b'(' => {
+            s.advance_raw(1);
+            let node = consume_sum(s)?;
+            s.skip_spaces();
+            s.consume_char(b')')?;
+            return Ok(node);
+        }
+        b'0'..=b'9' | b'.' | b'+' | b'-' if !is_ident_start(s) => {
+            let n = s.consume_number()?;
+
+            if s.char_at(0) == Some(b'%') {
+                s.advance_raw(1);
+                return Ok(CalcNode::Percentage(n));
+            }
+
+            if s.char_at(0).is_some_and(|c| c.is_ascii_alphabetic()) {
+                let unit_pos = s.gen_error_pos();
+                let unit = s.consume_ident()?;
+                return match Unit::from_name(unit) {
+                    Some(unit) => Ok(CalcNode::Dimension(n, unit)),
+                    None => Err(Error::UnknownToken(unit_pos)),
+                };
+            }
+
+            return Ok(CalcNode::Number(n));
+        }
+        _ => {}
+    }
+
+    let name = s.consume_ident()?;
+    s.consume_char(b'(')?;
+
+    let node = match name.to_ascii_lowercase().as_str() {
+        "calc" => {
+            let node = consume_sum(s)?;
+            s.skip_spaces();
+            node
+        }
+        "min" => CalcNode::Min(consume_args(s)?),
+        "max" => CalcNode::Max(consume_args(s)?),
+        "clamp" => {
+            let mut args = consume_args(s)?;
+            if args.len() != 3 {
+                return Err(Error::InvalidValue(start));
+            }
+
+            let max = args.pop().unwrap();
+            let value = args.pop().unwrap();
+            let min = args.pop().unwrap();
+            CalcNode::Clamp(Box::new(min), Box::new(value), Box::new(max))
+        }
+        _ => return Err(Error::UnsupportedToken(start)),
+    };
+
+    s.consume_char(b')')?;
+
+    if node.value_type().is_none() {
+        return Err(Error::InvalidValue(start));
+    }
+
+    Ok(node)
+}
+
+/// Checks that a `-` at the current position starts an ident, like `-webkit-calc`.
+fn is_ident_start(s: &Stream) -> bool {
+    s.char_at(0) == Some(b'-') && s.char_at(1).is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+/// Consumes a comma-separated list of sums, up to the closing `)`.
+fn consume_args(s: &mut Stream) -> Result<Vec<CalcNode>, Error> {
+    let mut args = Vec::new();
+
+    loop {
+        args.push(consume_sum(s)?);
+        s.skip_spaces();
+
+        if s.is_char_eq(b',')? {
+            s.advance_raw(1);
+        } else {
+            break;
+        }
+    }
+
+    Ok(args)
+}
+
+fn consume_sum(s: &mut Stream) -> Result<CalcNode, Error> {
+    s.skip_spaces();
+    let mut node = consume_product(s)?;
+
+    loop {
+        let had_space = s.char_at(0).is_some_and(stream::is_space);
+        s.skip_spaces();
+
+        let op = match s.char_at(0) {
+            Some(c @ b'+') | Some(c @ b'-') => c,
+            _ => return Ok(node),
+        };
+
+        // `+` and `-` must be surrounded by whitespace.
+        let op_pos = s.gen_error_pos();
+        if !had_space || !s.char_at(1).is_some_and(stream::is_space) {
+            return Err(Error::UnknownToken(op_pos));
+        }
+
+        s.advance_raw(1);
+        s.skip_spaces();
+        let rhs = consume_product(s)?;
+        node = if op == b'+' {
+            CalcNode::Add(Box::new(node), Box::new(rhs))
+        } else {
+            CalcNode::Sub(Box::new(node), Box::new(rhs))
+        };
+
+        if node.value_type().is_none() {
+            return Err(Error::InvalidValue(op_pos));
+        }
+    }
+}
+
+fn consume_product(s: &mut Stream) -> Result<CalcNode, Error> {
+    let mut node = consume_value(s)?;
+
+    loop {
+        // Whitespace before `+` and `-` is checked by `consume_sum`,
+        // so it must not be consumed here.
+        let mut next = *s;
+        next.skip_spaces();
+
+        let op = match next.char_at(0) {
+            Some(c @ b'*') | Some(c @ b'/') => c,
+            _ => return Ok(node),
+        };
+
+        *s = next;
+        let op_pos = s.gen_error_pos();
+        s.advance_raw(1);
+        s.skip_spaces();
+        let rhs = consume_value(s)?;
+        node = if op == b'*' {
+            CalcNode::Mul(Box::new(node), Box::new(rhs))
+        } else {
+            CalcNode::Div(Box::new(node), Box::new(rhs))
+        };
+
+        if node.value_type().is_none() {
+            return Err(Error::InvalidValue(op_pos));
+        }
+    }
+}
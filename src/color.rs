@@ -0,0 +1,486 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::f32::consts::PI;
+
+use crate::stream::Stream;
+use crate::error::Error;
+
+/// CSS color.
+///
+/// https://www.w3.org/TR/css-color-4/#color-type
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum Color {
+    /// sRGB color.
+    ///
+    /// All components are in the `0.0..=1.0` range.
+    Rgba {
+        #[allow(missing_docs)]
+        red: f32,
+        #[allow(missing_docs)]
+        green: f32,
+        #[allow(missing_docs)]
+        blue: f32,
+        #[allow(missing_docs)]
+        alpha: f32,
+    },
+    /// The `currentcolor` keyword.
+    ///
+    /// It can't be converted to sRGB without knowing the value of the `color` property.
+    CurrentColor,
+}
+
+#[derive(Clone,Copy)]
+enum Component {
+    Number(f32),
+    Percentage(f32),
+    /// Angle in degrees.
+    Angle(f32),
+}
+
+impl Color {
+    /// Constructs a new opaque color from 8-bit components.
+    pub fn from_rgb8(red: u8, green: u8, blue: u8) -> Color {
+        Color::from_rgba8(red, green, blue, 255)
+    }
+
+    /// Constructs a new color from 8-bit components.
+    pub fn from_rgba8(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color::Rgba {
+            red: red as f32 / 255.0,
+            green: green as f32 / 255.0,
+            blue: blue as f32 / 255.0,
+            alpha: alpha as f32 / 255.0,
+        }
+    }
+
+    /// Parses a color from a declaration value.
+    ///
+    /// Supports named colors, `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`,
+    /// `rgb()`, `rgba()`, `hsl()`, `hsla()`, `hwb()`, `currentcolor` and `transparent`.
+    /// Both the legacy comma syntax and the space syntax are accepted for functions.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &str) -> Result<Color, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+        let color = parse_color(&mut s)?;
+        s.skip_spaces();
+
+        if !s.at_end() {
+            return Err(Error::UnknownToken(s.gen_error_pos()));
+        }
+
+        Ok(color)
+    }
+
+    /// Converts the color into sRGBA components in the `0.0..=1.0` range.
+    ///
+    /// Returns `None` for `currentcolor`.
+    pub fn to_rgba_f32(&self) -> Option<[f32; 4]> {
+        match *self {
+            Color::Rgba { red, green, blue, alpha } => Some([red, green, blue, alpha]),
+            Color::CurrentColor => None,
+        }
+    }
+
+    /// Converts the color into 8-bit sRGBA components.
+    ///
+    /// Returns `None` for `currentcolor`.
+    pub fn to_rgba8(&self) -> Option<[u8; 4]> {
+        let c = self.to_rgba_f32()?;
+        let f = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Some([f(c[0]), f(c[1]), f(c[2]), f(c[3])])
+    }
+}
+
+fn parse_color(s: &mut Stream) -> Result<Color, Error> {
+    if s.is_char_eq(b'#')? {
+        s.advance_raw(1);
+        return parse_hex(s);
+    }
+
+    let start = s.gen_error_pos();
+    let name = s.consume_ident()?;
+
+    if s.char_at(0) == Some(b'(') {
+        s.advance_raw(1);
+        let color = if name.eq_ignore_ascii_case("rgb") || name.eq_ignore_ascii_case("rgba") {
+            parse_rgb(s)?
+        } else if name.eq_ignore_ascii_case("hsl") || name.eq_ignore_ascii_case("hsla") {
+            parse_hsl(s)?
+        } else if name.eq_ignore_ascii_case("hwb") {
+            parse_hwb(s)?
+        } else {
+            return Err(Error::UnsupportedToken(start));
+        };
+        return Ok(color);
+    }
+
+    if name.eq_ignore_ascii_case("currentcolor") {
+        return Ok(Color::CurrentColor);
+    }
+
+    if name.eq_ignore_ascii_case("transparent") {
+        return Ok(Color::from_rgba8(0, 0, 0, 0));
+    }
+
+    match from_name(name) {
+        Some(c) => Ok(c),
+        None => Err(Error::UnknownToken(start)),
+    }
+}
+
+fn parse_hex(s: &mut Stream) -> Result<Color, Error> {
+    let start = s.gen_error_pos();
+    let mut digits = [0u8; 8];
+    let mut len = 0;
+
+    while let Some(c) = s.char_at(0) {
+        let d = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => break,
+        };
+
+        if len == digits.len() {
+            return Err(Error::UnknownToken(start));
+        }
+
+        digits[len] = d;
+        len += 1;
+        s.advance_raw(1);
+    }
+
+    let d = digits;
+    let color = match len {
+        3 => Color::from_rgb8(d[0] * 17, d[1] * 17, d[2] * 17),
+        4 => Color::from_rgba8(d[0] * 17, d[1] * 17, d[2] * 17, d[3] * 17),
+        6 => Color::from_rgb8(d[0] * 16 + d[1], d[2] * 16 + d[3], d[4] * 16 + d[5]),
+        8 => Color::from_rgba8(d[0] * 16 + d[1], d[2] * 16 + d[3], d[4] * 16 + d[5],
+                               d[6] * 16 + d[7]),
+        _ => return Err(Error::UnknownToken(start)),
+    };
+
+    Ok(color)
+}
+
+fn consume_component(s: &mut Stream) -> Result<Component, Error> {
+    s.skip_spaces();
+    let start = s.gen_error_pos();
+    let n = s.consume_number()?;
+
+    if s.char_at(0) == Some(b'%') {
+        s.advance_raw(1);
+        return Ok(Component::Percentage(n));
+    }
+
+    if s.char_at(0).is_some_and(|c| c.is_ascii_alphabetic()) {
+        let unit = s.consume_ident()?;
+        let deg = if unit.eq_ignore_ascii_case("deg") {
+            n
+        } else if unit.eq_ignore_ascii_case("rad") {
+            n * 180.0 / PI
+        } else if unit.eq_ignore_ascii_case("grad") {
+            n * 0.9
+        } else if unit.eq_ignore_ascii_case("turn") {
+            n * 360.0
+        } else {
+            return Err(Error::UnknownToken(start));
+        };
+        return Ok(Component::Angle(deg));
+    }
+
+    Ok(Component::Number(n))
+}
+
+/// Parses the function arguments after `(` up to and including `)`.
+///
+/// Returns three components, an optional alpha and whether the legacy comma syntax is used.
+fn consume_arguments(s: &mut Stream) -> Result<([Component; 3], Option<Component>, bool), Error> {
+    let first = consume_component(s)?;
+    s.skip_spaces();
+
+    let legacy = s.is_char_eq(b',')?;
+    let mut comps = [first; 3];
+    for comp in comps.iter_mut().skip(1) {
+        if legacy {
+            s.skip_spaces();
+            s.consume_char(b',')?;
+        }
+        *comp = consume_component(s)?;
+    }
+    s.skip_spaces();
+
+    let mut alpha = None;
+    let separator = if legacy { b',' } else { b'/' };
+    if s.is_char_eq(separator)? {
+        s.advance_raw(1);
+        alpha = Some(consume_component(s)?);
+        s.skip_spaces();
+    }
+
+    s.consume_char(b')')?;
+    Ok((comps, alpha, legacy))
+}
+
+/// Returns `None` for an angle, which is not a valid alpha.
+fn to_alpha(c: Option<Component>) -> Option<f32> {
+    match c {
+        Some(Component::Number(n)) => Some(n.clamp(0.0, 1.0)),
+        Some(Component::Percentage(n)) => Some((n / 100.0).clamp(0.0, 1.0)),
+        Some(Component::Angle(_)) => None,
+        None => Some(1.0),
+    }
+}
+
+fn to_hue(c: Component) -> Option<f32> {
+    match c {
+        Component::Number(n) | Component::Angle(n) => Some(n.rem_euclid(360.0)),
+        Component::Percentage(_) => None,
+    }
+}
+
+fn to_fraction(c: Component) -> Option<f32> {
+    match c {
+        Component::Number(n) | Component::Percentage(n) => Some((n / 100.0).clamp(0.0, 1.0)),
+        Component::Angle(_) => None,
+    }
+}
+
+fn parse_rgb(s: &mut Stream) -> Result<Color, Error> {
+    let start = s.gen_error_pos();
+    let (comps, alpha, legacy) = consume_arguments(s)?;
+
+    // The legacy syntax doesn't allow mixing numbers and percentages.
+    if legacy {
+        let is_percentage = |c: &Component| matches!(c, Component::Percentage(_));
+        if comps.iter().any(is_percentage) && !comps.iter().all(is_percentage) {
+            return Err(Error::UnknownToken(start));
+        }
+    }
+
+    let mut rgb = [0.0; 3];
+    for (v, c) in rgb.iter_mut().zip(comps.iter()) {
+        *v = match *c {
+            Component::Number(n) => n / 255.0,
+            Component::Percentage(n) => n / 100.0,
+            Component::Angle(_) => return Err(Error::UnknownToken(start)),
+        }.clamp(0.0, 1.0);
+    }
+
+    let alpha = to_alpha(alpha).ok_or(Error::UnknownToken(start))?;
+    Ok(Color::Rgba { red: rgb[0], green: rgb[1], blue: rgb[2], alpha })
+}
+
+fn parse_hsl(s: &mut Stream) -> Result<Color, Error> {
+    let start = s.gen_error_pos();
+    let (comps, alpha, _) = consume_arguments(s)?;
+    let (hue, saturation, lightness) = match (to_hue(comps[0]), to_fraction(comps[1]),
+                                              to_fraction(comps[2])) {
+        (Some(h), Some(s), Some(l)) => (h, s, l),
+        _ => return Err(Error::UnknownToken(start)),
+    };
+
+    let [red, green, blue] = hsl_to_rgb(hue, saturation, lightness);
+    let alpha = to_alpha(alpha).ok_or(Error::UnknownToken(start))?;
+    Ok(Color::Rgba { red, green, blue, alpha })
+}
+
+fn parse_hwb(s: &mut Stream) -> Result<Color, Error> {
+    let start = s.gen_error_pos();
+    let (comps, alpha, _) = consume_arguments(s)?;
+    let (hue, white, black) = match (to_hue(comps[0]), to_fraction(comps[1]),
+                                     to_fraction(comps[2])) {
+        (Some(h), Some(w), Some(b)) => (h, w, b),
+        _ => return Err(Error::UnknownToken(start)),
+    };
+
+    // https://www.w3.org/TR/css-color-4/#hwb-to-rgb
+    let rgb = if white + black >= 1.0 {
+        let gray = white / (white + black);
+        [gray; 3]
+    } else {
+        let mut rgb = hsl_to_rgb(hue, 1.0, 0.5);
+        for v in rgb.iter_mut() {
+            *v = *v * (1.0 - white - black) + white;
+        }
+        rgb
+    };
+
+    let alpha = to_alpha(alpha).ok_or(Error::UnknownToken(start))?;
+    Ok(Color::Rgba { red: rgb[0], green: rgb[1], blue: rgb[2], alpha })
+}
+
+// https://www.w3.org/TR/css-color-4/#hsl-to-rgb
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> [f32; 3] {
+    let f = |n: f32| {
+        let k = (n + hue / 30.0) % 12.0;
+        let a = saturation * lightness.min(1.0 - lightness);
+        lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+
+    [f(0.0), f(8.0), f(4.0)]
+}
+
+fn from_name(name: &str) -> Option<Color> {
+    let name = name.to_ascii_lowercase();
+    let idx = NAMED_COLORS.binary_search_by(|(n, _)| n.cmp(&name.as_str())).ok()?;
+    let [r, g, b] = NAMED_COLORS[idx].1;
+    Some(Color::from_rgb8(r, g, b))
+}
+
+// https://www.w3.org/TR/css-color-4/#named-colors
+// Must be kept sorted.
+static NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
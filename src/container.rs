@@ -0,0 +1,484 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::CalcContext;
+use crate::error::Error;
+use crate::media::{self, DeviceValue, MediaCondition, MediaFeature};
+use crate::stream::Stream;
+use crate::value;
+use crate::var::CustomProperties;
+
+/// A parsed `@container` prelude, like `sidebar (min-width: 400px)`.
+///
+/// https://www.w3.org/TR/css-contain-3/#container-rule
+#[derive(Clone,PartialEq,Debug)]
+pub struct ContainerQuery<'a> {
+    /// Container name, if any.
+    pub name: Option<&'a str>,
+    /// Container condition.
+    pub condition: ContainerCondition<'a>,
+}
+
+/// Container condition.
+///
+/// https://www.w3.org/TR/css-contain-3/#typedef-container-condition
+#[derive(Clone,PartialEq,Debug)]
+pub enum ContainerCondition<'a> {
+    /// A size feature in parentheses, like `(width > 400px)`.
+    Feature(MediaFeature<'a>),
+    /// `style(...)`
+    Style(StyleQuery<'a>),
+    /// `not <condition>`
+    Not(Box<ContainerCondition<'a>>),
+    /// `<condition> and <condition> ...`
+    And(Vec<ContainerCondition<'a>>),
+    /// `<condition> or <condition> ...`
+    Or(Vec<ContainerCondition<'a>>),
+    /// Unknown content in parentheses or a function.
+    ///
+    /// Always evaluates to "unknown". Contains the raw text, including the parentheses.
+    Unknown(&'a str),
+}
+
+/// A style query: the content of `style(...)`.
+///
+/// https://www.w3.org/TR/css-contain-3/#typedef-style-query
+#[derive(Clone,PartialEq,Debug)]
+pub enum StyleQuery<'a> {
+    /// A declaration, like `--theme: dark`. The value is trimmed.
+    Declaration(&'a str, &'a str),
+    /// A property name without a value, like `--theme`.
+    ///
+    /// Matches if the property has a value.
+    Property(&'a str),
+    /// `not <query>`
+    Not(Box<StyleQuery<'a>>),
+    /// `<query> and <query> ...`
+    And(Vec<StyleQuery<'a>>),
+    /// `<query> or <query> ...`
+    Or(Vec<StyleQuery<'a>>),
+    /// Unknown content in parentheses. Contains the raw text, including the parentheses.
+    Unknown(&'a str),
+}
+
+/// `container-type` value.
+///
+/// Ordered by the supported queries: a `Size` container supports all queries.
+#[derive(Clone,Copy,PartialEq,PartialOrd,Debug)]
+pub enum ContainerType {
+    /// `normal`. Supports only style queries.
+    Normal,
+    /// `inline-size`. Supports inline axis size queries.
+    InlineSize,
+    /// `size`. Supports size queries in both axes.
+    Size,
+}
+
+/// A query container, used to evaluate container queries.
+///
+/// Only the horizontal writing mode is supported, so the inline size is the width.
+/// Lengths are in CSS pixels.
+#[derive(Clone,Copy,Debug)]
+pub struct Container<'c> {
+    /// Container type.
+    pub container_type: ContainerType,
+    /// Content box width.
+    pub width: f32,
+    /// Content box height.
+    pub height: f32,
+    /// Font size of the container, used to resolve `em`.
+    pub font_size: f32,
+    /// Font size of the root element, used to resolve `rem`.
+    pub root_font_size: f32,
+    /// Viewport width, used to resolve `vw`.
+    pub viewport_width: f32,
+    /// Viewport height, used to resolve `vh`.
+    pub viewport_height: f32,
+    /// Computed custom properties of the container, used by style queries.
+    ///
+    /// If `None`, style queries evaluate to "unknown".
+    pub custom_properties: Option<&'c CustomProperties>,
+}
+
+impl<'c> Default for Container<'c> {
+    fn default() -> Container<'c> {
+        Container {
+            container_type: ContainerType::Normal,
+            width: 0.0,
+            height: 0.0,
+            font_size: 16.0,
+            root_font_size: 16.0,
+            viewport_width: 0.0,
+            viewport_height: 0.0,
+            custom_properties: None,
+        }
+    }
+}
+
+impl ContainerType {
+    /// Parses a `container-type` value, ASCII case-insensitive.
+    pub fn from_name(name: &str) -> Option<ContainerType> {
+        let list = [
+            ("normal", ContainerType::Normal),
+            ("inline-size", ContainerType::InlineSize),
+            ("size", ContainerType::Size),
+        ];
+
+        list.iter().find(|(n, _)| name.eq_ignore_ascii_case(n)).map(|(_, t)| *t)
+    }
+}
+
+/// Parses a `container-name` value into a list of names.
+///
+/// Returns an empty list for `none`, and `None` on an invalid value.
+pub fn parse_container_names(text: &str) -> Option<Vec<&str>> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+
+    let names: Vec<&str> = text.split_ascii_whitespace().collect();
+    if names.is_empty() || !names.iter().all(|n| is_container_name(n)) {
+        return None;
+    }
+
+    Some(names)
+}
+
+fn is_container_name(name: &str) -> bool {
+    value::is_custom_ident(name) && !value::is_css_wide_keyword(name)
+        && !value::is_keyword(name, &["none", "and", "or", "not", "default"])
+}
+
+impl<'a> ContainerQuery<'a> {
+    /// Parses a `@container` prelude.
+    ///
+    /// Use `Tokenizer::parse_at_rule_prelude` right after `Token::AtRule("container")`
+    /// to get the prelude.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<ContainerQuery<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+
+        let mut name = None;
+        let name_pos = s.gen_error_pos();
+        let mut tmp = s;
+        if let Ok(ident) = tmp.consume_ident() {
+            let is_function = tmp.curr_char() == Ok(b'(');
+            if !is_function && !ident.eq_ignore_ascii_case("not") {
+                if !is_container_name(ident) {
+                    return Err(Error::InvalidValue(name_pos));
+                }
+
+                name = Some(ident);
+                s = tmp;
+                s.skip_spaces();
+            }
+        }
+
+        let condition = media::parse_condition(&mut s, true)?;
+        s.skip_spaces();
+        if !s.at_end() {
+            return Err(Error::UnknownToken(s.gen_error_pos()));
+        }
+
+        Ok(ContainerQuery {
+            name,
+            condition: ContainerCondition::from_media(condition),
+        })
+    }
+
+    /// Returns the container type required to evaluate the query.
+    pub fn required_type(&self) -> ContainerType {
+        self.condition.required_type()
+    }
+
+    /// Checks that the query matches.
+    ///
+    /// `lookup` is called with the container name and the required container type
+    /// and should return the nearest ancestor container with that name, if set,
+    /// and a type that is greater than or equal to the required one.
+    ///
+    /// Returns false if there is no such container. Unknown features and
+    /// `ContainerCondition::Unknown` evaluate to "unknown", which is treated as false.
+    pub fn matches<'c, F>(&self, lookup: F) -> bool
+        where F: Fn(Option<&str>, ContainerType) -> Option<Container<'c>>
+    {
+        match lookup(self.name, self.required_type()) {
+            Some(container) => self.condition.eval(&container) == Some(true),
+            None => false,
+        }
+    }
+}
+
+impl<'a> ContainerCondition<'a> {
+    fn from_media(condition: MediaCondition<'a>) -> ContainerCondition<'a> {
+        match condition {
+            MediaCondition::Feature(f) => ContainerCondition::Feature(f),
+            MediaCondition::Not(c) => ContainerCondition::Not(Box::new(Self::from_media(*c))),
+            MediaCondition::And(list) => {
+                ContainerCondition::And(list.into_iter().map(Self::from_media).collect())
+            }
+            MediaCondition::Or(list) => {
+                ContainerCondition::Or(list.into_iter().map(Self::from_media).collect())
+            }
+            MediaCondition::Unknown(text) => Self::from_unknown(text),
+        }
+    }
+
+    /// Parses `style()` and nested conditions that are not valid media conditions.
+    fn from_unknown(text: &'a str) -> ContainerCondition<'a> {
+        let mut s = Stream::new(text.as_bytes());
+        if s.try_consume_keyword("style") {
+            let args = &text[s.pos() + 1..text.len() - 1];
+            return match StyleQuery::parse(args) {
+                Ok(query) => ContainerCondition::Style(query),
+                Err(_) => ContainerCondition::Unknown(text),
+            };
+        }
+
+        if text.starts_with('(') {
+            let mut s = Stream::new(&text.as_bytes()[1..text.len() - 1]);
+            if let Ok(condition) = media::parse_condition(&mut s, true) {
+                s.skip_spaces();
+                if s.at_end() {
+                    return Self::from_media(condition);
+                }
+            }
+        }
+
+        ContainerCondition::Unknown(text)
+    }
+
+    /// Returns the container type required to evaluate the condition.
+    pub fn required_type(&self) -> ContainerType {
+        match *self {
+            ContainerCondition::Feature(ref f) => {
+                let name = match *f {
+                    MediaFeature::Boolean(name) => name,
+                    MediaFeature::Plain { name, .. } => name,
+                    MediaFeature::Range { name, .. } => name,
+                };
+
+                if value::is_keyword(name, &["width", "inline-size"]) {
+                    ContainerType::InlineSize
+                } else if value::is_keyword(name, &["height", "block-size", "aspect-ratio",
+                                                    "orientation"]) {
+                    ContainerType::Size
+                } else {
+                    ContainerType::Normal
+                }
+            }
+            ContainerCondition::Not(ref c) => c.required_type(),
+            ContainerCondition::And(ref list) | ContainerCondition::Or(ref list) => {
+                let mut t = ContainerType::Normal;
+                for c in list {
+                    let c = c.required_type();
+                    if c > t {
+                        t = c;
+                    }
+                }
+                t
+            }
+            ContainerCondition::Style(_) | ContainerCondition::Unknown(_) => ContainerType::Normal,
+        }
+    }
+
+    /// Evaluates the condition using three-valued logic.
+    ///
+    /// Returns `None` for "unknown", including size features that are not supported
+    /// by the container type.
+    pub fn eval(&self, container: &Container) -> Option<bool> {
+        match *self {
+            ContainerCondition::Feature(ref f) => {
+                let ctx = CalcContext {
+                    percentage_basis: None,
+                    font_size: container.font_size,
+                    root_font_size: container.root_font_size,
+                    viewport_width: container.viewport_width,
+                    viewport_height: container.viewport_height,
+                };
+
+                f.eval_with(|name| container_value(container, name), &ctx)
+            }
+            ContainerCondition::Style(ref q) => q.eval(container.custom_properties?),
+            ContainerCondition::Not(ref c) => c.eval(container).map(|v| !v),
+            ContainerCondition::And(ref list) => {
+                let mut result = Some(true);
+                for c in list {
+                    match c.eval(container) {
+                        Some(false) => return Some(false),
+                        None => result = None,
+                        Some(true) => {}
+                    }
+                }
+                result
+            }
+            ContainerCondition::Or(ref list) => {
+                let mut result = Some(false);
+                for c in list {
+                    match c.eval(container) {
+                        Some(true) => return Some(true),
+                        None => result = None,
+                        Some(false) => {}
+                    }
+                }
+                result
+            }
+            ContainerCondition::Unknown(_) => None,
+        }
+    }
+}
+
+fn container_value(container: &Container, name: &str) -> Option<DeviceValue> {
+    let name = name.to_ascii_lowercase();
+    let has_inline = container.container_type >= ContainerType::InlineSize;
+    let has_block = container.container_type == ContainerType::Size;
+
+    let v = match name.as_str() {
+        "width" | "inline-size" if has_inline => DeviceValue::Length(container.width),
+        "height" | "block-size" if has_block => DeviceValue::Length(container.height),
+        "aspect-ratio" if has_block => {
+            if container.height == 0.0 {
+                return None;
+            }
+
+            DeviceValue::Ratio(container.width / container.height)
+        }
+        "orientation" if has_block => {
+            let v = if container.height >= container.width { "portrait" } else { "landscape" };
+            DeviceValue::Ident(v, true)
+        }
+        _ => return None,
+    };
+
+    Some(v)
+}
+
+impl<'a> StyleQuery<'a> {
+    /// Parses the content of `style()`, like `--theme: dark`.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<StyleQuery<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+
+        if s.try_consume_keyword("not") {
+            s.skip_spaces();
+            let query = parse_style_in_parens(&mut s)?;
+            s.skip_spaces();
+            if !s.at_end() {
+                return Err(Error::UnknownToken(s.gen_error_pos()));
+            }
+
+            return Ok(StyleQuery::Not(Box::new(query)));
+        }
+
+        if s.curr_char()? != b'(' {
+            // A single declaration or a property name.
+            let name = s.consume_ident()?;
+            s.skip_spaces();
+            if s.at_end() {
+                return Ok(StyleQuery::Property(name));
+            }
+
+            s.consume_char(b':')?;
+            let value = text[s.pos()..].trim();
+            if value.is_empty() {
+                return Err(Error::UnexpectedEndOfStream(s.gen_error_pos()));
+            }
+
+            return Ok(StyleQuery::Declaration(name, value));
+        }
+
+        let mut list = vec![parse_style_in_parens(&mut s)?];
+        let mut is_or = None;
+        loop {
+            s.skip_spaces();
+            if s.at_end() {
+                break;
+            }
+
+            let op_pos = s.gen_error_pos();
+            let next_is_or = if s.try_consume_keyword("and") {
+                false
+            } else if s.try_consume_keyword("or") {
+                true
+            } else {
+                return Err(Error::UnknownToken(op_pos));
+            };
+
+            // `and` and `or` can't be mixed without parentheses.
+            if is_or.is_some() && is_or != Some(next_is_or) {
+                return Err(Error::UnknownToken(op_pos));
+            }
+            is_or = Some(next_is_or);
+
+            s.skip_spaces();
+            list.push(parse_style_in_parens(&mut s)?);
+        }
+
+        Ok(match is_or {
+            None => list.pop().unwrap(),
+            Some(false) => StyleQuery::And(list),
+            Some(true) => StyleQuery::Or(list),
+        })
+    }
+
+    /// Evaluates the query against the container's custom properties.
+    ///
+    /// Only custom properties can be queried. Values are compared as written,
+    /// ignoring leading and trailing spaces. Returns `None` for "unknown".
+    pub fn eval(&self, properties: &CustomProperties) -> Option<bool> {
+        match *self {
+            StyleQuery::Declaration(name, value) => {
+                if !name.starts_with("--") {
+                    return None;
+                }
+
+                Some(properties.get(name).map(str::trim) == Some(value))
+            }
+            StyleQuery::Property(name) => {
+                if !name.starts_with("--") {
+                    return None;
+                }
+
+                Some(properties.get(name).is_some())
+            }
+            StyleQuery::Not(ref q) => q.eval(properties).map(|v| !v),
+            StyleQuery::And(ref list) => {
+                let mut result = Some(true);
+                for q in list {
+                    match q.eval(properties) {
+                        Some(false) => return Some(false),
+                        None => result = None,
+                        Some(true) => {}
+                    }
+                }
+                result
+            }
+            StyleQuery::Or(ref list) => {
+                let mut result = Some(false);
+                for q in list {
+                    match q.eval(properties) {
+                        Some(true) => return Some(true),
+                        None => result = None,
+                        Some(false) => {}
+                    }
+                }
+                result
+            }
+            StyleQuery::Unknown(_) => None,
+        }
+    }
+}
+
+fn parse_style_in_parens<'a>(s: &mut Stream<'a>) -> Result<StyleQuery<'a>, Error> {
+    let block = s.consume_parenthesized()?;
+    match StyleQuery::parse(&block[1..block.len() - 1]) {
+        Ok(query) => Ok(query),
+        Err(_) => Ok(StyleQuery::Unknown(block)),
+    }
+}
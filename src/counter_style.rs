@@ -0,0 +1,296 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::descriptor::DescriptorBlock;
+use crate::error::Error;
+use crate::tokenizer::Tokenizer;
+use crate::value;
+
+/// A parsed `@counter-style` rule.
+///
+/// Invalid `system`, `symbols`, `additive-symbols` and `range` values are ignored,
+/// as required by the spec.
+///
+/// https://www.w3.org/TR/css-counter-styles-3/#the-counter-style-rule
+#[derive(Clone,PartialEq,Debug)]
+pub struct CounterStyle<'a> {
+    /// Counter style name.
+    pub name: &'a str,
+    /// `system`. Defaults to `symbolic`.
+    pub system: CounterSystem<'a>,
+    /// `symbols`.
+    pub symbols: Vec<CounterSymbol<'a>>,
+    /// `additive-symbols` as weight and symbol pairs, in descending weight order.
+    pub additive_symbols: Vec<(u32, CounterSymbol<'a>)>,
+    /// `range`.
+    pub range: CounterRange,
+    /// Other descriptors, like `prefix` or `pad`, in the source order.
+    pub descriptors: Vec<(&'a str, &'a str)>,
+}
+
+/// `system` descriptor.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum CounterSystem<'a> {
+    #[allow(missing_docs)]
+    Cyclic,
+    #[allow(missing_docs)]
+    Numeric,
+    #[allow(missing_docs)]
+    Alphabetic,
+    #[allow(missing_docs)]
+    Symbolic,
+    #[allow(missing_docs)]
+    Additive,
+    /// `fixed` with the first symbol value. `fixed` without a value is `1`.
+    Fixed(i32),
+    /// `extends` with a counter style name.
+    Extends(&'a str),
+}
+
+/// A counter symbol.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum CounterSymbol<'a> {
+    /// A string without quotes.
+    String(&'a str),
+    /// An ident.
+    Ident(&'a str),
+    /// An image, like `url(a.png)`.
+    Image(&'a str),
+}
+
+/// `range` descriptor.
+#[derive(Clone,PartialEq,Debug)]
+pub enum CounterRange {
+    /// Depends on the system.
+    Auto,
+    /// Inclusive ranges. `None` is `infinite`, which is negative infinity for
+    /// a lower bound and positive infinity for an upper bound.
+    Ranges(Vec<(Option<i32>, Option<i32>)>),
+}
+
+impl<'a> CounterStyle<'a> {
+    /// Parses a `@counter-style` rule.
+    ///
+    /// Should be called right after `Token::AtRule("counter-style")`.
+    /// Consumes everything up to and including the rule's `Token::BlockEnd`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` pointing at the name if the name is invalid
+    /// or the symbols don't fit the system, like `alphabetic` with a single symbol.
+    pub fn parse(tokenizer: &mut Tokenizer<'a>) -> Result<CounterStyle<'a>, Error> {
+        let block = DescriptorBlock::parse(tokenizer, "counter-style")?;
+        let name = block.prelude;
+        let name_pos = tokenizer.error_pos_of(name);
+
+        if !is_counter_style_name(name) || value::is_keyword(name, &[
+            "decimal", "disc", "square", "circle", "disclosure-open", "disclosure-closed",
+        ]) {
+            return Err(Error::InvalidValue(name_pos));
+        }
+
+        let mut style = CounterStyle {
+            name,
+            system: CounterSystem::Symbolic,
+            symbols: Vec::new(),
+            additive_symbols: Vec::new(),
+            range: CounterRange::Auto,
+            descriptors: Vec::new(),
+        };
+
+        for &(name, value) in &block.descriptors {
+            match name.to_ascii_lowercase().as_str() {
+                "system" => {
+                    if let Some(system) = CounterSystem::parse(value) {
+                        style.system = system;
+                    }
+                }
+                "symbols" => {
+                    if let Some(symbols) = parse_symbols(value) {
+                        style.symbols = symbols;
+                    }
+                }
+                "additive-symbols" => {
+                    if let Some(symbols) = parse_additive_symbols(value) {
+                        style.additive_symbols = symbols;
+                    }
+                }
+                "range" => {
+                    if let Some(range) = CounterRange::parse(value) {
+                        style.range = range;
+                    }
+                }
+                _ => style.descriptors.push((name, value)),
+            }
+        }
+
+        let is_valid = match style.system {
+            CounterSystem::Cyclic | CounterSystem::Fixed(_) | CounterSystem::Symbolic => {
+                !style.symbols.is_empty()
+            }
+            CounterSystem::Alphabetic | CounterSystem::Numeric => style.symbols.len() >= 2,
+            CounterSystem::Additive => !style.additive_symbols.is_empty(),
+            CounterSystem::Extends(_) => {
+                block.descriptor("symbols").is_none() && block.descriptor("additive-symbols").is_none()
+            }
+        };
+
+        if !is_valid {
+            return Err(Error::InvalidValue(name_pos));
+        }
+
+        Ok(style)
+    }
+
+    /// Checks that a counter value is in the counter style range.
+    ///
+    /// `auto` depends on the system. The range of `extends` is unknown without
+    /// the extended style, so it's treated as infinite.
+    pub fn in_range(&self, counter: i32) -> bool {
+        match self.range {
+            CounterRange::Auto => {
+                match self.system {
+                    CounterSystem::Alphabetic | CounterSystem::Symbolic => counter >= 1,
+                    CounterSystem::Additive => counter >= 0,
+                    _ => true,
+                }
+            }
+            CounterRange::Ranges(ref ranges) => {
+                ranges.iter().any(|&(lower, upper)| {
+                    lower.map_or(true, |n| counter >= n) && upper.map_or(true, |n| counter <= n)
+                })
+            }
+        }
+    }
+}
+
+impl<'a> CounterSystem<'a> {
+    /// Parses a `system` descriptor value.
+    pub fn parse(text: &'a str) -> Option<CounterSystem<'a>> {
+        let parts = value::split_components(text);
+        let system = match parts.as_slice() {
+            [name] => {
+                match name.to_ascii_lowercase().as_str() {
+                    "cyclic" => CounterSystem::Cyclic,
+                    "numeric" => CounterSystem::Numeric,
+                    "alphabetic" => CounterSystem::Alphabetic,
+                    "symbolic" => CounterSystem::Symbolic,
+                    "additive" => CounterSystem::Additive,
+                    "fixed" => CounterSystem::Fixed(1),
+                    _ => return None,
+                }
+            }
+            [name, n] if name.eq_ignore_ascii_case("fixed") => CounterSystem::Fixed(n.parse().ok()?),
+            [name, style] if name.eq_ignore_ascii_case("extends") && is_counter_style_name(style) => {
+                CounterSystem::Extends(style)
+            }
+            _ => return None,
+        };
+
+        Some(system)
+    }
+}
+
+impl<'a> CounterSymbol<'a> {
+    /// Parses a single symbol: a string, an ident or an image.
+    pub fn parse(text: &'a str) -> Option<CounterSymbol<'a>> {
+        if let Some(s) = value::unquote(text) {
+            return Some(CounterSymbol::String(s));
+        }
+
+        if value::is_image(text) && !text.eq_ignore_ascii_case("none") {
+            return Some(CounterSymbol::Image(text));
+        }
+
+        if value::is_custom_ident(text) {
+            return Some(CounterSymbol::Ident(text));
+        }
+
+        None
+    }
+}
+
+impl CounterRange {
+    /// Parses a `range` descriptor value.
+    ///
+    /// Returns `None` if a lower bound is greater than an upper bound.
+    pub fn parse(text: &str) -> Option<CounterRange> {
+        if text.trim().eq_ignore_ascii_case("auto") {
+            return Some(CounterRange::Auto);
+        }
+
+        let mut ranges = Vec::new();
+        for range in value::split_list(text) {
+            let bound = |text: &str| -> Option<Option<i32>> {
+                if text.eq_ignore_ascii_case("infinite") {
+                    Some(None)
+                } else {
+                    text.parse().ok().map(Some)
+                }
+            };
+
+            let (lower, upper) = match value::split_components(range).as_slice() {
+                [lower, upper] => (bound(lower)?, bound(upper)?),
+                _ => return None,
+            };
+
+            if let (Some(lower), Some(upper)) = (lower, upper) {
+                if lower > upper {
+                    return None;
+                }
+            }
+
+            ranges.push((lower, upper));
+        }
+
+        if ranges.is_empty() {
+            return None;
+        }
+
+        Some(CounterRange::Ranges(ranges))
+    }
+}
+
+/// Parses a `symbols` descriptor value.
+fn parse_symbols(text: &str) -> Option<Vec<CounterSymbol<'_>>> {
+    let symbols: Option<Vec<_>> = value::split_components(text).into_iter()
+        .map(CounterSymbol::parse)
+        .collect();
+    symbols.filter(|s| !s.is_empty())
+}
+
+/// Parses an `additive-symbols` descriptor value.
+///
+/// Weights must be in descending order.
+fn parse_additive_symbols(text: &str) -> Option<Vec<(u32, CounterSymbol<'_>)>> {
+    let mut symbols: Vec<(u32, CounterSymbol)> = Vec::new();
+    for pair in value::split_list(text) {
+        let (weight, symbol) = match value::split_components(pair).as_slice() {
+            [a, b] => {
+                match a.parse() {
+                    Ok(weight) => (weight, CounterSymbol::parse(b)?),
+                    Err(_) => (b.parse().ok()?, CounterSymbol::parse(a)?),
+                }
+            }
+            _ => return None,
+        };
+
+        if symbols.last().is_some_and(|prev| prev.0 <= weight) {
+            return None;
+        }
+
+        symbols.push((weight, symbol));
+    }
+
+    if symbols.is_empty() {
+        return None;
+    }
+
+    Some(symbols)
+}
+
+fn is_counter_style_name(name: &str) -> bool {
+    value::is_custom_ident(name) && !name.eq_ignore_ascii_case("none")
+        && !value::is_css_wide_keyword(name)
+}
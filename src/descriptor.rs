@@ -0,0 +1,102 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::tokenizer::{Tokenizer, Token};
+use crate::value;
+
+/// An at-rule with a block of descriptors, like `@counter-style`,
+/// `@font-feature-values`, `@font-palette-values` or `@view-transition`.
+///
+/// Nested at-rules with blocks, like `@styleset` inside `@font-feature-values`,
+/// are stored as nested descriptor blocks. Style rules are not allowed inside
+/// descriptor blocks, so they are skipped.
+#[derive(Clone,PartialEq,Debug)]
+pub struct DescriptorBlock<'a> {
+    /// At-rule name without `@`.
+    pub name: &'a str,
+    /// At-rule prelude, like `thumbs` in `@counter-style thumbs`. Can be empty.
+    pub prelude: &'a str,
+    /// Descriptors in the source order.
+    pub descriptors: Vec<(&'a str, &'a str)>,
+    /// Nested descriptor blocks in the source order.
+    pub blocks: Vec<DescriptorBlock<'a>>,
+}
+
+impl<'a> DescriptorBlock<'a> {
+    /// Parses a descriptor block at-rule.
+    ///
+    /// Should be called right after `Token::AtRule` with the rule name.
+    /// Consumes everything up to and including the rule's `Token::BlockEnd`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownToken` if the at-rule has no block.
+    pub fn parse(tokenizer: &mut Tokenizer<'a>, at_rule: &'a str) -> Result<DescriptorBlock<'a>, Error> {
+        let prelude = tokenizer.parse_at_rule_prelude()?;
+        if tokenizer.parse_next()? != Token::BlockStart {
+            return Err(Error::UnknownToken(tokenizer.error_pos_of(prelude)));
+        }
+
+        parse_body(tokenizer, at_rule, prelude)
+    }
+
+    /// Returns the value of a descriptor. ASCII case-insensitive.
+    ///
+    /// If the descriptor is set multiple times, the last one wins.
+    pub fn descriptor(&self, name: &str) -> Option<&'a str> {
+        self.descriptors.iter().rev().find(|d| d.0.eq_ignore_ascii_case(name)).map(|d| d.1)
+    }
+
+    /// Returns nested blocks with the selected at-rule name. ASCII case-insensitive.
+    pub fn blocks_named<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'b DescriptorBlock<'a>> {
+        self.blocks.iter().filter(move |b| b.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Checks that the at-rule block contains descriptors and not style rules or properties.
+pub fn is_descriptor_at_rule(name: &str) -> bool {
+    value::is_keyword(name, &[
+        "font-face", "counter-style", "font-feature-values", "font-palette-values",
+        "view-transition", "property",
+        // Feature value blocks inside `@font-feature-values`.
+        "stylistic", "historical-forms", "styleset", "character-variant", "swash", "ornaments",
+        "annotation",
+    ])
+}
+
+/// Parses descriptors up to and including `Token::BlockEnd`.
+fn parse_body<'a>(
+    tokenizer: &mut Tokenizer<'a>,
+    name: &'a str,
+    prelude: &'a str,
+) -> Result<DescriptorBlock<'a>, Error> {
+    let mut descriptors = Vec::new();
+    let mut blocks = Vec::new();
+
+    loop {
+        match tokenizer.parse_next()? {
+            Token::Declaration(name, value) | Token::CustomProperty(name, value) => {
+                descriptors.push((name, value));
+            }
+            Token::AtRule(name) => {
+                let prelude = tokenizer.parse_at_rule_prelude()?;
+                if tokenizer.parse_next()? == Token::BlockStart {
+                    blocks.push(parse_body(tokenizer, name, prelude)?);
+                }
+            }
+            // A style rule.
+            Token::BlockStart => tokenizer.skip_block()?,
+            Token::BlockEnd | Token::EndOfStream => break,
+            _ => {}
+        }
+    }
+
+    Ok(DescriptorBlock {
+        name,
+        prelude,
+        descriptors,
+        blocks,
+    })
+}
@@ -19,8 +19,8 @@ impl ErrorPos {
     /// Constructs a new error position.
     pub fn new(row: usize, col: usize) -> ErrorPos {
         ErrorPos {
-            row: row,
-            col: col,
+            row,
+            col,
         }
     }
 }
@@ -51,6 +51,39 @@ pub enum Error {
     UnsupportedToken(ErrorPos),
     /// Unknown token.
     UnknownToken(ErrorPos),
+    /// Syntactically valid, but semantically invalid value.
+    ///
+    /// For example, a `calc()` expression that mixes incompatible units.
+    InvalidValue(ErrorPos),
+    /// Unknown property name.
+    ///
+    /// Only returned by `Validator` in `ValidationMode::Strict`.
+    UnknownProperty(ErrorPos),
+}
+
+impl Error {
+    /// Converts a position relative to a substring that starts at `base`
+    /// into an absolute one.
+    pub(crate) fn offset_by(self, base: ErrorPos) -> Error {
+        let shift = |pos: ErrorPos| {
+            if pos.row == 1 {
+                ErrorPos::new(base.row, base.col + pos.col - 1)
+            } else {
+                ErrorPos::new(base.row + pos.row - 1, pos.col)
+            }
+        };
+
+        match self {
+            Error::UnexpectedEndOfStream(pos) => Error::UnexpectedEndOfStream(shift(pos)),
+            Error::InvalidAdvance { expected, total, pos } => {
+                Error::InvalidAdvance { expected, total, pos: shift(pos) }
+            }
+            Error::UnsupportedToken(pos) => Error::UnsupportedToken(shift(pos)),
+            Error::UnknownToken(pos) => Error::UnknownToken(shift(pos)),
+            Error::InvalidValue(pos) => Error::InvalidValue(shift(pos)),
+            Error::UnknownProperty(pos) => Error::UnknownProperty(shift(pos)),
+        }
+    }
 }
 
 impl fmt::Debug for Error {
@@ -66,6 +99,10 @@ impl fmt::Debug for Error {
                 write!(f, "Unsupported token at {:?}", pos),
             Error::UnknownToken(ref pos) =>
                 write!(f, "Unknown token at {:?}", pos),
+            Error::InvalidValue(ref pos) =>
+                write!(f, "Invalid value at {:?}", pos),
+            Error::UnknownProperty(ref pos) =>
+                write!(f, "Unknown property at {:?}", pos),
         }
     }
 }
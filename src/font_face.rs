@@ -0,0 +1,410 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::{CalcNode, CalcValue, CalcContext};
+use crate::error::Error;
+use crate::tokenizer::{Tokenizer, Token};
+use crate::value;
+
+/// A parsed `@font-face` rule.
+///
+/// Unknown and invalid descriptors are ignored, as required by the spec.
+///
+/// https://www.w3.org/TR/css-fonts-4/#font-face-rule
+#[derive(Clone,PartialEq,Debug)]
+pub struct FontFace<'a> {
+    /// `font-family`, without quotes.
+    pub family: Option<String>,
+    /// `src`. Only valid entries are stored.
+    pub sources: Vec<FontSource<'a>>,
+    /// `font-weight` range. `None` for `auto`.
+    pub weight: Option<(f32, f32)>,
+    /// `font-stretch` range in percents. `None` for `auto`.
+    pub stretch: Option<(f32, f32)>,
+    /// `font-style`. `None` for `auto`.
+    pub style: Option<FontStyle>,
+    /// `font-display`.
+    pub display: FontDisplay,
+    /// `unicode-range` as inclusive code point ranges.
+    ///
+    /// Empty if the descriptor is not set, which means the whole Unicode range.
+    pub unicode_range: Vec<(u32, u32)>,
+}
+
+/// An entry of the `src` descriptor.
+#[derive(Clone,PartialEq,Debug)]
+pub enum FontSource<'a> {
+    /// `url()` with optional `format()` and `tech()`.
+    Url {
+        /// URL without quotes.
+        url: &'a str,
+        /// Format name without quotes, like `woff2`.
+        format: Option<&'a str>,
+        /// Font technologies, like `variations` or `color-COLRv1`.
+        tech: Vec<&'a str>,
+    },
+    /// `local()` with a font name without quotes.
+    Local(String),
+}
+
+/// `font-style` descriptor.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum FontStyle {
+    #[allow(missing_docs)]
+    Normal,
+    #[allow(missing_docs)]
+    Italic,
+    /// Angle range in degrees. `oblique` without angles is `14deg`.
+    Oblique(f32, f32),
+}
+
+/// `font-display` descriptor.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum FontDisplay {
+    #[allow(missing_docs)]
+    Auto,
+    #[allow(missing_docs)]
+    Block,
+    #[allow(missing_docs)]
+    Swap,
+    #[allow(missing_docs)]
+    Fallback,
+    #[allow(missing_docs)]
+    Optional,
+}
+
+impl<'a> Default for FontFace<'a> {
+    fn default() -> FontFace<'a> {
+        FontFace {
+            family: None,
+            sources: Vec::new(),
+            weight: None,
+            stretch: None,
+            style: None,
+            display: FontDisplay::Auto,
+            unicode_range: Vec::new(),
+        }
+    }
+}
+
+impl<'a> FontFace<'a> {
+    /// Parses a `@font-face` rule.
+    ///
+    /// Should be called right after `Token::AtRule("font-face")`.
+    /// Consumes everything up to and including the rule's `Token::BlockEnd`.
+    pub fn parse(tokenizer: &mut Tokenizer<'a>) -> Result<FontFace<'a>, Error> {
+        let prelude = tokenizer.parse_at_rule_prelude()?;
+        if !prelude.is_empty() || tokenizer.parse_next()? != Token::BlockStart {
+            return Err(Error::UnknownToken(tokenizer.error_pos_of(prelude)));
+        }
+
+        let mut face = FontFace::default();
+        let mut depth = 1;
+        while depth > 0 {
+            match tokenizer.parse_next()? {
+                Token::Declaration(name, value) if depth == 1 => {
+                    face.set_descriptor(name, value);
+                }
+                Token::BlockStart => depth += 1,
+                Token::BlockEnd => depth -= 1,
+                Token::EndOfStream => break,
+                _ => {}
+            }
+        }
+
+        Ok(face)
+    }
+
+    /// Parses and sets a descriptor.
+    ///
+    /// Returns `false` if the descriptor is unknown or the value is invalid.
+    /// In this case, the previous value is kept.
+    pub fn set_descriptor(&mut self, name: &str, value: &'a str) -> bool {
+        let name = name.to_ascii_lowercase();
+        match name.as_str() {
+            "font-family" => set(&mut self.family, parse_family(value)),
+            "src" => {
+                let sources: Vec<_> = value::split_list(value).into_iter()
+                    .filter_map(parse_source)
+                    .collect();
+                if sources.is_empty() {
+                    return false;
+                }
+                self.sources = sources;
+                true
+            }
+            "font-weight" => set(&mut self.weight, parse_range(value, parse_weight)),
+            "font-stretch" => set(&mut self.stretch, parse_range(value, parse_stretch)),
+            "font-style" => set(&mut self.style, parse_style(value)),
+            "font-display" => {
+                match parse_display(value) {
+                    Some(display) => {
+                        self.display = display;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            "unicode-range" => {
+                let ranges: Option<Vec<_>> = value::split_list(value).into_iter()
+                    .map(parse_unicode_range)
+                    .collect();
+                match ranges {
+                    Some(ranges) => {
+                        self.unicode_range = ranges;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Sets a descriptor that accepts `auto`, which resets it to `None`.
+fn set<T>(field: &mut Option<T>, value: Option<Option<T>>) -> bool {
+    match value {
+        Some(v) => {
+            *field = v;
+            true
+        }
+        None => false,
+    }
+}
+
+fn parse_family(text: &str) -> Option<Option<String>> {
+    let text = text.trim();
+    if let Some(name) = value::unquote(text) {
+        return Some(Some(name.to_string()));
+    }
+
+    // A sequence of idents. Generic families are not allowed.
+    let parts = value::split_components(text);
+    let is_valid = !parts.is_empty()
+        && parts.iter().all(|p| value::is_custom_ident(p) && !value::is_css_wide_keyword(p))
+        && !(parts.len() == 1 && value::is_keyword(parts[0], &[
+            "serif", "sans-serif", "cursive", "fantasy", "monospace", "system-ui", "math",
+            "emoji", "fangsong", "ui-serif", "ui-sans-serif", "ui-monospace", "ui-rounded",
+            "default",
+        ]));
+
+    if !is_valid {
+        return None;
+    }
+
+    Some(Some(parts.join(" ")))
+}
+
+fn parse_source(text: &str) -> Option<FontSource<'_>> {
+    let parts = value::split_components(text);
+    let (first, rest) = parts.split_first()?;
+
+    if let Some(args) = function_args(first, "local") {
+        return match parse_family(args)? {
+            Some(name) if rest.is_empty() => Some(FontSource::Local(name)),
+            _ => None,
+        };
+    }
+
+    let url = function_args(first, "url")?;
+    let url = value::unquote(url).unwrap_or(url);
+
+    let mut format = None;
+    let mut tech = Vec::new();
+    for part in rest {
+        if let Some(args) = function_args(part, "format") {
+            if format.is_some() || !tech.is_empty() {
+                return None;
+            }
+
+            let args = args.trim();
+            let name = value::unquote(args).unwrap_or(args);
+            if name.is_empty() {
+                return None;
+            }
+            format = Some(name);
+        } else if let Some(args) = function_args(part, "tech") {
+            if !tech.is_empty() {
+                return None;
+            }
+
+            for name in value::split_list(args) {
+                if !value::is_custom_ident(name) {
+                    return None;
+                }
+                tech.push(name);
+            }
+        } else {
+            return None;
+        }
+    }
+
+    Some(FontSource::Url { url: url.trim(), format, tech })
+}
+
+/// Returns the arguments of `name(...)`, ASCII case-insensitive.
+fn function_args<'t>(text: &'t str, name: &str) -> Option<&'t str> {
+    let bytes = text.as_bytes();
+    if text.len() > name.len() + 1
+        && bytes[..name.len()].eq_ignore_ascii_case(name.as_bytes())
+        && bytes[name.len()] == b'('
+        && text.ends_with(')')
+    {
+        return Some(text[name.len() + 1..text.len() - 1].trim());
+    }
+
+    None
+}
+
+/// Parses `auto` or one or two values. Values are sorted.
+fn parse_range(text: &str, parse: fn(&str) -> Option<f32>) -> Option<Option<(f32, f32)>> {
+    let parts = value::split_components(text);
+    match parts.as_slice() {
+        [auto] if auto.eq_ignore_ascii_case("auto") => Some(None),
+        [a] => {
+            let a = parse(a)?;
+            Some(Some((a, a)))
+        }
+        [a, b] => {
+            let (a, b) = (parse(a)?, parse(b)?);
+            Some(Some((a.min(b), a.max(b))))
+        }
+        _ => None,
+    }
+}
+
+fn parse_weight(text: &str) -> Option<f32> {
+    if text.eq_ignore_ascii_case("normal") {
+        return Some(400.0);
+    }
+
+    if text.eq_ignore_ascii_case("bold") {
+        return Some(700.0);
+    }
+
+    match eval(text)? {
+        CalcValue::Number(n) if (1.0..=1000.0).contains(&n) => Some(n),
+        _ => None,
+    }
+}
+
+fn parse_stretch(text: &str) -> Option<f32> {
+    let keywords = [
+        ("ultra-condensed", 50.0),
+        ("extra-condensed", 62.5),
+        ("condensed", 75.0),
+        ("semi-condensed", 87.5),
+        ("normal", 100.0),
+        ("semi-expanded", 112.5),
+        ("expanded", 125.0),
+        ("extra-expanded", 150.0),
+        ("ultra-expanded", 200.0),
+    ];
+
+    if let Some((_, v)) = keywords.iter().find(|(k, _)| text.eq_ignore_ascii_case(k)) {
+        return Some(*v);
+    }
+
+    match eval(text)? {
+        CalcValue::Percentage(n) if n >= 0.0 => Some(n),
+        _ => None,
+    }
+}
+
+fn parse_style(text: &str) -> Option<Option<FontStyle>> {
+    let parts = value::split_components(text);
+    let (first, rest) = parts.split_first()?;
+
+    if first.eq_ignore_ascii_case("auto") && rest.is_empty() {
+        return Some(None);
+    }
+
+    if first.eq_ignore_ascii_case("normal") && rest.is_empty() {
+        return Some(Some(FontStyle::Normal));
+    }
+
+    if first.eq_ignore_ascii_case("italic") && rest.is_empty() {
+        return Some(Some(FontStyle::Italic));
+    }
+
+    if !first.eq_ignore_ascii_case("oblique") {
+        return None;
+    }
+
+    let angle = |text: &str| match eval(text)? {
+        CalcValue::Angle(deg) if (-90.0..=90.0).contains(&deg) => Some(deg),
+        _ => None,
+    };
+
+    let (a, b) = match rest {
+        [] => (14.0, 14.0),
+        [a] => (angle(a)?, angle(a)?),
+        [a, b] => (angle(a)?, angle(b)?),
+        _ => return None,
+    };
+
+    Some(Some(FontStyle::Oblique(a.min(b), a.max(b))))
+}
+
+fn parse_display(text: &str) -> Option<FontDisplay> {
+    let list = [
+        ("auto", FontDisplay::Auto),
+        ("block", FontDisplay::Block),
+        ("swap", FontDisplay::Swap),
+        ("fallback", FontDisplay::Fallback),
+        ("optional", FontDisplay::Optional),
+    ];
+
+    let text = text.trim();
+    list.iter().find(|(k, _)| text.eq_ignore_ascii_case(k)).map(|(_, v)| *v)
+}
+
+fn eval(text: &str) -> Option<CalcValue> {
+    CalcNode::parse(text).ok()?.eval(&CalcContext::default())
+}
+
+/// Parses `U+26`, `U+0-7F`, `U+0025-00FF` or `U+4??`.
+///
+/// https://www.w3.org/TR/css-syntax-3/#urange-syntax
+fn parse_unicode_range(text: &str) -> Option<(u32, u32)> {
+    let bytes = text.as_bytes();
+    if bytes.len() < 3 || !bytes[0].eq_ignore_ascii_case(&b'u') || bytes[1] != b'+' {
+        return None;
+    }
+
+    let text = &text[2..];
+    let (start, end) = match text.find('-') {
+        Some(idx) => {
+            let start = parse_hex(&text[..idx])?;
+            let end = parse_hex(&text[idx + 1..])?;
+            (start, end)
+        }
+        None => {
+            let digits = text.trim_end_matches('?');
+            let wildcards = text.len() - digits.len();
+            if text.is_empty() || text.len() > 6 {
+                return None;
+            }
+
+            let start = if digits.is_empty() { 0 } else { parse_hex(digits)? };
+            let shift = 4 * wildcards as u32;
+            (start << shift, (start << shift) | ((1 << shift) - 1))
+        }
+    };
+
+    if start > end || end > 0x10FFFF {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+fn parse_hex(text: &str) -> Option<u32> {
+    if text.is_empty() || text.len() > 6 || !text.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(text, 16).ok()
+}
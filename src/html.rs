@@ -0,0 +1,273 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::media::{Device, MediaQueryList};
+use crate::tokenizer::Tokenizer;
+
+/// A kind of an `EmbeddedStyle`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum EmbeddedStyleKind {
+    /// `<style>` element contents. A stylesheet.
+    StyleElement,
+    /// `style` attribute value. A declaration list.
+    StyleAttribute,
+}
+
+/// CSS embedded in an HTML or SVG document.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct EmbeddedStyle<'a> {
+    document: &'a str,
+    /// The style kind.
+    pub kind: EmbeddedStyleKind,
+    /// `<style>` element name, like `style` or `svg:style`, or the name
+    /// of the element with the `style` attribute.
+    pub element: &'a str,
+    /// `media` attribute value of the `<style>` element, without quotes.
+    pub media: Option<&'a str>,
+    /// Start position of the CSS in the document.
+    pub start: usize,
+    /// End position of the CSS in the document, exclusive.
+    pub end: usize,
+}
+
+impl<'a> EmbeddedStyle<'a> {
+    /// Returns the CSS text.
+    pub fn text(&self) -> &'a str {
+        &self.document[self.start..self.end]
+    }
+
+    /// Returns a bounded tokenizer for the CSS, so error positions point into the document.
+    ///
+    /// Style elements use `Tokenizer::new_bound` and style attributes use
+    /// `Tokenizer::new_declaration_list_bound`.
+    pub fn tokenizer(&self) -> Tokenizer<'a> {
+        match self.kind {
+            EmbeddedStyleKind::StyleElement => {
+                Tokenizer::new_bound(self.document, self.start, self.end)
+            }
+            EmbeddedStyleKind::StyleAttribute => {
+                Tokenizer::new_declaration_list_bound(self.document, self.start, self.end)
+            }
+        }
+    }
+
+    /// Checks that the `media` attribute matches the device.
+    ///
+    /// Returns `true` if there is no `media` attribute and `false` if it's invalid.
+    pub fn matches(&self, device: &Device) -> bool {
+        match self.media {
+            Some(media) => MediaQueryList::parse(media).is_ok_and(|list| list.matches(device)),
+            None => true,
+        }
+    }
+}
+
+/// Finds `<style>` elements and `style` attributes in an HTML or SVG document.
+///
+/// This is a lightweight scanner and not an HTML parser. Comments, CDATA sections
+/// and `<script>` contents are skipped. `<style>` elements with a `type` other than
+/// `text/css` are ignored. A CDATA section around the `<style>` contents, like in SVG,
+/// is not a part of the CSS. Character references, like `&quot;`, are not decoded.
+///
+/// Styles are returned in the document order.
+pub fn extract_styles(document: &str) -> Vec<EmbeddedStyle<'_>> {
+    let bytes = document.as_bytes();
+    let mut styles = Vec::new();
+    let mut pos = 0;
+
+    while let Some(idx) = find(bytes, pos, b"<") {
+        pos = idx;
+        let rest = &bytes[pos..];
+
+        if rest.starts_with(b"<!--") {
+            pos = find_end(bytes, pos + 4, b"-->");
+        } else if rest.starts_with(b"<![CDATA[") {
+            pos = find_end(bytes, pos + 9, b"]]>");
+        } else if rest.starts_with(b"<!") || rest.starts_with(b"<?") || rest.starts_with(b"</") {
+            pos = find_end(bytes, pos + 2, b">");
+        } else if rest.len() > 1 && rest[1].is_ascii_alphabetic() {
+            pos = consume_element(document, pos + 1, &mut styles);
+        } else {
+            pos += 1;
+        }
+    }
+
+    styles
+}
+
+/// Consumes a start tag after `<` and the contents of `<style>` and `<script>`.
+fn consume_element<'a>(document: &'a str, start: usize, styles: &mut Vec<EmbeddedStyle<'a>>) -> usize {
+    let bytes = document.as_bytes();
+    let mut pos = start;
+    while pos < bytes.len() && is_name_char(bytes[pos]) {
+        pos += 1;
+    }
+
+    let element = &document[start..pos];
+    let mut media = None;
+    let mut content_type = None;
+    let mut self_closing = false;
+
+    loop {
+        pos = skip_spaces(bytes, pos);
+        if pos >= bytes.len() {
+            return pos;
+        }
+
+        match bytes[pos] {
+            b'>' => {
+                pos += 1;
+                break;
+            }
+            b'/' => {
+                self_closing = true;
+                pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        self_closing = false;
+
+        let name_start = pos;
+        while pos < bytes.len() && !bytes[pos].is_ascii_whitespace()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+        {
+            pos += 1;
+        }
+        let name = &document[name_start..pos];
+        if name.is_empty() {
+            // A stray `=`.
+            pos += 1;
+            continue;
+        }
+
+        pos = skip_spaces(bytes, pos);
+        if pos >= bytes.len() || bytes[pos] != b'=' {
+            continue;
+        }
+
+        pos = skip_spaces(bytes, pos + 1);
+        let (value_start, value_end) = match bytes.get(pos) {
+            Some(&quote) if quote == b'"' || quote == b'\'' => {
+                let end = find(bytes, pos + 1, &[quote]).unwrap_or(bytes.len());
+                let range = (pos + 1, end);
+                pos = (end + 1).min(bytes.len());
+                range
+            }
+            _ => {
+                let value_start = pos;
+                while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && bytes[pos] != b'>' {
+                    pos += 1;
+                }
+                (value_start, pos)
+            }
+        };
+
+        let value = &document[value_start..value_end];
+        if name.eq_ignore_ascii_case("style") {
+            styles.push(EmbeddedStyle {
+                document,
+                kind: EmbeddedStyleKind::StyleAttribute,
+                element,
+                media: None,
+                start: value_start,
+                end: value_end,
+            });
+        } else if name.eq_ignore_ascii_case("media") {
+            media = Some(value);
+        } else if name.eq_ignore_ascii_case("type") {
+            content_type = Some(value);
+        }
+    }
+
+    if self_closing {
+        return pos;
+    }
+
+    let local_name = match element.rfind(':') {
+        Some(idx) => &element[idx + 1..],
+        None => element,
+    };
+
+    let is_style = local_name.eq_ignore_ascii_case("style");
+    if !is_style && !local_name.eq_ignore_ascii_case("script") {
+        return pos;
+    }
+
+    // Raw text up to the end tag.
+    let content_start = pos;
+    let mut content_end = bytes.len();
+    let mut search = pos;
+    while let Some(idx) = find(bytes, search, b"</") {
+        let name_end = idx + 2 + element.len();
+        if name_end <= bytes.len()
+            && bytes[idx + 2..name_end].eq_ignore_ascii_case(element.as_bytes())
+            && !bytes.get(name_end).is_some_and(|c| is_name_char(*c))
+        {
+            content_end = idx;
+            break;
+        }
+        search = idx + 2;
+    }
+
+    let is_css = content_type.map_or(true, |t| t.trim().is_empty() || t.trim().eq_ignore_ascii_case("text/css"));
+    if is_style && is_css {
+        let (start, end) = strip_cdata(bytes, content_start, content_end);
+        styles.push(EmbeddedStyle {
+            document,
+            kind: EmbeddedStyleKind::StyleElement,
+            element,
+            media,
+            start,
+            end,
+        });
+    }
+
+    find_end(bytes, content_end, b">")
+}
+
+/// Returns the contents of a CDATA section that wraps the whole region, if any.
+fn strip_cdata(bytes: &[u8], start: usize, end: usize) -> (usize, usize) {
+    let inner_start = skip_spaces(bytes, start);
+    let mut inner_end = end;
+    while inner_end > inner_start && bytes[inner_end - 1].is_ascii_whitespace() {
+        inner_end -= 1;
+    }
+
+    let region = &bytes[inner_start..inner_end];
+    if region.len() >= 12 && region.starts_with(b"<![CDATA[") && region.ends_with(b"]]>") {
+        return (inner_start + 9, inner_end - 3);
+    }
+
+    (start, end)
+}
+
+fn is_name_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b':' | b'.')
+}
+
+fn skip_spaces(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+        pos += 1;
+    }
+
+    pos
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    if from >= bytes.len() {
+        return None;
+    }
+
+    bytes[from..].windows(needle.len()).position(|w| w == needle).map(|idx| from + idx)
+}
+
+/// Returns the position after `needle`, or the end of data.
+fn find_end(bytes: &[u8], from: usize, needle: &[u8]) -> usize {
+    match find(bytes, from, needle) {
+        Some(idx) => idx + needle.len(),
+        None => bytes.len(),
+    }
+}
@@ -0,0 +1,303 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+use crate::error::Error;
+use crate::layer;
+use crate::media::MediaQueryList;
+use crate::stream::Stream;
+use crate::supports::{self, SupportsCondition};
+use crate::tokenizer::{Tokenizer, Token};
+
+/// A parsed `@import` rule.
+///
+/// https://www.w3.org/TR/css-cascade-5/#at-import
+#[derive(Clone,PartialEq,Debug)]
+pub struct Import<'a> {
+    /// URL without quotes.
+    pub url: &'a str,
+    /// `layer` or `layer(name)`.
+    pub layer: Option<ImportLayer<'a>>,
+    /// `supports(...)`.
+    pub supports: Option<SupportsCondition<'a>>,
+    /// Media query list. Empty if not set.
+    pub media: MediaQueryList<'a>,
+}
+
+/// A cascade layer of an `@import` rule.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ImportLayer<'a> {
+    /// `layer`
+    Anonymous,
+    /// `layer(name)`. Contains a dotted name, like `base.reset`.
+    Named(&'a str),
+}
+
+impl<'a> Import<'a> {
+    /// Parses an `@import` prelude, like `url("a.css") layer(base) screen`.
+    ///
+    /// Use `Tokenizer::parse_at_rule_prelude` right after `Token::AtRule("import")`
+    /// to get the prelude. The next token is `Token::AtRuleEnd`.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<Import<'a>, Error> {
+        parse_import(text).map(|(import, _)| import)
+    }
+}
+
+/// Maps an `@import` URL to the stylesheet source text.
+pub trait StylesheetLoader {
+    /// Resolves an `@import` URL into a canonical URL.
+    ///
+    /// `parent` is the canonical URL of the importing stylesheet, or `None` for the root one,
+    /// and can be used to resolve relative URLs.
+    ///
+    /// Equal canonical URLs must refer to the same stylesheet, since they are used
+    /// for cycle detection.
+    fn resolve(&self, url: &str, parent: Option<&str>) -> String;
+
+    /// Loads a stylesheet by a canonical URL returned by `resolve`.
+    ///
+    /// Returns `None` if the stylesheet can't be loaded.
+    fn load(&self, url: &str) -> Option<String>;
+}
+
+/// A `resolve_imports` error.
+#[derive(Clone,PartialEq)]
+pub enum ImportError {
+    /// A stylesheet can't be parsed.
+    Parse {
+        /// Canonical stylesheet URL. `None` for the root stylesheet.
+        url: Option<String>,
+        /// Parsing error. Positions are relative to the stylesheet.
+        error: Error,
+    },
+    /// `StylesheetLoader` failed to load a stylesheet. Contains the canonical URL.
+    NotFound(String),
+    /// A stylesheet imports itself, directly or indirectly.
+    Cycle(String),
+    /// Imports are nested deeper than allowed.
+    TooDeep(String),
+}
+
+impl fmt::Debug for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImportError::Parse { url: Some(ref url), ref error } =>
+                write!(f, "{:?} in '{}'", error, url),
+            ImportError::Parse { url: None, ref error } =>
+                write!(f, "{:?}", error),
+            ImportError::NotFound(ref url) =>
+                write!(f, "Failed to load '{}'", url),
+            ImportError::Cycle(ref url) =>
+                write!(f, "Import cycle at '{}'", url),
+            ImportError::TooDeep(ref url) =>
+                write!(f, "Imports are nested too deep at '{}'", url),
+        }
+    }
+}
+
+/// Recursively inlines `@import` rules into a single stylesheet.
+///
+/// Imported rules are wrapped in `@layer`, `@supports` and `@media` blocks
+/// matching the import conditions. `@charset` rules of imported stylesheets are removed.
+/// `@import` rules that appear after other rules are invalid and removed.
+///
+/// `max_depth` is the maximum import nesting level. `0` doesn't allow any imports.
+pub fn resolve_imports(
+    text: &str,
+    loader: &dyn StylesheetLoader,
+    max_depth: usize,
+) -> Result<String, ImportError> {
+    let mut stack = Vec::new();
+    resolve(text, None, loader, max_depth, &mut stack)
+}
+
+fn resolve(
+    text: &str,
+    url: Option<&str>,
+    loader: &dyn StylesheetLoader,
+    max_depth: usize,
+    stack: &mut Vec<String>,
+) -> Result<String, ImportError> {
+    let parse_error = |error| ImportError::Parse { url: url.map(|u| u.to_string()), error };
+
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut depth = 0;
+    let mut allow_imports = true;
+    let mut t = Tokenizer::new(text);
+
+    loop {
+        let token = t.parse_next().map_err(parse_error)?;
+        match token {
+            Token::AtRule(name) if depth == 0 => {
+                if !name.eq_ignore_ascii_case("import") {
+                    if !name.eq_ignore_ascii_case("layer") {
+                        allow_imports = false;
+                    }
+
+                    // The next token is the rule's `BlockStart` or `AtRuleEnd`.
+                    t.parse_at_rule_prelude().map_err(parse_error)?;
+                    continue;
+                }
+
+                let start = t.offset_of(name) - 1; // @
+                let prelude = t.parse_at_rule_prelude().map_err(parse_error)?;
+                match t.parse_next().map_err(parse_error)? {
+                    Token::AtRuleEnd | Token::EndOfStream => {}
+                    _ => return Err(parse_error(Error::UnknownToken(t.error_pos_of(prelude)))),
+                }
+
+                out.push_str(&text[last..start]);
+                last = t.pos();
+
+                if !allow_imports {
+                    continue;
+                }
+
+                let (import, raw) = parse_import(prelude)
+                    .map_err(|e| parse_error(e.offset_by(t.error_pos_of(prelude))))?;
+
+                let child_url = loader.resolve(import.url, url);
+                if stack.len() >= max_depth {
+                    return Err(ImportError::TooDeep(child_url));
+                }
+
+                if stack.contains(&child_url) {
+                    return Err(ImportError::Cycle(child_url));
+                }
+
+                let child = match loader.load(&child_url) {
+                    Some(child) => child,
+                    None => return Err(ImportError::NotFound(child_url)),
+                };
+
+                stack.push(child_url.clone());
+                let child = resolve(&child, Some(&child_url), loader, max_depth, stack)?;
+                stack.pop();
+
+                wrap(&mut out, &import, &raw, &child);
+            }
+            Token::Charset(encoding) => {
+                let start = text[..t.offset_of(encoding)].rfind('@').unwrap_or(0);
+                out.push_str(&text[last..start]);
+                if url.is_none() {
+                    out.push_str(&text[start..t.pos()]);
+                }
+                last = t.pos();
+            }
+            Token::Namespace { .. } => allow_imports = false,
+            Token::BlockStart => {
+                depth += 1;
+                allow_imports = false;
+            }
+            Token::BlockEnd => depth -= 1,
+            Token::EndOfStream => break,
+            _ => {}
+        }
+    }
+
+    out.push_str(&text[last..]);
+    Ok(out)
+}
+
+fn wrap(out: &mut String, import: &Import, raw: &RawConditions, child: &str) {
+    let mut blocks = 0;
+
+    match import.layer {
+        Some(ImportLayer::Named(name)) => out.push_str(&format!("@layer {} {{\n", name)),
+        Some(ImportLayer::Anonymous) => out.push_str("@layer {\n"),
+        None => {}
+    }
+    if import.layer.is_some() {
+        blocks += 1;
+    }
+
+    if let Some(ref supports) = raw.supports {
+        out.push_str(&format!("@supports {} {{\n", supports));
+        blocks += 1;
+    }
+
+    if !raw.media.is_empty() {
+        out.push_str(&format!("@media {} {{\n", raw.media));
+        blocks += 1;
+    }
+
+    out.push_str(child.trim());
+    out.push('\n');
+
+    for _ in 0..blocks {
+        out.push_str("}\n");
+    }
+}
+
+/// Raw condition texts of an `@import` rule, used to wrap imported rules.
+struct RawConditions<'a> {
+    /// `supports()` arguments, with parentheses added to a bare declaration.
+    supports: Option<String>,
+    media: &'a str,
+}
+
+fn parse_import(text: &str) -> Result<(Import<'_>, RawConditions<'_>), Error> {
+    let mut s = Stream::new(text.as_bytes());
+    s.skip_spaces();
+
+    let url = s.consume_url()?;
+    s.skip_spaces();
+
+    let mut layer = None;
+    let layer_pos = s.gen_error_pos();
+    if s.try_consume_keyword("layer") {
+        if s.curr_char() == Ok(b'(') {
+            let block = s.consume_parenthesized()?;
+            let name = block[1..block.len() - 1].trim();
+            if !layer::is_layer_name(name) {
+                return Err(Error::InvalidValue(layer_pos));
+            }
+            layer = Some(ImportLayer::Named(name));
+        } else {
+            layer = Some(ImportLayer::Anonymous);
+        }
+
+        s.skip_spaces();
+    }
+
+    let mut supports = None;
+    let mut raw_supports = None;
+    let mut tmp = s;
+    if tmp.try_consume_keyword("supports") && tmp.curr_char() == Ok(b'(') {
+        let supports_pos = s.gen_error_pos();
+        s = tmp;
+        let block = s.consume_parenthesized()?;
+        let args = &block[1..block.len() - 1];
+
+        // `supports()` also accepts a bare declaration.
+        if let Ok(condition) = SupportsCondition::parse(args) {
+            supports = Some(condition);
+            raw_supports = Some(args.trim().to_string());
+        } else if let Some(feature) = supports::parse_declaration(args) {
+            supports = Some(SupportsCondition::Feature(feature));
+            raw_supports = Some(format!("({})", args.trim()));
+        } else {
+            return Err(Error::InvalidValue(supports_pos));
+        }
+
+        s.skip_spaces();
+    }
+
+    let media_pos = s.gen_error_pos();
+    let raw_media = s.slice_region_raw_str(s.pos(), text.len()).trim();
+    let media = MediaQueryList::parse(raw_media).map_err(|e| e.offset_by(media_pos))?;
+
+    let import = Import {
+        url,
+        layer,
+        supports,
+        media,
+    };
+
+    Ok((import, RawConditions { supports: raw_supports, media: raw_media }))
+}
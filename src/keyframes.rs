@@ -0,0 +1,142 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::tokenizer::{Tokenizer, Token};
+use crate::value;
+
+/// A parsed `@keyframes` rule.
+///
+/// https://www.w3.org/TR/css-animations-1/#keyframes
+#[derive(Clone,PartialEq,Debug)]
+pub struct Keyframes<'a> {
+    /// Animation name, without quotes.
+    pub name: &'a str,
+    /// Vendor prefix, like `-webkit-`.
+    pub vendor_prefix: Option<&'a str>,
+    /// Keyframes ordered by offset. Each offset is unique.
+    pub keyframes: Vec<Keyframe<'a>>,
+}
+
+/// A single keyframe.
+#[derive(Clone,PartialEq,Debug)]
+pub struct Keyframe<'a> {
+    /// Offset in percents, from `0.0` to `100.0`.
+    pub offset: f32,
+    /// Declarations in the source order. A property can appear only once.
+    pub declarations: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Keyframes<'a> {
+    /// Parses a `@keyframes` rule.
+    ///
+    /// Should be called right after `Token::AtRule` with the rule name.
+    /// Consumes everything up to and including the rule's `Token::BlockEnd`.
+    ///
+    /// Keyframes with the same offset are merged, and later declarations win.
+    /// Declarations with `!important` are ignored, as required by the spec.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` if the rule is not `@keyframes`
+    /// or the name is not a valid `<custom-ident>` or string.
+    pub fn parse(tokenizer: &mut Tokenizer<'a>, at_rule: &'a str) -> Result<Keyframes<'a>, Error> {
+        let vendor_prefix = match at_rule.len().checked_sub("keyframes".len()) {
+            Some(0) if at_rule.eq_ignore_ascii_case("keyframes") => None,
+            Some(idx) if at_rule[idx..].eq_ignore_ascii_case("keyframes")
+                && at_rule.starts_with('-') && at_rule[..idx].ends_with('-') && idx > 2 => {
+                Some(&at_rule[..idx])
+            }
+            _ => return Err(Error::InvalidValue(tokenizer.error_pos_of(at_rule))),
+        };
+
+        let prelude = tokenizer.parse_at_rule_prelude()?;
+        let name = parse_name(prelude)
+            .ok_or_else(|| Error::InvalidValue(tokenizer.error_pos_of(prelude)))?;
+
+        match tokenizer.parse_next()? {
+            Token::BlockStart => {}
+            _ => return Err(Error::UnknownToken(tokenizer.error_pos_of(prelude))),
+        }
+
+        let mut keyframes: Vec<Keyframe> = Vec::new();
+        let mut offsets = Vec::new();
+
+        loop {
+            match tokenizer.parse_next()? {
+                Token::KeyframeSelector(offset) => offsets.push(offset),
+                Token::Comma => {}
+                Token::BlockStart => {
+                    let declarations = tokenizer.parse_declarations(|_, value| !is_important(value))?;
+                    for offset in offsets.drain(..) {
+                        let idx = match keyframes.iter().position(|k| k.offset == offset) {
+                            Some(idx) => idx,
+                            None => {
+                                keyframes.push(Keyframe { offset, declarations: Vec::new() });
+                                keyframes.len() - 1
+                            }
+                        };
+
+                        merge(&mut keyframes[idx].declarations, &declarations);
+                    }
+                }
+                Token::BlockEnd | Token::EndOfStream => break,
+                Token::Declaration(..) | Token::CustomProperty(..) => {}
+                _ => {
+                    // Anything else is not a valid keyframe rule.
+                    skip_rule(tokenizer)?;
+                    offsets.clear();
+                }
+            }
+        }
+
+        // Stable, so source order is preserved for equal offsets.
+        keyframes.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap());
+
+        Ok(Keyframes {
+            name,
+            vendor_prefix,
+            keyframes,
+        })
+    }
+}
+
+fn parse_name(prelude: &str) -> Option<&str> {
+    if let Some(name) = value::unquote(prelude) {
+        return Some(name);
+    }
+
+    if !value::is_custom_ident(prelude) || prelude.eq_ignore_ascii_case("none")
+        || value::is_css_wide_keyword(prelude) || prelude.eq_ignore_ascii_case("default")
+    {
+        return None;
+    }
+
+    Some(prelude)
+}
+
+fn is_important(value: &str) -> bool {
+    match value.rfind('!') {
+        Some(idx) => value[idx + 1..].trim().eq_ignore_ascii_case("important"),
+        None => false,
+    }
+}
+
+fn merge<'a>(declarations: &mut Vec<(&'a str, &'a str)>, new: &[(&'a str, &'a str)]) {
+    for &(name, value) in new {
+        declarations.retain(|d| d.0 != name);
+        declarations.push((name, value));
+    }
+}
+
+/// Skips tokens up to and including the end of the next block.
+fn skip_rule(tokenizer: &mut Tokenizer) -> Result<(), Error> {
+    loop {
+        match tokenizer.parse_next()? {
+            Token::BlockStart => return tokenizer.skip_block(),
+            Token::EndOfStream => return Ok(()),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,366 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cmp::Ordering;
+
+use crate::error::Error;
+use crate::stream::Stream;
+use crate::tokenizer::{Tokenizer, Token};
+use crate::value;
+
+/// A comma-separated list of layer names of an `@layer` rule, like `reset, base.forms`.
+///
+/// https://www.w3.org/TR/css-cascade-5/#layering
+#[derive(Clone,PartialEq,Debug)]
+pub struct LayerList<'a> {
+    /// Dotted layer names. Empty for an anonymous `@layer { ... }` block.
+    pub names: Vec<&'a str>,
+}
+
+impl<'a> LayerList<'a> {
+    /// Parses an `@layer` prelude.
+    ///
+    /// An empty prelude is parsed as an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` on a CSS-wide keyword used as a name.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<LayerList<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        let mut names = Vec::new();
+
+        s.skip_spaces();
+        while !s.at_end() {
+            let pos = s.gen_error_pos();
+            let start = s.pos();
+            s.consume_ident()?;
+            while s.char_at(0) == Some(b'.') {
+                s.advance_raw(1);
+                s.consume_ident()?;
+            }
+
+            let name = s.slice_region_raw_str(start, s.pos());
+            if !is_layer_name(name) {
+                return Err(Error::InvalidValue(pos));
+            }
+            names.push(name);
+
+            s.skip_spaces();
+            if s.at_end() {
+                break;
+            }
+
+            s.consume_char(b',')?;
+            s.skip_spaces();
+            if s.at_end() {
+                return Err(Error::UnexpectedEndOfStream(s.gen_error_pos()));
+            }
+        }
+
+        Ok(LayerList { names })
+    }
+}
+
+/// Checks that `name` is a dotted layer name, like `base.reset`.
+pub(crate) fn is_layer_name(name: &str) -> bool {
+    name.split('.').all(|part| value::is_custom_ident(part) && !value::is_css_wide_keyword(part))
+}
+
+/// A cascade layer declared in a `LayerOrder`.
+#[derive(Clone,Copy,PartialEq,Eq,Hash,Debug)]
+pub struct LayerId(usize);
+
+#[derive(Clone,Debug)]
+struct Layer {
+    /// Name relative to the parent layer. `None` for anonymous layers.
+    name: Option<String>,
+    parent: Option<LayerId>,
+    children: Vec<LayerId>,
+}
+
+/// Cascade layers in the declaration order.
+///
+/// Layers are ordered by their first declaration. Sub-layers are ordered within
+/// their parent layer and come before the parent's own declarations.
+/// Unlayered declarations come after all layers.
+#[derive(Clone,Default,Debug)]
+pub struct LayerOrder {
+    layers: Vec<Layer>,
+    roots: Vec<LayerId>,
+}
+
+impl LayerOrder {
+    /// Constructs a new, empty `LayerOrder`.
+    pub fn new() -> LayerOrder {
+        LayerOrder::default()
+    }
+
+    /// Declares a layer, like `@layer base.reset;` inside the `parent` layer.
+    ///
+    /// Each part of a dotted name is declared in turn. Layers that were already
+    /// declared keep their position.
+    ///
+    /// Returns the innermost layer.
+    pub fn declare(&mut self, parent: Option<LayerId>, name: &str) -> LayerId {
+        let mut parent = parent;
+        for part in name.split('.') {
+            let id = match self.find_child(parent, part) {
+                Some(id) => id,
+                None => self.push(parent, Some(part.to_string())),
+            };
+            parent = Some(id);
+        }
+
+        parent.unwrap()
+    }
+
+    /// Declares an anonymous layer, like `@layer { ... }` inside the `parent` layer.
+    ///
+    /// Each anonymous layer is unique.
+    pub fn declare_anonymous(&mut self, parent: Option<LayerId>) -> LayerId {
+        self.push(parent, None)
+    }
+
+    /// Returns a layer by its full dotted name, like `base.reset`.
+    pub fn find(&self, name: &str) -> Option<LayerId> {
+        let mut parent = None;
+        for part in name.split('.') {
+            parent = Some(self.find_child(parent, part)?);
+        }
+
+        parent
+    }
+
+    /// Returns the full dotted name of a layer.
+    ///
+    /// Returns `None` if the layer or any of its parents is anonymous.
+    pub fn name(&self, layer: LayerId) -> Option<String> {
+        let mut parts = Vec::new();
+        let mut curr = Some(layer);
+        while let Some(id) = curr {
+            let layer = &self.layers[id.0];
+            parts.push(layer.name.as_ref()?.as_str());
+            curr = layer.parent;
+        }
+
+        parts.reverse();
+        Some(parts.join("."))
+    }
+
+    /// Returns the number of declared layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Checks that no layers were declared.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns the position of a layer in the cascade. `None` is for unlayered declarations.
+    ///
+    /// A higher rank wins for normal declarations and loses for important ones.
+    /// Ranks can change when new layers are declared, so they should be computed
+    /// after the whole stylesheet was parsed.
+    pub fn rank(&self, layer: Option<LayerId>) -> usize {
+        let layer = match layer {
+            Some(layer) => layer,
+            None => return self.layers.len(),
+        };
+
+        let mut rank = 0;
+        self.find_rank(&self.roots, layer, &mut rank);
+        rank
+    }
+
+    /// Compares the cascade positions of two layers. See `rank`.
+    pub fn compare(&self, a: Option<LayerId>, b: Option<LayerId>) -> Ordering {
+        self.rank(a).cmp(&self.rank(b))
+    }
+
+    fn find_rank(&self, list: &[LayerId], layer: LayerId, rank: &mut usize) -> bool {
+        for &id in list {
+            if self.find_rank(&self.layers[id.0].children, layer, rank) {
+                return true;
+            }
+
+            if id == layer {
+                return true;
+            }
+
+            *rank += 1;
+        }
+
+        false
+    }
+
+    fn find_child(&self, parent: Option<LayerId>, name: &str) -> Option<LayerId> {
+        let list = match parent {
+            Some(parent) => &self.layers[parent.0].children,
+            None => &self.roots,
+        };
+
+        list.iter().cloned().find(|id| self.layers[id.0].name.as_deref() == Some(name))
+    }
+
+    fn push(&mut self, parent: Option<LayerId>, name: Option<String>) -> LayerId {
+        let id = LayerId(self.layers.len());
+        self.layers.push(Layer { name, parent, children: Vec::new() });
+        match parent {
+            Some(parent) => self.layers[parent.0].children.push(id),
+            None => self.roots.push(id),
+        }
+
+        id
+    }
+}
+
+/// A cascade priority of a declaration. A greater priority wins.
+///
+/// Compares, in order: importance, layer rank, scope proximity, specificity
+/// and source order. Layer order is reversed for important declarations.
+///
+/// https://www.w3.org/TR/css-cascade-5/#cascade-sort
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct CascadePriority {
+    /// Whether the declaration has `!important`.
+    pub important: bool,
+    /// Layer rank from `LayerOrder::rank`.
+    pub layer: usize,
+    /// Scope proximity from `ScopeRule::proximity`. `None` for unscoped declarations.
+    ///
+    /// A lower proximity wins. Unscoped declarations lose to scoped ones.
+    pub scope_proximity: Option<usize>,
+    /// Selector specificity, as (ID, class, type) counts.
+    pub specificity: (u32, u32, u32),
+    /// Declaration position in the stylesheet.
+    pub source_order: usize,
+}
+
+impl Ord for CascadePriority {
+    fn cmp(&self, other: &CascadePriority) -> Ordering {
+        let layer = if self.important && other.important {
+            other.layer.cmp(&self.layer)
+        } else {
+            self.layer.cmp(&other.layer)
+        };
+
+        let proximity = match (self.scope_proximity, other.scope_proximity) {
+            (Some(a), Some(b)) => b.cmp(&a),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+
+        self.important.cmp(&other.important)
+            .then(layer)
+            .then(proximity)
+            .then(self.specificity.cmp(&other.specificity))
+            .then(self.source_order.cmp(&other.source_order))
+    }
+}
+
+impl PartialOrd for CascadePriority {
+    fn partial_cmp(&self, other: &CascadePriority) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A tokenizer wrapper that tracks cascade layers.
+///
+/// `@layer` statements are removed and their names are declared.
+/// `@layer` blocks are unwrapped: the `AtRule`, `BlockStart` and `BlockEnd`
+/// tokens are removed and the contents are returned as if they were written
+/// in the parent block, while `current_layer` returns the block's layer.
+pub struct CascadeLayers<'a> {
+    tokenizer: Tokenizer<'a>,
+    order: LayerOrder,
+    /// Each entry is `true` if the block is an unwrapped `@layer` block.
+    blocks: Vec<bool>,
+    layers: Vec<LayerId>,
+}
+
+impl<'a> CascadeLayers<'a> {
+    /// Constructs a new `CascadeLayers`.
+    pub fn new(tokenizer: Tokenizer<'a>) -> CascadeLayers<'a> {
+        CascadeLayers {
+            tokenizer,
+            order: LayerOrder::new(),
+            blocks: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// Returns the layer of the last returned token. `None` if unlayered.
+    pub fn current_layer(&self) -> Option<LayerId> {
+        self.layers.last().cloned()
+    }
+
+    /// Returns layers declared so far.
+    pub fn order(&self) -> &LayerOrder {
+        &self.order
+    }
+
+    /// Returns the underlying tokenizer.
+    pub fn tokenizer(&self) -> &Tokenizer<'a> {
+        &self.tokenizer
+    }
+
+    /// Consumes the wrapper and returns the declared layers.
+    pub fn into_order(self) -> LayerOrder {
+        self.order
+    }
+
+    /// Parses a next token.
+    ///
+    /// # Errors
+    ///
+    /// Returns tokenizer errors and `LayerList::parse` errors.
+    /// Returns `Error::InvalidValue` on a statement without names
+    /// or a block with more than one name.
+    pub fn parse_next(&mut self) -> Result<Token<'a>, Error> {
+        loop {
+            let token = self.tokenizer.parse_next()?;
+
+            match token {
+                Token::AtRule(name) if name.eq_ignore_ascii_case("layer") => {
+                    let prelude = self.tokenizer.parse_at_rule_prelude()?;
+                    let list = LayerList::parse(prelude)
+                        .map_err(|e| e.offset_by(self.tokenizer.error_pos_of(prelude)))?;
+
+                    let parent = self.current_layer();
+                    match self.tokenizer.parse_next()? {
+                        Token::AtRuleEnd | Token::EndOfStream if !list.names.is_empty() => {
+                            for name in list.names {
+                                self.order.declare(parent, name);
+                            }
+                        }
+                        Token::BlockStart if list.names.len() <= 1 => {
+                            let layer = match list.names.first() {
+                                Some(name) => self.order.declare(parent, name),
+                                None => self.order.declare_anonymous(parent),
+                            };
+
+                            self.blocks.push(true);
+                            self.layers.push(layer);
+                        }
+                        _ => return Err(Error::InvalidValue(self.tokenizer.error_pos_of(name))),
+                    }
+                }
+                Token::BlockStart => {
+                    self.blocks.push(false);
+                    return Ok(token);
+                }
+                Token::BlockEnd => {
+                    if self.blocks.pop() != Some(true) {
+                        return Ok(token);
+                    }
+
+                    self.layers.pop();
+                }
+                _ => return Ok(token),
+            }
+        }
+    }
+}
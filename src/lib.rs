@@ -14,17 +14,21 @@ Since it's very simple we will start with limitations:
 - Property values are not parsed.
 
   In CSS like `* { width: 5px }` you will get `width` property with `5px` values as a string.
-- Attribute selector rule is not parsed.
+- Attribute selector rule is not parsed by the tokenizer.
 
   `[foo~="warning"]` will be parsed as `Token::AttributeSelector("foo~=\"warning\"")`.
+  Use `Attribute::parse` to parse it.
 - There are no data validation.
 
   - Pseudo-class tokens can contain any text, language pseudo-class can contain any text or even none.
   - Declarations can contain any kind of names and values.
+    Use `Validator` to check them against the known property grammars.
 - All comments will be ignored.
 
   They didn't have it's own `Token` item.
-- CDO/CDC comments are not supported.
+- CDO/CDC (`<!--` and `-->`) between top-level rules are ignored.
+
+  Use `Tokenizer::set_cdo_cdc_tokens` to get them as `Token::Cdo` and `Token::Cdc`.
 - Parser is case sensitive. All keywords should be lowercase.
 - Unicode escape, like `\26`, is not supported.
 - No spec-defined error handling.
@@ -73,10 +77,67 @@ div#x:first-letter em[id] + .hh1 { color : red }
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
-
+#![allow(clippy::needless_return)]
+
+pub use calc::{CalcNode, CalcType, CalcContext, CalcValue, Unit};
+pub use color::Color;
+pub use container::{
+    ContainerQuery, ContainerCondition, StyleQuery, ContainerType, Container, parse_container_names,
+};
+pub use counter_style::{CounterStyle, CounterSystem, CounterSymbol, CounterRange};
+pub use descriptor::{DescriptorBlock, is_descriptor_at_rule};
 pub use error::{Error, ErrorPos};
+pub use font_face::{FontFace, FontSource, FontStyle, FontDisplay};
+pub use html::{EmbeddedStyle, EmbeddedStyleKind, extract_styles};
+pub use import::{Import, ImportLayer, ImportError, StylesheetLoader, resolve_imports};
+pub use keyframes::{Keyframes, Keyframe};
+pub use layer::{LayerList, LayerId, LayerOrder, CascadePriority, CascadeLayers};
+pub use media::{
+    MediaQueryList, MediaQuery, MediaQualifier, MediaType, MediaCondition, MediaFeature,
+    MediaBound, MediaValue, Device, ColorScheme, Pointer,
+};
+pub use os::{Os, OsQuery, OsCondition, OsFilter};
+pub use page::{
+    PageSelectorList, PageSelector, PagePseudoClass, PageContext, MarginBox, MarginBoxRule, PageRule,
+};
+pub use property_rule::{
+    PropertyRule, PropertySyntax, SyntaxComponent, SyntaxComponentName, SyntaxMultiplier,
+};
+pub use properties::{
+    PropertyInfo, Grammar, ValueType, ValidationError, ValidationMode, ValidationWarning,
+    Validator, lookup_property, suggest_property, validate_declaration,
+};
+pub use scope::ScopeRule;
+pub use selector::{
+    Element, NamespaceConstraint, Namespaces, Attribute, AttributeOperator, SelectorList, Selector,
+    SelectorComponent,
+};
+pub use shorthand::{Shorthand, Longhands};
+pub use supports::{SupportsCondition, SupportsFeature};
 pub use tokenizer::{Tokenizer, Token, Combinator};
+pub use var::CustomProperties;
 
+mod calc;
+mod color;
+mod container;
+mod counter_style;
+mod descriptor;
 mod error;
+mod font_face;
+mod html;
+mod import;
+mod keyframes;
+mod layer;
+mod media;
+mod os;
+mod page;
+mod properties;
+mod property_rule;
+mod scope;
+mod selector;
+mod shorthand;
 mod stream;
+mod supports;
 mod tokenizer;
+mod value;
+mod var;
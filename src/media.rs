@@ -0,0 +1,827 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::{CalcNode, CalcContext, CalcValue, Unit};
+use crate::stream::Stream;
+use crate::error::Error;
+
+/// A comma-separated list of media queries.
+///
+/// An empty list matches everything.
+///
+/// https://www.w3.org/TR/mediaqueries-4/#mq-list
+#[derive(Clone,PartialEq,Debug)]
+pub struct MediaQueryList<'a> {
+    /// Media queries.
+    pub queries: Vec<MediaQuery<'a>>,
+}
+
+/// A single media query.
+///
+/// https://www.w3.org/TR/mediaqueries-4/#media
+#[derive(Clone,PartialEq,Debug)]
+pub struct MediaQuery<'a> {
+    /// `not` or `only` before the media type.
+    pub qualifier: Option<MediaQualifier>,
+    /// Media type. `None` if the query consists only of a condition.
+    pub media_type: Option<MediaType<'a>>,
+    /// Media condition.
+    pub condition: Option<MediaCondition<'a>>,
+}
+
+/// Media query qualifier.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum MediaQualifier {
+    /// `not`
+    Not,
+    /// `only`
+    Only,
+}
+
+/// Media type.
+///
+/// https://www.w3.org/TR/mediaqueries-4/#media-types
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum MediaType<'a> {
+    /// `all`
+    All,
+    /// `screen`
+    Screen,
+    /// `print`
+    Print,
+    /// Any other media type. Never matches.
+    Other(&'a str),
+}
+
+/// Media condition.
+///
+/// https://www.w3.org/TR/mediaqueries-4/#media-conditions
+#[derive(Clone,PartialEq,Debug)]
+pub enum MediaCondition<'a> {
+    /// A single media feature in parentheses.
+    Feature(MediaFeature<'a>),
+    /// `not <condition>`
+    Not(Box<MediaCondition<'a>>),
+    /// `<condition> and <condition> ...`
+    And(Vec<MediaCondition<'a>>),
+    /// `<condition> or <condition> ...`
+    Or(Vec<MediaCondition<'a>>),
+    /// Unknown content in parentheses or a function, like `(foo bar)` or `baz()`.
+    ///
+    /// Always evaluates to false. Contains the raw text, including the parentheses.
+    ///
+    /// https://www.w3.org/TR/mediaqueries-4/#typedef-general-enclosed
+    Unknown(&'a str),
+}
+
+/// Media feature.
+///
+/// Names are stored as written. `min-` and `max-` prefixes are stripped
+/// and the feature is stored as a `Range`.
+///
+/// https://www.w3.org/TR/mediaqueries-4/#mq-features
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum MediaFeature<'a> {
+    /// Feature in a boolean context, like `(color)`.
+    Boolean(&'a str),
+    /// Feature with a value, like `(orientation: landscape)` or `(width = 800px)`.
+    Plain {
+        #[allow(missing_docs)]
+        name: &'a str,
+        #[allow(missing_docs)]
+        value: MediaValue<'a>,
+    },
+    /// Feature in a range context, like `(min-width: 400px)` or `(400px <= width < 800px)`.
+    Range {
+        #[allow(missing_docs)]
+        name: &'a str,
+        /// The lower bound.
+        start: Option<MediaBound<'a>>,
+        /// The upper bound.
+        end: Option<MediaBound<'a>>,
+    },
+}
+
+/// A bound of a media feature range.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct MediaBound<'a> {
+    /// Bound value.
+    pub value: MediaValue<'a>,
+    /// Whether the bound value itself is in the range.
+    pub inclusive: bool,
+}
+
+/// Media feature value.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum MediaValue<'a> {
+    /// Plain number.
+    Number(f32),
+    /// Number with a unit, like `800px` or `2dppx`.
+    Dimension(f32, &'a str),
+    /// Ratio, like `16/9`.
+    Ratio(f32, f32),
+    /// Keyword, like `landscape`.
+    Ident(&'a str),
+}
+
+/// A description of the output device, used to evaluate media queries.
+///
+/// Lengths are in CSS pixels.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct Device {
+    /// Media type. `MediaType::Other` never matches.
+    pub media_type: MediaType<'static>,
+    /// Viewport width.
+    pub width: f32,
+    /// Viewport height.
+    pub height: f32,
+    /// Device pixels per CSS pixel.
+    pub resolution: f32,
+    /// Bits per color component. 0 for monochrome devices.
+    pub color_bits: u32,
+    /// Bits per pixel on monochrome devices. 0 for color devices.
+    pub monochrome_bits: u32,
+    /// Font size used to resolve `em` and `rem`.
+    pub font_size: f32,
+    /// `prefers-color-scheme`
+    pub color_scheme: ColorScheme,
+    /// `prefers-reduced-motion: reduce`
+    pub reduced_motion: bool,
+    /// Primary pointing device.
+    pub pointer: Pointer,
+    /// Most capable of all pointing devices.
+    pub any_pointer: Pointer,
+    /// Whether the primary pointing device can hover.
+    pub hover: bool,
+    /// Whether any pointing device can hover.
+    pub any_hover: bool,
+}
+
+impl Default for Device {
+    fn default() -> Device {
+        Device {
+            media_type: MediaType::Screen,
+            width: 0.0,
+            height: 0.0,
+            resolution: 1.0,
+            color_bits: 8,
+            monochrome_bits: 0,
+            font_size: 16.0,
+            color_scheme: ColorScheme::Light,
+            reduced_motion: false,
+            pointer: Pointer::Fine,
+            any_pointer: Pointer::Fine,
+            hover: true,
+            any_hover: true,
+        }
+    }
+}
+
+/// `prefers-color-scheme` value.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ColorScheme {
+    #[allow(missing_docs)]
+    Light,
+    #[allow(missing_docs)]
+    Dark,
+}
+
+/// `pointer` and `any-pointer` value.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum Pointer {
+    /// No pointing device.
+    None,
+    /// Limited accuracy, like a touchscreen.
+    Coarse,
+    /// Accurate, like a mouse.
+    Fine,
+}
+
+impl Pointer {
+    fn name(&self) -> &'static str {
+        match *self {
+            Pointer::None => "none",
+            Pointer::Coarse => "coarse",
+            Pointer::Fine => "fine",
+        }
+    }
+}
+
+/// A device value of a media feature.
+#[derive(Clone,Copy,PartialEq)]
+pub(crate) enum DeviceValue {
+    Length(f32),
+    Ratio(f32),
+    Resolution(f32),
+    Integer(f32),
+    /// A discrete value and its value in a boolean context.
+    Ident(&'static str, bool),
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum Operand<'a> {
+    Name(&'a str),
+    Value(MediaValue<'a>),
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl<'a> MediaQueryList<'a> {
+    /// Parses a media query list, like the `@media` prelude.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<MediaQueryList<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        let mut queries = Vec::new();
+
+        s.skip_spaces();
+        if s.at_end() {
+            return Ok(MediaQueryList { queries });
+        }
+
+        loop {
+            queries.push(parse_query(&mut s)?);
+            s.skip_spaces();
+
+            if s.at_end() {
+                break;
+            }
+
+            s.consume_char(b',')?;
+        }
+
+        Ok(MediaQueryList { queries })
+    }
+
+    /// Checks that the list matches the device.
+    ///
+    /// An empty list always matches. Unknown features and `MediaCondition::Unknown`
+    /// evaluate to "unknown", which is treated as false at the query level.
+    ///
+    /// https://www.w3.org/TR/mediaqueries-4/#evaluating
+    pub fn matches(&self, device: &Device) -> bool {
+        self.queries.is_empty() || self.queries.iter().any(|q| q.matches(device))
+    }
+}
+
+impl<'a> MediaQuery<'a> {
+    /// Checks that the query matches the device.
+    pub fn matches(&self, device: &Device) -> bool {
+        let type_matches = match self.media_type {
+            None | Some(MediaType::All) => true,
+            Some(MediaType::Other(_)) => false,
+            Some(t) => t == device.media_type,
+        };
+
+        let result = if type_matches {
+            match self.condition {
+                Some(ref condition) => condition.eval(device),
+                None => Some(true),
+            }
+        } else {
+            Some(false)
+        };
+
+        // `not` negates the whole query, but an unknown result stays unknown.
+        if self.qualifier == Some(MediaQualifier::Not) {
+            return result == Some(false);
+        }
+
+        result == Some(true)
+    }
+}
+
+impl<'a> MediaCondition<'a> {
+    /// Evaluates the condition using three-valued logic.
+    ///
+    /// Returns `None` for "unknown".
+    pub fn eval(&self, device: &Device) -> Option<bool> {
+        match *self {
+            MediaCondition::Feature(ref f) => f.eval(device),
+            MediaCondition::Not(ref c) => c.eval(device).map(|v| !v),
+            MediaCondition::And(ref list) => {
+                let mut result = Some(true);
+                for c in list {
+                    match c.eval(device) {
+                        Some(false) => return Some(false),
+                        None => result = None,
+                        Some(true) => {}
+                    }
+                }
+                result
+            }
+            MediaCondition::Or(ref list) => {
+                let mut result = Some(false);
+                for c in list {
+                    match c.eval(device) {
+                        Some(true) => return Some(true),
+                        None => result = None,
+                        Some(false) => {}
+                    }
+                }
+                result
+            }
+            MediaCondition::Unknown(_) => None,
+        }
+    }
+}
+
+impl<'a> MediaFeature<'a> {
+    /// Evaluates the feature.
+    ///
+    /// Returns `None` for unknown features and invalid values.
+    pub fn eval(&self, device: &Device) -> Option<bool> {
+        let ctx = CalcContext {
+            percentage_basis: None,
+            font_size: device.font_size,
+            root_font_size: device.font_size,
+            viewport_width: device.width,
+            viewport_height: device.height,
+        };
+
+        self.eval_with(|name| device_value(device, name), &ctx)
+    }
+
+    /// Evaluates the feature using `lookup` to get feature values by name.
+    ///
+    /// Query lengths are resolved using `ctx`.
+    pub(crate) fn eval_with<F>(&self, lookup: F, ctx: &CalcContext) -> Option<bool>
+        where F: Fn(&str) -> Option<DeviceValue>
+    {
+        match *self {
+            MediaFeature::Boolean(name) => {
+                match lookup(name)? {
+                    DeviceValue::Ident(_, v) => Some(v),
+                    DeviceValue::Length(v)
+                    | DeviceValue::Ratio(v)
+                    | DeviceValue::Resolution(v)
+                    | DeviceValue::Integer(v) => Some(v != 0.0),
+                }
+            }
+            MediaFeature::Plain { name, value } => {
+                let dev = lookup(name)?;
+                if let DeviceValue::Ident(ident, _) = dev {
+                    return match value {
+                        MediaValue::Ident(v) => Some(v.eq_ignore_ascii_case(ident)),
+                        _ => None,
+                    };
+                }
+
+                let (dev, v) = (number(dev)?, resolve_value(dev, value, ctx)?);
+                Some((dev - v).abs() <= f32::EPSILON * dev.abs().max(1.0))
+            }
+            MediaFeature::Range { name, start, end } => {
+                let dev_value = lookup(name)?;
+                let dev = number(dev_value)?;
+
+                if let Some(b) = start {
+                    let v = resolve_value(dev_value, b.value, ctx)?;
+                    if dev < v || (!b.inclusive && dev == v) {
+                        return Some(false);
+                    }
+                }
+
+                if let Some(b) = end {
+                    let v = resolve_value(dev_value, b.value, ctx)?;
+                    if dev > v || (!b.inclusive && dev == v) {
+                        return Some(false);
+                    }
+                }
+
+                Some(true)
+            }
+        }
+    }
+}
+
+fn device_value(device: &Device, name: &str) -> Option<DeviceValue> {
+    let name = name.to_ascii_lowercase();
+    let v = match name.as_str() {
+        "width" | "device-width" => DeviceValue::Length(device.width),
+        "height" | "device-height" => DeviceValue::Length(device.height),
+        "aspect-ratio" | "device-aspect-ratio" => {
+            if device.height == 0.0 {
+                return None;
+            }
+
+            DeviceValue::Ratio(device.width / device.height)
+        }
+        "orientation" => {
+            let v = if device.height >= device.width { "portrait" } else { "landscape" };
+            DeviceValue::Ident(v, true)
+        }
+        "resolution" => DeviceValue::Resolution(device.resolution),
+        "color" => DeviceValue::Integer(device.color_bits as f32),
+        "monochrome" => DeviceValue::Integer(device.monochrome_bits as f32),
+        "color-index" | "grid" => DeviceValue::Integer(0.0),
+        "prefers-color-scheme" => {
+            let v = match device.color_scheme {
+                ColorScheme::Light => "light",
+                ColorScheme::Dark => "dark",
+            };
+            DeviceValue::Ident(v, true)
+        }
+        "prefers-reduced-motion" => {
+            if device.reduced_motion {
+                DeviceValue::Ident("reduce", true)
+            } else {
+                DeviceValue::Ident("no-preference", false)
+            }
+        }
+        "pointer" => DeviceValue::Ident(device.pointer.name(), device.pointer != Pointer::None),
+        "any-pointer" => {
+            DeviceValue::Ident(device.any_pointer.name(), device.any_pointer != Pointer::None)
+        }
+        "hover" => DeviceValue::Ident(if device.hover { "hover" } else { "none" }, device.hover),
+        "any-hover" => {
+            DeviceValue::Ident(if device.any_hover { "hover" } else { "none" }, device.any_hover)
+        }
+        "update" => {
+            if device.media_type == MediaType::Print {
+                DeviceValue::Ident("none", false)
+            } else {
+                DeviceValue::Ident("fast", true)
+            }
+        }
+        _ => return None,
+    };
+
+    Some(v)
+}
+
+fn number(v: DeviceValue) -> Option<f32> {
+    match v {
+        DeviceValue::Length(n)
+        | DeviceValue::Ratio(n)
+        | DeviceValue::Resolution(n)
+        | DeviceValue::Integer(n) => Some(n),
+        DeviceValue::Ident(..) => None,
+    }
+}
+
+/// Converts a query value into the units of the device value.
+fn resolve_value(dev: DeviceValue, value: MediaValue, ctx: &CalcContext) -> Option<f32> {
+    match (dev, value) {
+        // Only a unitless zero is a length.
+        (DeviceValue::Length(_), MediaValue::Number(n)) => (n == 0.0).then_some(0.0),
+        (DeviceValue::Length(_), MediaValue::Dimension(n, unit)) => {
+            match CalcNode::Dimension(n, Unit::from_name(unit)?).eval(ctx)? {
+                CalcValue::Length(px) => Some(px),
+                _ => None,
+            }
+        }
+        (DeviceValue::Ratio(_), MediaValue::Ratio(a, b)) if b != 0.0 => Some(a / b),
+        (DeviceValue::Ratio(_), MediaValue::Number(n)) => Some(n),
+        (DeviceValue::Resolution(_), MediaValue::Dimension(n, unit)) => {
+            let unit = unit.to_ascii_lowercase();
+            match unit.as_str() {
+                "dppx" | "x" => Some(n),
+                "dpi" => Some(n / 96.0),
+                "dpcm" => Some(n * 2.54 / 96.0),
+                _ => None,
+            }
+        }
+        (DeviceValue::Integer(_), MediaValue::Number(n)) if n.fract() == 0.0 => Some(n),
+        _ => None,
+    }
+}
+
+impl<'a> MediaType<'a> {
+    fn from_name(name: &'a str) -> MediaType<'a> {
+        if name.eq_ignore_ascii_case("all") {
+            MediaType::All
+        } else if name.eq_ignore_ascii_case("screen") {
+            MediaType::Screen
+        } else if name.eq_ignore_ascii_case("print") {
+            MediaType::Print
+        } else {
+            MediaType::Other(name)
+        }
+    }
+}
+
+fn parse_query<'a>(s: &mut Stream<'a>) -> Result<MediaQuery<'a>, Error> {
+    s.skip_spaces();
+
+    let starts_with_condition = s.is_char_eq(b'(')? || {
+        // `not (...)` is a condition, while `not screen` is a qualifier.
+        let mut tmp = *s;
+        tmp.try_consume_keyword("not") && {
+            tmp.skip_spaces();
+            tmp.curr_char() == Ok(b'(')
+        }
+    } || {
+        // A function, like `foo(...)`.
+        let mut tmp = *s;
+        tmp.consume_ident().is_ok() && tmp.curr_char() == Ok(b'(')
+    };
+
+    if starts_with_condition {
+        return Ok(MediaQuery {
+            qualifier: None,
+            media_type: None,
+            condition: Some(parse_condition(s, true)?),
+        });
+    }
+
+    let qualifier = if s.try_consume_keyword("not") {
+        Some(MediaQualifier::Not)
+    } else if s.try_consume_keyword("only") {
+        Some(MediaQualifier::Only)
+    } else {
+        None
+    };
+    s.skip_spaces();
+
+    let type_pos = s.gen_error_pos();
+    let name = s.consume_ident()?;
+    if ["not", "only", "and", "or", "layer"].iter().any(|k| name.eq_ignore_ascii_case(k)) {
+        return Err(Error::UnknownToken(type_pos));
+    }
+
+    let mut condition = None;
+    let mut tmp = *s;
+    tmp.skip_spaces();
+    if tmp.try_consume_keyword("and") {
+        *s = tmp;
+        // `or` is not allowed after a media type.
+        condition = Some(parse_condition(s, false)?);
+    }
+
+    Ok(MediaQuery {
+        qualifier,
+        media_type: Some(MediaType::from_name(name)),
+        condition,
+    })
+}
+
+pub(crate) fn parse_condition<'a>(s: &mut Stream<'a>, allow_or: bool) -> Result<MediaCondition<'a>, Error> {
+    s.skip_spaces();
+
+    if s.try_consume_keyword("not") {
+        s.skip_spaces();
+        return Ok(MediaCondition::Not(Box::new(parse_in_parens(s)?)));
+    }
+
+    let first = parse_in_parens(s)?;
+    let mut list = vec![first];
+    let mut is_or = None;
+
+    loop {
+        let mut tmp = *s;
+        tmp.skip_spaces();
+        let op_pos = tmp.gen_error_pos();
+
+        let next_is_or = if tmp.try_consume_keyword("and") {
+            false
+        } else if tmp.try_consume_keyword("or") {
+            if !allow_or {
+                return Err(Error::UnknownToken(op_pos));
+            }
+            true
+        } else {
+            break;
+        };
+
+        // `and` and `or` can't be mixed without parentheses.
+        if is_or.is_some() && is_or != Some(next_is_or) {
+            return Err(Error::UnknownToken(op_pos));
+        }
+        is_or = Some(next_is_or);
+
+        *s = tmp;
+        s.skip_spaces();
+        list.push(parse_in_parens(s)?);
+    }
+
+    Ok(match is_or {
+        None => list.pop().unwrap(),
+        Some(false) => MediaCondition::And(list),
+        Some(true) => MediaCondition::Or(list),
+    })
+}
+
+fn parse_in_parens<'a>(s: &mut Stream<'a>) -> Result<MediaCondition<'a>, Error> {
+    if !s.is_char_eq(b'(')? {
+        // A function is a `<general-enclosed>`.
+        let mut tmp = *s;
+        if tmp.consume_ident().is_ok() && tmp.curr_char() == Ok(b'(') {
+            return consume_general_enclosed(s);
+        }
+
+        return Err(Error::UnknownToken(s.gen_error_pos()));
+    }
+
+    let start = *s;
+    s.advance_raw(1);
+    s.skip_spaces();
+
+    let is_nested = s.is_char_eq(b'(')? || {
+        let mut tmp = *s;
+        tmp.try_consume_keyword("not") && {
+            tmp.skip_spaces();
+            tmp.curr_char() == Ok(b'(')
+        }
+    };
+
+    let result = if is_nested {
+        parse_condition(s, true)
+    } else {
+        parse_feature(s).map(MediaCondition::Feature)
+    };
+
+    if let Ok(condition) = result {
+        s.skip_spaces();
+        if s.curr_char() == Ok(b')') {
+            s.advance_raw(1);
+            return Ok(condition);
+        }
+    }
+
+    // Anything else in parentheses is a `<general-enclosed>`.
+    *s = start;
+    consume_general_enclosed(s)
+}
+
+fn consume_general_enclosed<'a>(s: &mut Stream<'a>) -> Result<MediaCondition<'a>, Error> {
+    let start = s.pos();
+
+    if !s.is_char_eq(b'(')? {
+        s.consume_ident()?;
+    }
+    s.consume_parenthesized()?;
+
+    Ok(MediaCondition::Unknown(s.slice_region_raw_str(start, s.pos())))
+}
+
+fn parse_feature<'a>(s: &mut Stream<'a>) -> Result<MediaFeature<'a>, Error> {
+    let first = parse_operand(s)?;
+    s.skip_spaces();
+
+    let pos = s.gen_error_pos();
+    match s.curr_char()? {
+        b')' => {
+            match first {
+                Operand::Name(name) => Ok(MediaFeature::Boolean(name)),
+                Operand::Value(_) => Err(Error::UnknownToken(pos)),
+            }
+        }
+        b':' => {
+            let name = match first {
+                Operand::Name(name) => name,
+                Operand::Value(_) => return Err(Error::UnknownToken(pos)),
+            };
+
+            s.advance_raw(1);
+            s.skip_spaces();
+            let value = match parse_operand(s)? {
+                Operand::Value(v) => v,
+                Operand::Name(ident) => MediaValue::Ident(ident),
+            };
+
+            let bound = Some(MediaBound { value, inclusive: true });
+            if let Some(name) = strip_prefix(name, "min-") {
+                Ok(MediaFeature::Range { name, start: bound, end: None })
+            } else if let Some(name) = strip_prefix(name, "max-") {
+                Ok(MediaFeature::Range { name, start: None, end: bound })
+            } else {
+                Ok(MediaFeature::Plain { name, value })
+            }
+        }
+        _ => parse_range(s, first),
+    }
+}
+
+fn strip_prefix<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    if name.len() > prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix) {
+        Some(&name[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// Parses the range syntax: `name op value`, `value op name` or `value op name op value`.
+fn parse_range<'a>(s: &mut Stream<'a>, first: Operand<'a>) -> Result<MediaFeature<'a>, Error> {
+    let op_pos = s.gen_error_pos();
+    let op1 = parse_op(s)?;
+    s.skip_spaces();
+    let second = parse_operand(s)?;
+
+    let bound = |value, op: Op| MediaBound { value, inclusive: op == Op::Le || op == Op::Ge };
+
+    match (first, second) {
+        (Operand::Name(name), _) => {
+            // `name op value`
+            let value = match second {
+                Operand::Value(v) => v,
+                Operand::Name(ident) => MediaValue::Ident(ident),
+            };
+
+            let b = Some(bound(value, op1));
+            Ok(match op1 {
+                Op::Eq => MediaFeature::Plain { name, value },
+                Op::Lt | Op::Le => MediaFeature::Range { name, start: None, end: b },
+                Op::Gt | Op::Ge => MediaFeature::Range { name, start: b, end: None },
+            })
+        }
+        (Operand::Value(value), Operand::Name(name)) => {
+            let mut tmp = *s;
+            tmp.skip_spaces();
+            let has_second_op = matches!(tmp.curr_char(), Ok(b'<') | Ok(b'>'));
+
+            if !has_second_op {
+                // `value op name`
+                let b = Some(bound(value, op1));
+                return Ok(match op1 {
+                    Op::Eq => MediaFeature::Plain { name, value },
+                    Op::Lt | Op::Le => MediaFeature::Range { name, start: b, end: None },
+                    Op::Gt | Op::Ge => MediaFeature::Range { name, start: None, end: b },
+                });
+            }
+
+            // `value op name op value`
+            *s = tmp;
+            let op2_pos = s.gen_error_pos();
+            let op2 = parse_op(s)?;
+            s.skip_spaces();
+            let value2 = match parse_operand(s)? {
+                Operand::Value(v) => v,
+                Operand::Name(_) => return Err(Error::UnknownToken(op2_pos)),
+            };
+
+            let is_lt = |op| op == Op::Lt || op == Op::Le;
+            let is_gt = |op| op == Op::Gt || op == Op::Ge;
+            if is_lt(op1) && is_lt(op2) {
+                Ok(MediaFeature::Range {
+                    name,
+                    start: Some(bound(value, op1)),
+                    end: Some(bound(value2, op2)),
+                })
+            } else if is_gt(op1) && is_gt(op2) {
+                Ok(MediaFeature::Range {
+                    name,
+                    start: Some(bound(value2, op2)),
+                    end: Some(bound(value, op1)),
+                })
+            } else {
+                Err(Error::UnknownToken(op2_pos))
+            }
+        }
+        _ => Err(Error::UnknownToken(op_pos)),
+    }
+}
+
+fn parse_op(s: &mut Stream) -> Result<Op, Error> {
+    let op = match s.curr_char()? {
+        b'<' => Op::Lt,
+        b'>' => Op::Gt,
+        b'=' => Op::Eq,
+        _ => return Err(Error::UnknownToken(s.gen_error_pos())),
+    };
+    s.advance_raw(1);
+
+    if op != Op::Eq && s.char_at(0) == Some(b'=') {
+        s.advance_raw(1);
+        return Ok(if op == Op::Lt { Op::Le } else { Op::Ge });
+    }
+
+    Ok(op)
+}
+
+fn parse_operand<'a>(s: &mut Stream<'a>) -> Result<Operand<'a>, Error> {
+    let c = s.curr_char()?;
+    let is_ident = c.is_ascii_alphabetic() || c == b'_'
+        || (c == b'-' && s.char_at(1).is_some_and(|c| !c.is_ascii_digit() && c != b'.'));
+
+    if is_ident {
+        return Ok(Operand::Name(s.consume_ident()?));
+    }
+
+    let n = s.consume_number()?;
+
+    if s.char_at(0).is_some_and(|c| c.is_ascii_alphabetic()) {
+        let unit = s.consume_ident()?;
+        return Ok(Operand::Value(MediaValue::Dimension(n, unit)));
+    }
+
+    // A ratio, like `16/9` or `16 / 9`.
+    let mut tmp = *s;
+    tmp.skip_spaces();
+    if tmp.curr_char() == Ok(b'/') {
+        tmp.advance_raw(1);
+        tmp.skip_spaces();
+        let d = tmp.consume_number()?;
+        *s = tmp;
+        return Ok(Operand::Value(MediaValue::Ratio(n, d)));
+    }
+
+    Ok(Operand::Value(MediaValue::Number(n)))
+}
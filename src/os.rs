@@ -0,0 +1,177 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::stream::Stream;
+use crate::error::Error;
+use crate::tokenizer::{Tokenizer, Token};
+
+/// Target operating system of the azul-specific `@os` rule.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum Os {
+    #[allow(missing_docs)]
+    Linux,
+    #[allow(missing_docs)]
+    Windows,
+    #[allow(missing_docs)]
+    MacOs,
+    #[allow(missing_docs)]
+    Ios,
+    #[allow(missing_docs)]
+    Android,
+    /// WebAssembly in a browser.
+    Web,
+}
+
+impl Os {
+    /// Parses an OS name, ASCII case-insensitive.
+    pub fn from_name(name: &str) -> Option<Os> {
+        let list = [
+            ("linux", Os::Linux),
+            ("windows", Os::Windows),
+            ("macos", Os::MacOs),
+            ("ios", Os::Ios),
+            ("android", Os::Android),
+            ("web", Os::Web),
+        ];
+
+        list.iter().find(|(n, _)| name.eq_ignore_ascii_case(n)).map(|(_, os)| *os)
+    }
+}
+
+/// A single entry of an `@os` condition, like `linux` or `not windows`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct OsQuery {
+    #[allow(missing_docs)]
+    pub negated: bool,
+    #[allow(missing_docs)]
+    pub os: Os,
+}
+
+/// An `@os` condition: a comma-separated list of `OsQuery`,
+/// which matches if any of the entries matches.
+///
+/// An entry can be wrapped in parentheses, like `(linux)`.
+#[derive(Clone,PartialEq,Debug)]
+pub struct OsCondition {
+    #[allow(missing_docs)]
+    pub queries: Vec<OsQuery>,
+}
+
+impl OsCondition {
+    /// Parses an `@os` condition, like the `@os` prelude.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` on an unknown OS name.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &str) -> Result<OsCondition, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        let mut queries = Vec::new();
+
+        loop {
+            s.skip_spaces();
+            let negated = s.try_consume_keyword("not");
+            s.skip_spaces();
+
+            let in_parens = s.is_char_eq(b'(')?;
+            if in_parens {
+                s.advance_raw(1);
+                s.skip_spaces();
+            }
+
+            let pos = s.gen_error_pos();
+            let name = s.consume_ident()?;
+            let os = match Os::from_name(name) {
+                Some(os) => os,
+                None => return Err(Error::InvalidValue(pos)),
+            };
+
+            s.skip_spaces();
+            if in_parens {
+                s.consume_char(b')')?;
+                s.skip_spaces();
+            }
+
+            queries.push(OsQuery { negated, os });
+
+            if s.at_end() {
+                break;
+            }
+
+            s.consume_char(b',')?;
+        }
+
+        Ok(OsCondition { queries })
+    }
+
+    /// Checks that the condition matches the target OS.
+    pub fn matches(&self, os: Os) -> bool {
+        self.queries.iter().any(|q| (q.os == os) != q.negated)
+    }
+}
+
+/// A tokenizer wrapper that evaluates `@os` rules for a target OS.
+///
+/// Matching `@os` blocks are unwrapped: the `AtRule`, `BlockStart` and `BlockEnd`
+/// tokens are removed and the contents are returned as if they were written
+/// in the parent block. Non-matching blocks are skipped entirely.
+///
+/// Useful to precompute per-platform stylesheets.
+pub struct OsFilter<'a> {
+    tokenizer: Tokenizer<'a>,
+    os: Os,
+    /// Each entry is `true` if the block is an unwrapped `@os` block.
+    blocks: Vec<bool>,
+}
+
+impl<'a> OsFilter<'a> {
+    /// Constructs a new `OsFilter`.
+    pub fn new(tokenizer: Tokenizer<'a>, os: Os) -> OsFilter<'a> {
+        OsFilter {
+            tokenizer,
+            os,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Parses a next token.
+    ///
+    /// # Errors
+    ///
+    /// Returns tokenizer errors and `OsCondition::parse` errors.
+    pub fn parse_next(&mut self) -> Result<Token<'a>, Error> {
+        loop {
+            let token = self.tokenizer.parse_next()?;
+
+            match token {
+                Token::AtRule(name) if name.eq_ignore_ascii_case("os") => {
+                    let prelude = self.tokenizer.parse_at_rule_prelude()?;
+                    let condition = OsCondition::parse(prelude)
+                        .map_err(|e| e.offset_by(self.tokenizer.error_pos_of(prelude)))?;
+
+                    if self.tokenizer.parse_next()? != Token::BlockStart {
+                        return Err(Error::UnknownToken(self.tokenizer.error_pos_of(prelude)));
+                    }
+
+                    if condition.matches(self.os) {
+                        self.blocks.push(true);
+                    } else {
+                        self.tokenizer.skip_block()?;
+                    }
+                }
+                Token::BlockStart => {
+                    self.blocks.push(false);
+                    return Ok(token);
+                }
+                Token::BlockEnd => {
+                    if self.blocks.pop() != Some(true) {
+                        return Ok(token);
+                    }
+                }
+                _ => return Ok(token),
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::stream::Stream;
+use crate::tokenizer::{Tokenizer, Token};
+use crate::value;
+
+/// A comma-separated list of page selectors of an `@page` rule, like `toc:first, :left`.
+///
+/// https://www.w3.org/TR/css-page-3/#page-selectors
+#[derive(Clone,PartialEq,Debug)]
+pub struct PageSelectorList<'a> {
+    /// Page selectors. Empty for `@page { ... }`, which matches all pages.
+    pub selectors: Vec<PageSelector<'a>>,
+}
+
+/// A single page selector.
+#[derive(Clone,PartialEq,Debug)]
+pub struct PageSelector<'a> {
+    /// Page type name, like `toc`.
+    pub name: Option<&'a str>,
+    /// Page pseudo-classes in the source order.
+    pub pseudo_classes: Vec<PagePseudoClass>,
+}
+
+/// A page pseudo-class.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum PagePseudoClass {
+    /// `:first`
+    First,
+    /// `:left`
+    Left,
+    /// `:right`
+    Right,
+    /// `:blank`
+    Blank,
+}
+
+/// A page to match page selectors against.
+#[derive(Clone,Copy,PartialEq,Default,Debug)]
+pub struct PageContext<'a> {
+    /// Page type name, from the `page` property.
+    pub name: Option<&'a str>,
+    /// Zero-based page index in the document.
+    pub index: usize,
+    /// Whether the page is blank, like a page inserted by a forced break.
+    pub blank: bool,
+    /// Whether the page progression is right-to-left.
+    ///
+    /// In left-to-right documents the first page is a right page.
+    pub right_to_left: bool,
+}
+
+/// A page-margin box.
+///
+/// https://www.w3.org/TR/css-page-3/#margin-boxes
+#[derive(Clone,Copy,PartialEq,Debug)]
+#[allow(missing_docs)]
+pub enum MarginBox {
+    TopLeftCorner,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    TopRightCorner,
+    BottomLeftCorner,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    BottomRightCorner,
+    LeftTop,
+    LeftMiddle,
+    LeftBottom,
+    RightTop,
+    RightMiddle,
+    RightBottom,
+}
+
+/// A margin box rule inside `@page`, like `@top-center { content: "Title" }`.
+#[derive(Clone,PartialEq,Debug)]
+pub struct MarginBoxRule<'a> {
+    /// The margin box.
+    pub margin_box: MarginBox,
+    /// Declarations in the source order.
+    pub declarations: Vec<(&'a str, &'a str)>,
+}
+
+/// A parsed `@page` rule.
+///
+/// https://www.w3.org/TR/css-page-3/#at-page-rule
+#[derive(Clone,PartialEq,Debug)]
+pub struct PageRule<'a> {
+    /// Page selectors.
+    pub selectors: PageSelectorList<'a>,
+    /// Page declarations in the source order.
+    pub declarations: Vec<(&'a str, &'a str)>,
+    /// Margin box rules in the source order.
+    pub margin_boxes: Vec<MarginBoxRule<'a>>,
+}
+
+impl<'a> PageSelectorList<'a> {
+    /// Parses an `@page` prelude.
+    ///
+    /// An empty prelude is parsed as an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` on an unknown pseudo-class or an invalid page name.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<PageSelectorList<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        let mut selectors = Vec::new();
+
+        s.skip_spaces();
+        while !s.at_end() {
+            selectors.push(parse_selector(&mut s)?);
+
+            s.skip_spaces();
+            if s.at_end() {
+                break;
+            }
+
+            s.consume_char(b',')?;
+            s.skip_spaces();
+            if s.at_end() {
+                return Err(Error::UnexpectedEndOfStream(s.gen_error_pos()));
+            }
+        }
+
+        Ok(PageSelectorList { selectors })
+    }
+
+    /// Checks that any of the selectors matches the page.
+    ///
+    /// An empty list matches all pages.
+    pub fn matches(&self, page: &PageContext) -> bool {
+        self.selectors.is_empty() || self.selectors.iter().any(|s| s.matches(page))
+    }
+
+    /// Returns the specificity of the most specific selector that matches the page.
+    ///
+    /// Returns `(0, 0, 0)` for an empty list and `None` if nothing matches.
+    pub fn matching_specificity(&self, page: &PageContext) -> Option<(u32, u32, u32)> {
+        if self.selectors.is_empty() {
+            return Some((0, 0, 0));
+        }
+
+        self.selectors.iter().filter(|s| s.matches(page)).map(|s| s.specificity()).max()
+    }
+}
+
+impl<'a> PageSelector<'a> {
+    /// Returns the selector specificity.
+    ///
+    /// Counts, in order: the page name, `:first` and `:blank`, `:left` and `:right`.
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        let mut specificity = (self.name.is_some() as u32, 0, 0);
+        for pseudo_class in &self.pseudo_classes {
+            match pseudo_class {
+                PagePseudoClass::First | PagePseudoClass::Blank => specificity.1 += 1,
+                PagePseudoClass::Left | PagePseudoClass::Right => specificity.2 += 1,
+            }
+        }
+
+        specificity
+    }
+
+    /// Checks that the selector matches the page.
+    pub fn matches(&self, page: &PageContext) -> bool {
+        if let Some(name) = self.name {
+            if page.name != Some(name) {
+                return false;
+            }
+        }
+
+        let is_right = (page.index % 2 == 0) != page.right_to_left;
+        self.pseudo_classes.iter().all(|pseudo_class| {
+            match pseudo_class {
+                PagePseudoClass::First => page.index == 0,
+                PagePseudoClass::Left => !is_right,
+                PagePseudoClass::Right => is_right,
+                PagePseudoClass::Blank => page.blank,
+            }
+        })
+    }
+}
+
+impl PagePseudoClass {
+    /// Parses a pseudo-class name without `:`. ASCII case-insensitive.
+    pub fn from_name(name: &str) -> Option<PagePseudoClass> {
+        let pseudo_class = match name.to_ascii_lowercase().as_str() {
+            "first" => PagePseudoClass::First,
+            "left" => PagePseudoClass::Left,
+            "right" => PagePseudoClass::Right,
+            "blank" => PagePseudoClass::Blank,
+            _ => return None,
+        };
+
+        Some(pseudo_class)
+    }
+}
+
+impl MarginBox {
+    /// Parses a margin box at-rule name without `@`, like `top-center`. ASCII case-insensitive.
+    pub fn from_name(name: &str) -> Option<MarginBox> {
+        let margin_box = match name.to_ascii_lowercase().as_str() {
+            "top-left-corner" => MarginBox::TopLeftCorner,
+            "top-left" => MarginBox::TopLeft,
+            "top-center" => MarginBox::TopCenter,
+            "top-right" => MarginBox::TopRight,
+            "top-right-corner" => MarginBox::TopRightCorner,
+            "bottom-left-corner" => MarginBox::BottomLeftCorner,
+            "bottom-left" => MarginBox::BottomLeft,
+            "bottom-center" => MarginBox::BottomCenter,
+            "bottom-right" => MarginBox::BottomRight,
+            "bottom-right-corner" => MarginBox::BottomRightCorner,
+            "left-top" => MarginBox::LeftTop,
+            "left-middle" => MarginBox::LeftMiddle,
+            "left-bottom" => MarginBox::LeftBottom,
+            "right-top" => MarginBox::RightTop,
+            "right-middle" => MarginBox::RightMiddle,
+            "right-bottom" => MarginBox::RightBottom,
+            _ => return None,
+        };
+
+        Some(margin_box)
+    }
+
+    /// Returns the at-rule name without `@`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarginBox::TopLeftCorner => "top-left-corner",
+            MarginBox::TopLeft => "top-left",
+            MarginBox::TopCenter => "top-center",
+            MarginBox::TopRight => "top-right",
+            MarginBox::TopRightCorner => "top-right-corner",
+            MarginBox::BottomLeftCorner => "bottom-left-corner",
+            MarginBox::BottomLeft => "bottom-left",
+            MarginBox::BottomCenter => "bottom-center",
+            MarginBox::BottomRight => "bottom-right",
+            MarginBox::BottomRightCorner => "bottom-right-corner",
+            MarginBox::LeftTop => "left-top",
+            MarginBox::LeftMiddle => "left-middle",
+            MarginBox::LeftBottom => "left-bottom",
+            MarginBox::RightTop => "right-top",
+            MarginBox::RightMiddle => "right-middle",
+            MarginBox::RightBottom => "right-bottom",
+        }
+    }
+}
+
+impl<'a> PageRule<'a> {
+    /// Parses a `@page` rule.
+    ///
+    /// Should be called right after `Token::AtRule("page")`.
+    /// Consumes everything up to and including the rule's `Token::BlockEnd`.
+    ///
+    /// Unknown at-rules and nested style rules are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns tokenizer errors and `PageSelectorList::parse` errors.
+    /// Returns `Error::UnknownToken` on a margin box rule with a prelude.
+    pub fn parse(tokenizer: &mut Tokenizer<'a>) -> Result<PageRule<'a>, Error> {
+        let prelude = tokenizer.parse_at_rule_prelude()?;
+        let selectors = PageSelectorList::parse(prelude)
+            .map_err(|e| e.offset_by(tokenizer.error_pos_of(prelude)))?;
+
+        if tokenizer.parse_next()? != Token::BlockStart {
+            return Err(Error::UnknownToken(tokenizer.error_pos_of(prelude)));
+        }
+
+        let mut declarations = Vec::new();
+        let mut margin_boxes = Vec::new();
+        loop {
+            match tokenizer.parse_next()? {
+                Token::Declaration(name, value) | Token::CustomProperty(name, value) => {
+                    declarations.push((name, value));
+                }
+                Token::AtRule(name) => {
+                    let prelude = tokenizer.parse_at_rule_prelude()?;
+                    match MarginBox::from_name(name) {
+                        Some(margin_box) => {
+                            if !prelude.is_empty() || tokenizer.parse_next()? != Token::BlockStart {
+                                return Err(Error::UnknownToken(tokenizer.error_pos_of(prelude)));
+                            }
+
+                            margin_boxes.push(MarginBoxRule {
+                                margin_box,
+                                declarations: tokenizer.parse_declarations(|_, _| true)?,
+                            });
+                        }
+                        None => {
+                            if tokenizer.parse_next()? == Token::BlockStart {
+                                tokenizer.skip_block()?;
+                            }
+                        }
+                    }
+                }
+                Token::BlockStart => tokenizer.skip_block()?,
+                Token::BlockEnd | Token::EndOfStream => break,
+                _ => {}
+            }
+        }
+
+        Ok(PageRule {
+            selectors,
+            declarations,
+            margin_boxes,
+        })
+    }
+}
+
+fn parse_selector<'a>(s: &mut Stream<'a>) -> Result<PageSelector<'a>, Error> {
+    let mut name = None;
+    if s.curr_char_raw() != b':' {
+        let pos = s.gen_error_pos();
+        let ident = s.consume_ident()?;
+        if !value::is_custom_ident(ident) || ident.eq_ignore_ascii_case("auto") {
+            return Err(Error::InvalidValue(pos));
+        }
+        name = Some(ident);
+    }
+
+    let mut pseudo_classes = Vec::new();
+    while !s.at_end() && s.curr_char_raw() == b':' {
+        s.advance_raw(1);
+        let pos = s.gen_error_pos();
+        let ident = s.consume_ident()?;
+        let pseudo_class = PagePseudoClass::from_name(ident).ok_or(Error::InvalidValue(pos))?;
+        pseudo_classes.push(pseudo_class);
+    }
+
+    Ok(PageSelector { name, pseudo_classes })
+}
@@ -0,0 +1,644 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::{CalcNode, CalcType};
+use crate::descriptor;
+use crate::error::{Error, ErrorPos};
+use crate::shorthand::Shorthand;
+use crate::stream::Stream;
+use crate::tokenizer::{Token, Tokenizer};
+use crate::value;
+
+/// Type of a single component value.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ValueType {
+    /// A specific keyword. Case-insensitive.
+    Keyword(&'static str),
+    /// `<length>`, including a unitless zero.
+    Length,
+    /// `<percentage>`
+    Percentage,
+    /// `<length-percentage>`, including a unitless zero.
+    LengthPercentage,
+    /// `<number>`
+    Number,
+    /// `<integer>`
+    Integer,
+    /// `<color>`
+    Color,
+    /// `<time>`
+    Time,
+    /// `<angle>`
+    Angle,
+    /// `<image>`, like `url()` or a gradient.
+    Image,
+    /// `<string>`
+    String,
+    /// `<custom-ident>`
+    CustomIdent,
+    /// `<url>`
+    Url,
+    /// `<resolution>`, like `2dppx` or `96dpi`.
+    Resolution,
+    /// `<transform-function>`, like `rotate(45deg)`.
+    TransformFunction,
+}
+
+/// Value grammar of a property.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum Grammar {
+    /// A single component value of one of the types.
+    Single(&'static [ValueType]),
+    /// Space-separated component values of the types, with a minimum and maximum count.
+    Multiple(&'static [ValueType], usize, usize),
+    /// Comma-separated list of `Multiple`.
+    List(&'static [ValueType], usize, usize),
+    /// A shorthand. Validated by expanding it.
+    Shorthand(Shorthand),
+    /// Any value. Used for properties with grammars that are not validated yet.
+    Any,
+}
+
+/// Information about a known property.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct PropertyInfo {
+    /// Property name.
+    pub name: &'static str,
+    /// Value grammar.
+    pub grammar: Grammar,
+    /// Whether the property is inherited by default.
+    pub inherited: bool,
+}
+
+/// A declaration validation error.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ValidationError {
+    /// Unknown property name.
+    UnknownProperty {
+        /// A known property with a similar name, if any.
+        suggestion: Option<&'static str>,
+    },
+    /// Value doesn't match the property grammar.
+    InvalidValue,
+}
+
+/// Declaration validation mode.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ValidationMode {
+    /// Return an error on the first invalid declaration.
+    Strict,
+    /// Collect warnings and return all tokens as is.
+    Warn,
+}
+
+/// A validation warning collected in `ValidationMode::Warn`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct ValidationWarning<'a> {
+    /// Position of the property name.
+    pub pos: ErrorPos,
+    /// Property name.
+    pub name: &'a str,
+    /// Property value.
+    pub value: &'a str,
+    /// The validation error.
+    pub error: ValidationError,
+}
+
+/// Returns information about a known property.
+///
+/// Property names are case-insensitive.
+pub fn lookup_property(name: &str) -> Option<&'static PropertyInfo> {
+    let name = name.to_ascii_lowercase();
+    let idx = PROPERTIES.binary_search_by(|p| p.name.cmp(name.as_str())).ok()?;
+    Some(&PROPERTIES[idx])
+}
+
+/// Returns a known property with a name similar to `name`, if any.
+///
+/// Useful for reporting typos, like `colr` instead of `color`.
+pub fn suggest_property(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    let max_distance = if name.len() <= 3 { 1 } else { 2 };
+
+    let mut best = None;
+    for p in PROPERTIES.iter() {
+        let d = edit_distance(&name, p.name);
+        if d <= max_distance && best.map_or(true, |(bd, _)| d < bd) {
+            best = Some((d, p.name));
+        }
+    }
+
+    best.map(|(_, name)| name)
+}
+
+/// Validates a declaration against the known property grammars.
+///
+/// Custom properties, values with `var()` and CSS-wide keywords are always valid.
+/// A trailing `!important` is ignored.
+pub fn validate_declaration(name: &str, value: &str) -> Result<(), ValidationError> {
+    if name.starts_with("--") {
+        return Ok(());
+    }
+
+    let info = match lookup_property(name) {
+        Some(info) => info,
+        None => return Err(ValidationError::UnknownProperty { suggestion: suggest_property(name) }),
+    };
+
+    let value = strip_important(value);
+    if value::find_var(value).is_some() || value::is_css_wide_keyword(value.trim()) {
+        return Ok(());
+    }
+
+    let is_valid = match info.grammar {
+        Grammar::Single(types) => {
+            let parts = value::split_components(value);
+            parts.len() == 1 && matches_any(parts[0], types)
+        }
+        Grammar::Multiple(types, min, max) => matches_multiple(value, types, min, max),
+        Grammar::List(types, min, max) => {
+            value::split_list(value).iter().all(|v| matches_multiple(v, types, min, max))
+        }
+        Grammar::Shorthand(shorthand) => shorthand.expand(value).is_ok(),
+        Grammar::Any => !value.trim().is_empty(),
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidValue)
+    }
+}
+
+/// A tokenizer wrapper that validates declarations.
+///
+/// Declarations inside descriptor at-rules, like `@font-face`, are not validated,
+/// since they contain descriptors and not properties. The same applies to `@page`
+/// descriptors, like `size`.
+pub struct Validator<'a> {
+    tokenizer: Tokenizer<'a>,
+    mode: ValidationMode,
+    warnings: Vec<ValidationWarning<'a>>,
+    blocks: Vec<BlockKind>,
+    at_rule: Option<&'a str>,
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum BlockKind {
+    /// Declarations are validated.
+    Properties,
+    /// A `@page` block, which contains both properties and descriptors.
+    Page,
+    /// A descriptor at-rule block. Declarations are not validated.
+    Descriptors,
+}
+
+impl<'a> Validator<'a> {
+    /// Constructs a new `Validator`.
+    pub fn new(tokenizer: Tokenizer<'a>, mode: ValidationMode) -> Validator<'a> {
+        Validator {
+            tokenizer,
+            mode,
+            warnings: Vec::new(),
+            blocks: Vec::new(),
+            at_rule: None,
+        }
+    }
+
+    /// Returns warnings collected so far.
+    pub fn warnings(&self) -> &[ValidationWarning<'a>] {
+        &self.warnings
+    }
+
+    /// Parses a next token.
+    ///
+    /// # Errors
+    ///
+    /// In `ValidationMode::Strict`, returns `Error::UnknownProperty` pointing at the
+    /// property name or `Error::InvalidValue` pointing at the value.
+    pub fn parse_next(&mut self) -> Result<Token<'a>, Error> {
+        let token = self.tokenizer.parse_next()?;
+
+        match token {
+            Token::AtRule(name) => self.at_rule = Some(name),
+            Token::AtRuleEnd => self.at_rule = None,
+            Token::BlockStart => {
+                let parent = self.blocks.last().copied().unwrap_or(BlockKind::Properties);
+                let kind = match self.at_rule.take() {
+                    _ if parent == BlockKind::Descriptors => BlockKind::Descriptors,
+                    Some(name) if descriptor::is_descriptor_at_rule(name) => BlockKind::Descriptors,
+                    Some(name) if name.eq_ignore_ascii_case("page") => BlockKind::Page,
+                    _ => BlockKind::Properties,
+                };
+                self.blocks.push(kind);
+            }
+            Token::BlockEnd => {
+                self.blocks.pop();
+            }
+            Token::Declaration(name, value) if self.is_validated(name) => {
+                if let Err(error) = validate_declaration(name, value) {
+                    match self.mode {
+                        ValidationMode::Strict => {
+                            return Err(match error {
+                                ValidationError::UnknownProperty { .. } => {
+                                    Error::UnknownProperty(self.tokenizer.error_pos_of(name))
+                                }
+                                ValidationError::InvalidValue => {
+                                    Error::InvalidValue(self.tokenizer.error_pos_of(value))
+                                }
+                            });
+                        }
+                        ValidationMode::Warn => {
+                            self.warnings.push(ValidationWarning {
+                                pos: self.tokenizer.error_pos_of(name),
+                                name,
+                                value,
+                                error,
+                            });
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(token)
+    }
+
+    fn is_validated(&self, name: &str) -> bool {
+        match self.blocks.last() {
+            Some(BlockKind::Descriptors) => false,
+            Some(BlockKind::Page) => {
+                !value::is_keyword(name, &["size", "marks", "bleed", "page-orientation"])
+            }
+            _ => true,
+        }
+    }
+}
+
+fn strip_important(value: &str) -> &str {
+    if let Some(idx) = value.rfind('!') {
+        if value[idx + 1..].trim().eq_ignore_ascii_case("important") {
+            return &value[..idx];
+        }
+    }
+
+    value
+}
+
+fn matches_multiple(value: &str, types: &[ValueType], min: usize, max: usize) -> bool {
+    let parts = value::split_components(value);
+    parts.len() >= min && parts.len() <= max && parts.iter().all(|p| matches_any(p, types))
+}
+
+fn matches_any(text: &str, types: &[ValueType]) -> bool {
+    types.iter().any(|t| matches_type(text, *t))
+}
+
+pub(crate) fn matches_type(text: &str, t: ValueType) -> bool {
+    match t {
+        ValueType::Keyword(k) => text.eq_ignore_ascii_case(k),
+        ValueType::Length => value::is_length(text),
+        ValueType::Percentage => value::calc_type(text) == Some(CalcType::Percentage),
+        ValueType::LengthPercentage => value::is_length_percentage(text),
+        ValueType::Number => value::is_number(text),
+        ValueType::Integer => {
+            match CalcNode::parse(text) {
+                Ok(CalcNode::Number(n)) => n.fract() == 0.0,
+                _ => false,
+            }
+        }
+        ValueType::Color => value::is_color(text),
+        ValueType::Time => value::is_time(text),
+        ValueType::Angle => value::calc_type(text) == Some(CalcType::Angle),
+        ValueType::Image => value::is_image(text) && !text.eq_ignore_ascii_case("none"),
+        ValueType::String => {
+            text.len() >= 2
+                && (text.starts_with('"') && text.ends_with('"')
+                    || text.starts_with('\'') && text.ends_with('\''))
+        }
+        ValueType::CustomIdent => value::is_custom_ident(text),
+        ValueType::Url => function_name(text).is_some_and(|name| name.eq_ignore_ascii_case("url")),
+        ValueType::Resolution => {
+            let mut s = Stream::new(text.as_bytes());
+            s.consume_number().is_ok()
+                && value::is_keyword(&text[s.pos()..], &["dpi", "dpcm", "dppx", "x"])
+        }
+        ValueType::TransformFunction => {
+            function_name(text).is_some_and(|name| value::is_keyword(name, &[
+                "matrix", "matrix3d", "translate", "translate3d", "translatex", "translatey",
+                "translatez", "scale", "scale3d", "scalex", "scaley", "scalez", "rotate",
+                "rotate3d", "rotatex", "rotatey", "rotatez", "skew", "skewx", "skewy",
+                "perspective",
+            ]))
+        }
+    }
+}
+
+/// Returns the name of a function, like `url` in `url(a.png)`.
+fn function_name(text: &str) -> Option<&str> {
+    match text.find('(') {
+        Some(idx) if text.ends_with(')') && value::is_custom_ident(&text[..idx]) => Some(&text[..idx]),
+        _ => None,
+    }
+}
+
+/// Edit distance, where a transposition of two adjacent chars counts as a single edit.
+///
+/// https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+use self::ValueType::{Keyword as K, *};
+
+static ALIGN: [ValueType; 18] = [
+    K("normal"), K("stretch"), K("center"), K("start"), K("end"), K("flex-start"),
+    K("flex-end"), K("self-start"), K("self-end"), K("left"), K("right"), K("baseline"),
+    K("first"), K("last"), K("safe"), K("unsafe"), K("space-between"), K("space-around"),
+];
+static ANIMATION_NAME: [ValueType; 3] = [K("none"), CustomIdent, String];
+static TIME: [ValueType; 1] = [Time];
+static ITERATION_COUNT: [ValueType; 2] = [K("infinite"), Number];
+static ANIMATION_DIRECTION: [ValueType; 4] = [
+    K("normal"), K("reverse"), K("alternate"), K("alternate-reverse"),
+];
+static FILL_MODE: [ValueType; 4] = [K("none"), K("forwards"), K("backwards"), K("both")];
+static PLAY_STATE: [ValueType; 2] = [K("running"), K("paused")];
+static COLOR: [ValueType; 1] = [Color];
+static CONTAINER_NAME: [ValueType; 2] = [K("none"), CustomIdent];
+static CONTAINER_TYPE: [ValueType; 3] = [K("normal"), K("size"), K("inline-size")];
+static IMAGE: [ValueType; 2] = [K("none"), Image];
+static POSITION: [ValueType; 6] = [
+    K("left"), K("right"), K("top"), K("bottom"), K("center"), LengthPercentage,
+];
+static BACKGROUND_SIZE: [ValueType; 4] = [K("auto"), K("cover"), K("contain"), LengthPercentage];
+static BACKGROUND_REPEAT: [ValueType; 6] = [
+    K("repeat-x"), K("repeat-y"), K("repeat"), K("space"), K("round"), K("no-repeat"),
+];
+static BACKGROUND_ATTACHMENT: [ValueType; 3] = [K("scroll"), K("fixed"), K("local")];
+static BOX: [ValueType; 4] = [K("border-box"), K("padding-box"), K("content-box"), K("text")];
+static LINE_WIDTH: [ValueType; 4] = [K("thin"), K("medium"), K("thick"), Length];
+static LINE_STYLE: [ValueType; 10] = [
+    K("none"), K("hidden"), K("dotted"), K("dashed"), K("solid"), K("double"), K("groove"),
+    K("ridge"), K("inset"), K("outset"),
+];
+static LENGTH: [ValueType; 1] = [Length];
+static LENGTH_PERCENTAGE: [ValueType; 1] = [LengthPercentage];
+static LENGTH_PERCENTAGE_AUTO: [ValueType; 2] = [K("auto"), LengthPercentage];
+static BORDER_COLLAPSE: [ValueType; 2] = [K("collapse"), K("separate")];
+static BOX_SIZING: [ValueType; 2] = [K("content-box"), K("border-box")];
+static CLEAR: [ValueType; 6] = [
+    K("none"), K("left"), K("right"), K("both"), K("inline-start"), K("inline-end"),
+];
+static GAP: [ValueType; 2] = [K("normal"), LengthPercentage];
+static DISPLAY: [ValueType; 20] = [
+    K("none"), K("contents"), K("block"), K("inline"), K("inline-block"), K("flow"),
+    K("flow-root"), K("flex"), K("inline-flex"), K("grid"), K("inline-grid"), K("table"),
+    K("inline-table"), K("table-row"), K("table-cell"), K("list-item"), K("run-in"),
+    K("table-row-group"), K("table-column"), K("table-caption"),
+];
+static FLEX_BASIS: [ValueType; 6] = [
+    K("auto"), K("content"), K("min-content"), K("max-content"), K("fit-content"),
+    LengthPercentage,
+];
+static FLEX_DIRECTION: [ValueType; 4] = [
+    K("row"), K("row-reverse"), K("column"), K("column-reverse"),
+];
+static FLEX_WRAP: [ValueType; 3] = [K("nowrap"), K("wrap"), K("wrap-reverse")];
+static FLEX_FLOW: [ValueType; 7] = [
+    K("row"), K("row-reverse"), K("column"), K("column-reverse"), K("nowrap"), K("wrap"),
+    K("wrap-reverse"),
+];
+static NUMBER: [ValueType; 1] = [Number];
+static FLOAT: [ValueType; 5] = [
+    K("none"), K("left"), K("right"), K("inline-start"), K("inline-end"),
+];
+static FONT_SIZE: [ValueType; 11] = [
+    K("xx-small"), K("x-small"), K("small"), K("medium"), K("large"), K("x-large"),
+    K("xx-large"), K("xxx-large"), K("larger"), K("smaller"), LengthPercentage,
+];
+static FONT_STRETCH: [ValueType; 10] = [
+    K("normal"), K("ultra-condensed"), K("extra-condensed"), K("condensed"),
+    K("semi-condensed"), K("semi-expanded"), K("expanded"), K("extra-expanded"),
+    K("ultra-expanded"), Percentage,
+];
+static FONT_STYLE: [ValueType; 4] = [K("normal"), K("italic"), K("oblique"), Angle];
+static FONT_WEIGHT: [ValueType; 5] = [K("normal"), K("bold"), K("bolder"), K("lighter"), Number];
+static SIZE: [ValueType; 5] = [
+    K("auto"), K("min-content"), K("max-content"), K("fit-content"), LengthPercentage,
+];
+static MAX_SIZE: [ValueType; 5] = [
+    K("none"), K("min-content"), K("max-content"), K("fit-content"), LengthPercentage,
+];
+static SPACING: [ValueType; 2] = [K("normal"), Length];
+static LINE_HEIGHT: [ValueType; 3] = [K("normal"), Number, LengthPercentage];
+static LIST_STYLE_POSITION: [ValueType; 2] = [K("inside"), K("outside")];
+static ALPHA: [ValueType; 2] = [Number, Percentage];
+static INTEGER: [ValueType; 1] = [Integer];
+static OVERFLOW: [ValueType; 5] = [K("visible"), K("hidden"), K("clip"), K("scroll"), K("auto")];
+static OVERFLOW_WRAP: [ValueType; 3] = [K("normal"), K("break-word"), K("anywhere")];
+static POSITION_KEYWORDS: [ValueType; 5] = [
+    K("static"), K("relative"), K("absolute"), K("fixed"), K("sticky"),
+];
+static TEXT_ALIGN: [ValueType; 8] = [
+    K("start"), K("end"), K("left"), K("right"), K("center"), K("justify"), K("match-parent"),
+    K("justify-all"),
+];
+static TEXT_OVERFLOW: [ValueType; 3] = [K("clip"), K("ellipsis"), String];
+static TEXT_TRANSFORM: [ValueType; 5] = [
+    K("none"), K("capitalize"), K("uppercase"), K("lowercase"), K("full-width"),
+];
+static CUSTOM_IDENT: [ValueType; 1] = [CustomIdent];
+static USER_SELECT: [ValueType; 5] = [K("auto"), K("text"), K("none"), K("contain"), K("all")];
+static VERTICAL_ALIGN: [ValueType; 9] = [
+    K("baseline"), K("sub"), K("super"), K("text-top"), K("text-bottom"), K("middle"),
+    K("top"), K("bottom"), LengthPercentage,
+];
+static VISIBILITY: [ValueType; 3] = [K("visible"), K("hidden"), K("collapse")];
+static WHITE_SPACE: [ValueType; 6] = [
+    K("normal"), K("pre"), K("nowrap"), K("pre-wrap"), K("pre-line"), K("break-spaces"),
+];
+static WORD_BREAK: [ValueType; 4] = [
+    K("normal"), K("break-all"), K("keep-all"), K("break-word"),
+];
+static Z_INDEX: [ValueType; 2] = [K("auto"), Integer];
+
+const fn property(name: &'static str, grammar: Grammar, inherited: bool) -> PropertyInfo {
+    PropertyInfo { name, grammar, inherited }
+}
+
+// Must be kept sorted by name.
+static PROPERTIES: &[PropertyInfo] = &[
+    property("align-content", Grammar::Multiple(&ALIGN, 1, 2), false),
+    property("align-items", Grammar::Multiple(&ALIGN, 1, 2), false),
+    property("align-self", Grammar::Multiple(&ALIGN, 1, 2), false),
+    property("animation", Grammar::Shorthand(Shorthand::Animation), false),
+    property("animation-delay", Grammar::List(&TIME, 1, 1), false),
+    property("animation-direction", Grammar::List(&ANIMATION_DIRECTION, 1, 1), false),
+    property("animation-duration", Grammar::List(&TIME, 1, 1), false),
+    property("animation-fill-mode", Grammar::List(&FILL_MODE, 1, 1), false),
+    property("animation-iteration-count", Grammar::List(&ITERATION_COUNT, 1, 1), false),
+    property("animation-name", Grammar::List(&ANIMATION_NAME, 1, 1), false),
+    property("animation-play-state", Grammar::List(&PLAY_STATE, 1, 1), false),
+    property("animation-timing-function", Grammar::Any, false),
+    property("background", Grammar::Shorthand(Shorthand::Background), false),
+    property("background-attachment", Grammar::List(&BACKGROUND_ATTACHMENT, 1, 1), false),
+    property("background-clip", Grammar::List(&BOX, 1, 1), false),
+    property("background-color", Grammar::Single(&COLOR), false),
+    property("background-image", Grammar::List(&IMAGE, 1, 1), false),
+    property("background-origin", Grammar::List(&BOX, 1, 1), false),
+    property("background-position", Grammar::List(&POSITION, 1, 4), false),
+    property("background-repeat", Grammar::List(&BACKGROUND_REPEAT, 1, 2), false),
+    property("background-size", Grammar::List(&BACKGROUND_SIZE, 1, 2), false),
+    property("border", Grammar::Shorthand(Shorthand::Border), false),
+    property("border-bottom", Grammar::Shorthand(Shorthand::BorderBottom), false),
+    property("border-bottom-color", Grammar::Single(&COLOR), false),
+    property("border-bottom-left-radius", Grammar::Multiple(&LENGTH_PERCENTAGE, 1, 2), false),
+    property("border-bottom-right-radius", Grammar::Multiple(&LENGTH_PERCENTAGE, 1, 2), false),
+    property("border-bottom-style", Grammar::Single(&LINE_STYLE), false),
+    property("border-bottom-width", Grammar::Single(&LINE_WIDTH), false),
+    property("border-collapse", Grammar::Single(&BORDER_COLLAPSE), true),
+    property("border-color", Grammar::Shorthand(Shorthand::BorderColor), false),
+    property("border-left", Grammar::Shorthand(Shorthand::BorderLeft), false),
+    property("border-left-color", Grammar::Single(&COLOR), false),
+    property("border-left-style", Grammar::Single(&LINE_STYLE), false),
+    property("border-left-width", Grammar::Single(&LINE_WIDTH), false),
+    property("border-radius", Grammar::Shorthand(Shorthand::BorderRadius), false),
+    property("border-right", Grammar::Shorthand(Shorthand::BorderRight), false),
+    property("border-right-color", Grammar::Single(&COLOR), false),
+    property("border-right-style", Grammar::Single(&LINE_STYLE), false),
+    property("border-right-width", Grammar::Single(&LINE_WIDTH), false),
+    property("border-spacing", Grammar::Multiple(&LENGTH, 1, 2), true),
+    property("border-style", Grammar::Shorthand(Shorthand::BorderStyle), false),
+    property("border-top", Grammar::Shorthand(Shorthand::BorderTop), false),
+    property("border-top-color", Grammar::Single(&COLOR), false),
+    property("border-top-left-radius", Grammar::Multiple(&LENGTH_PERCENTAGE, 1, 2), false),
+    property("border-top-right-radius", Grammar::Multiple(&LENGTH_PERCENTAGE, 1, 2), false),
+    property("border-top-style", Grammar::Single(&LINE_STYLE), false),
+    property("border-top-width", Grammar::Single(&LINE_WIDTH), false),
+    property("border-width", Grammar::Shorthand(Shorthand::BorderWidth), false),
+    property("bottom", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("box-shadow", Grammar::Any, false),
+    property("box-sizing", Grammar::Single(&BOX_SIZING), false),
+    property("clear", Grammar::Single(&CLEAR), false),
+    property("color", Grammar::Single(&COLOR), true),
+    property("column-gap", Grammar::Single(&GAP), false),
+    property("container", Grammar::Shorthand(Shorthand::Container), false),
+    property("container-name", Grammar::Multiple(&CONTAINER_NAME, 1, usize::MAX), false),
+    property("container-type", Grammar::Single(&CONTAINER_TYPE), false),
+    property("content", Grammar::Any, false),
+    property("cursor", Grammar::Any, true),
+    property("display", Grammar::Multiple(&DISPLAY, 1, 2), false),
+    property("filter", Grammar::Any, false),
+    property("flex", Grammar::Shorthand(Shorthand::Flex), false),
+    property("flex-basis", Grammar::Single(&FLEX_BASIS), false),
+    property("flex-direction", Grammar::Single(&FLEX_DIRECTION), false),
+    property("flex-flow", Grammar::Multiple(&FLEX_FLOW, 1, 2), false),
+    property("flex-grow", Grammar::Single(&NUMBER), false),
+    property("flex-shrink", Grammar::Single(&NUMBER), false),
+    property("flex-wrap", Grammar::Single(&FLEX_WRAP), false),
+    property("float", Grammar::Single(&FLOAT), false),
+    property("font", Grammar::Shorthand(Shorthand::Font), true),
+    property("font-family", Grammar::Any, true),
+    property("font-size", Grammar::Single(&FONT_SIZE), true),
+    property("font-stretch", Grammar::Single(&FONT_STRETCH), true),
+    property("font-style", Grammar::Multiple(&FONT_STYLE, 1, 2), true),
+    property("font-variant", Grammar::Any, true),
+    property("font-weight", Grammar::Single(&FONT_WEIGHT), true),
+    property("gap", Grammar::Shorthand(Shorthand::Gap), false),
+    property("grid-area", Grammar::Any, false),
+    property("grid-auto-columns", Grammar::Any, false),
+    property("grid-auto-flow", Grammar::Any, false),
+    property("grid-auto-rows", Grammar::Any, false),
+    property("grid-column", Grammar::Any, false),
+    property("grid-row", Grammar::Any, false),
+    property("grid-template-areas", Grammar::Any, false),
+    property("grid-template-columns", Grammar::Any, false),
+    property("grid-template-rows", Grammar::Any, false),
+    property("height", Grammar::Single(&SIZE), false),
+    property("inset", Grammar::Shorthand(Shorthand::Inset), false),
+    property("justify-content", Grammar::Multiple(&ALIGN, 1, 2), false),
+    property("justify-items", Grammar::Multiple(&ALIGN, 1, 2), false),
+    property("justify-self", Grammar::Multiple(&ALIGN, 1, 2), false),
+    property("left", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("letter-spacing", Grammar::Single(&SPACING), true),
+    property("line-height", Grammar::Single(&LINE_HEIGHT), true),
+    property("list-style-image", Grammar::Single(&IMAGE), true),
+    property("list-style-position", Grammar::Single(&LIST_STYLE_POSITION), true),
+    property("list-style-type", Grammar::Any, true),
+    property("margin", Grammar::Shorthand(Shorthand::Margin), false),
+    property("margin-bottom", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("margin-left", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("margin-right", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("margin-top", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("max-height", Grammar::Single(&MAX_SIZE), false),
+    property("max-width", Grammar::Single(&MAX_SIZE), false),
+    property("min-height", Grammar::Single(&SIZE), false),
+    property("min-width", Grammar::Single(&SIZE), false),
+    property("opacity", Grammar::Single(&ALPHA), false),
+    property("order", Grammar::Single(&INTEGER), false),
+    property("outline", Grammar::Any, false),
+    property("outline-color", Grammar::Single(&COLOR), false),
+    property("outline-offset", Grammar::Single(&LENGTH), false),
+    property("outline-style", Grammar::Single(&LINE_STYLE), false),
+    property("outline-width", Grammar::Single(&LINE_WIDTH), false),
+    property("overflow", Grammar::Multiple(&OVERFLOW, 1, 2), false),
+    property("overflow-wrap", Grammar::Single(&OVERFLOW_WRAP), true),
+    property("overflow-x", Grammar::Single(&OVERFLOW), false),
+    property("overflow-y", Grammar::Single(&OVERFLOW), false),
+    property("padding", Grammar::Shorthand(Shorthand::Padding), false),
+    property("padding-bottom", Grammar::Single(&LENGTH_PERCENTAGE), false),
+    property("padding-left", Grammar::Single(&LENGTH_PERCENTAGE), false),
+    property("padding-right", Grammar::Single(&LENGTH_PERCENTAGE), false),
+    property("padding-top", Grammar::Single(&LENGTH_PERCENTAGE), false),
+    property("pointer-events", Grammar::Any, true),
+    property("position", Grammar::Single(&POSITION_KEYWORDS), false),
+    property("right", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("row-gap", Grammar::Single(&GAP), false),
+    property("text-align", Grammar::Single(&TEXT_ALIGN), true),
+    property("text-decoration", Grammar::Any, false),
+    property("text-indent", Grammar::Single(&LENGTH_PERCENTAGE), true),
+    property("text-overflow", Grammar::Multiple(&TEXT_OVERFLOW, 1, 2), false),
+    property("text-shadow", Grammar::Any, true),
+    property("text-transform", Grammar::Single(&TEXT_TRANSFORM), true),
+    property("top", Grammar::Single(&LENGTH_PERCENTAGE_AUTO), false),
+    property("transform", Grammar::Any, false),
+    property("transform-origin", Grammar::Multiple(&POSITION, 1, 3), false),
+    property("transition", Grammar::Shorthand(Shorthand::Transition), false),
+    property("transition-delay", Grammar::List(&TIME, 1, 1), false),
+    property("transition-duration", Grammar::List(&TIME, 1, 1), false),
+    property("transition-property", Grammar::List(&CUSTOM_IDENT, 1, 1), false),
+    property("transition-timing-function", Grammar::Any, false),
+    property("user-select", Grammar::Single(&USER_SELECT), false),
+    property("vertical-align", Grammar::Single(&VERTICAL_ALIGN), false),
+    property("visibility", Grammar::Single(&VISIBILITY), true),
+    property("white-space", Grammar::Single(&WHITE_SPACE), true),
+    property("width", Grammar::Single(&SIZE), false),
+    property("word-break", Grammar::Single(&WORD_BREAK), true),
+    property("word-spacing", Grammar::Single(&SPACING), true),
+    property("z-index", Grammar::Single(&Z_INDEX), false),
+];
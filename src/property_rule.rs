@@ -0,0 +1,316 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::Unit;
+use crate::error::Error;
+use crate::properties::{self, ValueType};
+use crate::stream::Stream;
+use crate::tokenizer::{Tokenizer, Token};
+use crate::value;
+
+/// A parsed `@property` rule, which registers a custom property.
+///
+/// https://www.w3.org/TR/css-properties-values-api-1/#at-property-rule
+#[derive(Clone,PartialEq,Debug)]
+pub struct PropertyRule<'a> {
+    /// Custom property name with the leading `--`.
+    pub name: &'a str,
+    /// `syntax` descriptor.
+    pub syntax: PropertySyntax<'a>,
+    /// `inherits` descriptor.
+    pub inherits: bool,
+    /// `initial-value` descriptor. Can be `None` only for the universal syntax.
+    pub initial_value: Option<&'a str>,
+}
+
+/// A `syntax` descriptor value, like `<length> | auto`.
+///
+/// https://www.w3.org/TR/css-properties-values-api-1/#syntax-strings
+#[derive(Clone,PartialEq,Debug)]
+pub enum PropertySyntax<'a> {
+    /// `*`, which matches any value.
+    Universal,
+    /// Alternatives separated by `|`.
+    Components(Vec<SyntaxComponent<'a>>),
+}
+
+/// A single alternative of a `PropertySyntax`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct SyntaxComponent<'a> {
+    /// A data type or an ident.
+    pub name: SyntaxComponentName<'a>,
+    /// `+` or `#` after the name.
+    pub multiplier: Option<SyntaxMultiplier>,
+}
+
+/// A name of a `SyntaxComponent`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum SyntaxComponentName<'a> {
+    /// A data type name, like `<length>`.
+    ///
+    /// `<transform-list>` is stored as `<transform-function>+`.
+    Type(ValueType),
+    /// An ident, like `auto`. Case-sensitive.
+    Ident(&'a str),
+}
+
+/// A multiplier of a `SyntaxComponent`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum SyntaxMultiplier {
+    /// `+`, a space-separated list.
+    SpaceList,
+    /// `#`, a comma-separated list.
+    CommaList,
+}
+
+impl<'a> PropertyRule<'a> {
+    /// Parses a `@property` rule.
+    ///
+    /// Should be called right after `Token::AtRule("property")`.
+    /// Consumes everything up to and including the rule's `Token::BlockEnd`.
+    ///
+    /// Unknown descriptors are ignored and later descriptors win.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` if the name is not a custom property name,
+    /// `syntax` or `inherits` are missing or invalid, or `initial-value` is missing
+    /// or doesn't match the syntax. Like the spec requires, `initial-value` must be
+    /// computationally independent, so `var()` and relative lengths like `1em` are invalid.
+    pub fn parse(tokenizer: &mut Tokenizer<'a>) -> Result<PropertyRule<'a>, Error> {
+        let name = tokenizer.parse_at_rule_prelude()?;
+        let name_pos = tokenizer.error_pos_of(name);
+        if tokenizer.parse_next()? != Token::BlockStart {
+            return Err(Error::UnknownToken(name_pos));
+        }
+
+        let mut syntax = None;
+        let mut inherits = None;
+        let mut initial_value = None;
+        let mut depth = 1;
+        while depth > 0 {
+            match tokenizer.parse_next()? {
+                Token::Declaration(descriptor, value) if depth == 1 => {
+                    match descriptor.to_ascii_lowercase().as_str() {
+                        "syntax" => syntax = Some(value),
+                        "inherits" => inherits = Some(value),
+                        "initial-value" => initial_value = Some(value),
+                        _ => {}
+                    }
+                }
+                Token::BlockStart => depth += 1,
+                Token::BlockEnd => depth -= 1,
+                Token::EndOfStream => break,
+                _ => {}
+            }
+        }
+
+        if !(name.len() > 2 && name.starts_with("--") && value::is_custom_ident(name)) {
+            return Err(Error::InvalidValue(name_pos));
+        }
+
+        let syntax = syntax.ok_or(Error::InvalidValue(name_pos))?;
+        let syntax_text = value::unquote(syntax)
+            .ok_or_else(|| Error::InvalidValue(tokenizer.error_pos_of(syntax)))?;
+        let syntax = PropertySyntax::parse(syntax_text)
+            .map_err(|e| e.offset_by(tokenizer.error_pos_of(syntax_text)))?;
+
+        let inherits_text = inherits.ok_or(Error::InvalidValue(name_pos))?;
+        let inherits = if inherits_text.eq_ignore_ascii_case("true") {
+            true
+        } else if inherits_text.eq_ignore_ascii_case("false") {
+            false
+        } else {
+            return Err(Error::InvalidValue(tokenizer.error_pos_of(inherits_text)));
+        };
+
+        match initial_value {
+            Some(value) if !syntax.matches(value) || !is_computationally_independent(value) => {
+                return Err(Error::InvalidValue(tokenizer.error_pos_of(value)));
+            }
+            None if syntax != PropertySyntax::Universal => return Err(Error::InvalidValue(name_pos)),
+            _ => {}
+        }
+
+        Ok(PropertyRule {
+            name,
+            syntax,
+            inherits,
+            initial_value,
+        })
+    }
+}
+
+impl<'a> PropertySyntax<'a> {
+    /// Parses a `syntax` descriptor string without quotes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` on an unknown data type name, a CSS-wide keyword
+    /// used as an ident or a multiplier after `<transform-list>`.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<PropertySyntax<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+
+        if s.is_char_eq(b'*')? {
+            s.advance_raw(1);
+            s.skip_spaces();
+            if !s.at_end() {
+                return Err(Error::UnknownToken(s.gen_error_pos()));
+            }
+
+            return Ok(PropertySyntax::Universal);
+        }
+
+        let mut components = Vec::new();
+        loop {
+            components.push(parse_component(&mut s)?);
+
+            s.skip_spaces();
+            if s.at_end() {
+                break;
+            }
+
+            s.consume_char(b'|')?;
+            s.skip_spaces();
+        }
+
+        Ok(PropertySyntax::Components(components))
+    }
+
+    /// Checks that a value matches the syntax.
+    ///
+    /// `var()` references must be substituted beforehand.
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            PropertySyntax::Universal => true,
+            PropertySyntax::Components(components) => {
+                let text = text.trim();
+                components.iter().any(|c| c.matches(text))
+            }
+        }
+    }
+}
+
+impl<'a> SyntaxComponent<'a> {
+    fn matches(&self, text: &str) -> bool {
+        let parts = match self.multiplier {
+            None => vec![text],
+            Some(SyntaxMultiplier::SpaceList) => value::split_components(text),
+            Some(SyntaxMultiplier::CommaList) => value::split_list(text),
+        };
+
+        // A single component value can't contain spaces, unlike a function.
+        !parts.is_empty() && parts.iter().all(|part| {
+            value::split_components(part).len() == 1 && match self.name {
+                SyntaxComponentName::Type(t) => properties::matches_type(part, t),
+                SyntaxComponentName::Ident(ident) => *part == ident,
+            }
+        })
+    }
+}
+
+fn parse_component<'a>(s: &mut Stream<'a>) -> Result<SyntaxComponent<'a>, Error> {
+    let pos = s.gen_error_pos();
+    let name = if s.is_char_eq(b'<')? {
+        s.advance_raw(1);
+        let name = s.consume_ident()?;
+        s.consume_char(b'>')?;
+
+        if name == "transform-list" {
+            if let Some(b'+') | Some(b'#') = s.char_at(0) {
+                return Err(Error::InvalidValue(s.gen_error_pos()));
+            }
+
+            return Ok(SyntaxComponent {
+                name: SyntaxComponentName::Type(ValueType::TransformFunction),
+                multiplier: Some(SyntaxMultiplier::SpaceList),
+            });
+        }
+
+        SyntaxComponentName::Type(data_type(name).ok_or(Error::InvalidValue(pos))?)
+    } else {
+        let ident = s.consume_ident()?;
+        if !value::is_custom_ident(ident) || value::is_css_wide_keyword(ident)
+            || ident.eq_ignore_ascii_case("default")
+        {
+            return Err(Error::InvalidValue(pos));
+        }
+
+        SyntaxComponentName::Ident(ident)
+    };
+
+    let multiplier = match s.char_at(0) {
+        Some(b'+') => Some(SyntaxMultiplier::SpaceList),
+        Some(b'#') => Some(SyntaxMultiplier::CommaList),
+        _ => None,
+    };
+
+    if multiplier.is_some() {
+        s.advance_raw(1);
+    }
+
+    Ok(SyntaxComponent { name, multiplier })
+}
+
+fn data_type(name: &str) -> Option<ValueType> {
+    let t = match name {
+        "length" => ValueType::Length,
+        "number" => ValueType::Number,
+        "percentage" => ValueType::Percentage,
+        "length-percentage" => ValueType::LengthPercentage,
+        "color" => ValueType::Color,
+        "image" => ValueType::Image,
+        "url" => ValueType::Url,
+        "integer" => ValueType::Integer,
+        "angle" => ValueType::Angle,
+        "time" => ValueType::Time,
+        "resolution" => ValueType::Resolution,
+        "transform-function" => ValueType::TransformFunction,
+        "custom-ident" => ValueType::CustomIdent,
+        "string" => ValueType::String,
+        _ => return None,
+    };
+
+    Some(t)
+}
+
+/// Checks that a value doesn't depend on other properties, like `var()` or `1em` do.
+///
+/// https://www.w3.org/TR/css-properties-values-api-1/#computationally-independent
+fn is_computationally_independent(text: &str) -> bool {
+    if value::find_var(text).is_some() {
+        return false;
+    }
+
+    let mut s = Stream::new(text.as_bytes());
+    let mut prev_is_ident = false;
+    while !s.at_end() {
+        let c = s.curr_char_raw();
+        if c == b'"' || c == b'\'' {
+            s.skip_quoted_string();
+            prev_is_ident = false;
+            continue;
+        }
+
+        if !prev_is_ident && s.consume_number().is_ok() {
+            if let Ok(unit) = s.consume_ident() {
+                if Unit::from_name(unit).is_some_and(|u| u.absolute_factor().is_none()) {
+                    return false;
+                }
+            }
+
+            prev_is_ident = false;
+            continue;
+        }
+
+        // Hex colors like `#1em` are not dimensions.
+        prev_is_ident = s.is_ident_raw() || c == b'#';
+        s.advance_raw(1);
+    }
+
+    true
+}
@@ -0,0 +1,158 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::selector::{Element, Namespaces, Selector, SelectorComponent, SelectorList};
+use crate::stream::Stream;
+use crate::tokenizer::Combinator;
+
+/// A parsed `@scope` prelude, like `(.card) to (.content)`.
+///
+/// https://www.w3.org/TR/css-cascade-6/#scope-atrule
+#[derive(Clone,PartialEq,Debug)]
+pub struct ScopeRule<'a> {
+    /// Scoping root selectors. `None` if the rule uses the implicit root.
+    pub root: Option<SelectorList<'a>>,
+    /// Scoping limit selectors, after `to`.
+    pub limit: Option<SelectorList<'a>>,
+}
+
+impl<'a> ScopeRule<'a> {
+    /// Parses a `@scope` prelude.
+    ///
+    /// Use `Tokenizer::parse_at_rule_prelude` right after `Token::AtRule("scope")`
+    /// to get the prelude.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<ScopeRule<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+
+        let mut root = None;
+        if !s.at_end() && s.curr_char_raw() == b'(' {
+            root = Some(parse_selectors(&mut s)?);
+            s.skip_spaces();
+        }
+
+        let mut limit = None;
+        if !s.at_end() {
+            let pos = s.gen_error_pos();
+            if !s.try_consume_keyword("to") {
+                return Err(Error::UnknownToken(pos));
+            }
+
+            s.skip_spaces();
+            limit = Some(parse_selectors(&mut s)?);
+            s.skip_spaces();
+        }
+
+        if !s.at_end() {
+            return Err(Error::UnknownToken(s.gen_error_pos()));
+        }
+
+        Ok(ScopeRule { root, limit })
+    }
+
+    /// Checks that the element is in the scope of the scoping root.
+    ///
+    /// The root itself is in scope. Elements matched by the limit selectors,
+    /// with `:scope` being the root, and their descendants are not.
+    pub fn in_scope<E>(&self, element: &E, root: &E, namespaces: &Namespaces) -> bool
+        where E: Element + PartialEq + Clone
+    {
+        let mut curr = Some(element.clone());
+        while let Some(e) = curr {
+            if e == *root {
+                return true;
+            }
+
+            if let Some(ref limit) = self.limit {
+                if limit.matches(&e, namespaces, Some(root)) {
+                    return false;
+                }
+            }
+
+            curr = e.parent_element();
+        }
+
+        // Not a descendant of the root.
+        false
+    }
+
+    /// Matches a selector of a style rule inside the `@scope` block.
+    ///
+    /// Returns the scope proximity: the number of generations between the element
+    /// and the nearest scoping root it's in scope of. `None` if the selector doesn't match.
+    ///
+    /// `:scope` matches the scoping root. A selector without `:scope` is treated
+    /// as a descendant of `:scope`, so only elements in scope can match it.
+    ///
+    /// `implicit_root` is used when the rule has no root selectors, like the parent
+    /// of the `<style>` element. Without it, the root element is used.
+    pub fn proximity<E>(
+        &self,
+        selector: &Selector,
+        element: &E,
+        namespaces: &Namespaces,
+        implicit_root: Option<&E>,
+    ) -> Option<usize>
+        where E: Element + PartialEq + Clone
+    {
+        let scoped;
+        let selector = if selector.has_scope() {
+            selector
+        } else {
+            let mut components = vec![
+                SelectorComponent::PseudoClass("scope", None),
+                SelectorComponent::Combinator(Combinator::Space),
+            ];
+            components.extend_from_slice(&selector.components);
+            scoped = Selector { components };
+            &scoped
+        };
+
+        let mut depth = 0;
+        let mut curr = Some(element.clone());
+        while let Some(root) = curr {
+            if self.is_root(&root, namespaces, implicit_root)
+                && self.in_scope(element, &root, namespaces)
+                && selector.matches(element, namespaces, Some(&root))
+            {
+                return Some(depth);
+            }
+
+            depth += 1;
+            curr = root.parent_element();
+        }
+
+        None
+    }
+
+    fn is_root<E>(&self, element: &E, namespaces: &Namespaces, implicit_root: Option<&E>) -> bool
+        where E: Element + PartialEq + Clone
+    {
+        match (&self.root, implicit_root) {
+            (Some(root), _) => root.matches(element, namespaces, None),
+            (None, Some(root)) => element == root,
+            (None, None) => element.parent_element().is_none(),
+        }
+    }
+}
+
+fn parse_selectors<'a>(s: &mut Stream<'a>) -> Result<SelectorList<'a>, Error> {
+    if s.at_end() {
+        return Err(Error::UnexpectedEndOfStream(s.gen_error_pos()));
+    }
+
+    if s.curr_char_raw() != b'(' {
+        return Err(Error::UnknownToken(s.gen_error_pos()));
+    }
+
+    let mut inner = *s;
+    inner.advance_raw(1); // (
+    let base = inner.gen_error_pos();
+
+    let block = s.consume_parenthesized()?;
+    SelectorList::parse(&block[1..block.len() - 1]).map_err(|e| e.offset_by(base))
+}
@@ -0,0 +1,677 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::stream::Stream;
+use crate::tokenizer::Combinator;
+use crate::value;
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// An element of a document tree, used by selector matching.
+pub trait Element {
+    /// Returns the element's local name, like `circle`.
+    fn local_name(&self) -> &str;
+
+    /// Returns the element's namespace URI, if any.
+    fn namespace_uri(&self) -> Option<&str>;
+
+    /// Returns a value of the attribute with the selected namespace URI and local name.
+    ///
+    /// `namespace_uri` is `None` for attributes without a namespace.
+    fn attribute(&self, namespace_uri: Option<&str>, local_name: &str) -> Option<&str>;
+
+    /// Returns a value of the attribute with the selected local name in any namespace.
+    ///
+    /// Used by `[*|name]`. Returns an attribute without a namespace by default.
+    fn attribute_in_any_namespace(&self, local_name: &str) -> Option<&str> {
+        self.attribute(None, local_name)
+    }
+
+    /// Returns the element's ID. Uses the `id` attribute by default.
+    fn id(&self) -> Option<&str> {
+        self.attribute(None, "id")
+    }
+
+    /// Checks that the element has the class. Uses the `class` attribute by default.
+    fn has_class(&self, name: &str) -> bool {
+        match self.attribute(None, "class") {
+            Some(list) => list.split_ascii_whitespace().any(|c| c == name),
+            None => false,
+        }
+    }
+
+    /// Returns the parent element, if any.
+    ///
+    /// Used by combinators and `:root`. Returns `None` by default.
+    fn parent_element(&self) -> Option<Self> where Self: Sized {
+        None
+    }
+
+    /// Returns the previous sibling element, if any.
+    ///
+    /// Used by sibling combinators and `:first-child`. Returns `None` by default.
+    fn prev_sibling_element(&self) -> Option<Self> where Self: Sized {
+        None
+    }
+}
+
+/// A namespace prefix of a type, universal or attribute selector.
+///
+/// https://www.w3.org/TR/selectors-3/#typenmsp
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum NamespaceConstraint<'a> {
+    /// `*|`, any namespace.
+    Any,
+    /// `|`, no namespace.
+    None,
+    /// `prefix|`, a namespace declared by `@namespace`.
+    Prefix(&'a str),
+}
+
+/// Namespaces declared by `@namespace` rules.
+#[derive(Clone,Default,PartialEq,Debug)]
+pub struct Namespaces<'a> {
+    default: Option<&'a str>,
+    prefixes: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Namespaces<'a> {
+    /// Constructs a new, empty `Namespaces`.
+    pub fn new() -> Namespaces<'a> {
+        Namespaces::default()
+    }
+
+    /// Declares a namespace, like `Token::Namespace`.
+    ///
+    /// A later declaration of the same prefix replaces the previous one.
+    pub fn insert(&mut self, prefix: Option<&'a str>, uri: &'a str) {
+        match prefix {
+            Some(prefix) => {
+                self.prefixes.retain(|p| p.0 != prefix);
+                self.prefixes.push((prefix, uri));
+            }
+            None => self.default = Some(uri),
+        }
+    }
+
+    /// Returns a namespace URI of the prefix. Prefixes are case-sensitive.
+    pub fn uri(&self, prefix: &str) -> Option<&'a str> {
+        self.prefixes.iter().find(|p| p.0 == prefix).map(|p| p.1)
+    }
+
+    /// Returns the default namespace URI.
+    pub fn default_uri(&self) -> Option<&'a str> {
+        self.default
+    }
+
+    /// Checks that the element matches a type or universal selector.
+    ///
+    /// `name` is `None` for the universal selector. Names are case-sensitive, like in SVG
+    /// and XML, except for elements in the HTML namespace, which are matched ASCII case-insensitive.
+    ///
+    /// Without a prefix, the default namespace, if declared, must match.
+    /// An undeclared prefix never matches.
+    pub fn matches_type<E: Element>(
+        &self,
+        element: &E,
+        namespace: Option<NamespaceConstraint>,
+        name: Option<&str>,
+    ) -> bool {
+        if let Some(name) = name {
+            let is_match = if element.namespace_uri() == Some(HTML_NAMESPACE) {
+                element.local_name().eq_ignore_ascii_case(name)
+            } else {
+                element.local_name() == name
+            };
+
+            if !is_match {
+                return false;
+            }
+        }
+
+        match namespace {
+            None => {
+                match self.default {
+                    Some(uri) => element.namespace_uri() == Some(uri),
+                    None => true,
+                }
+            }
+            Some(NamespaceConstraint::Any) => true,
+            Some(NamespaceConstraint::None) => element.namespace_uri().is_none(),
+            Some(NamespaceConstraint::Prefix(prefix)) => {
+                match self.uri(prefix) {
+                    Some(uri) => element.namespace_uri() == Some(uri),
+                    None => false,
+                }
+            }
+        }
+    }
+
+    /// Checks that the element matches an attribute selector.
+    ///
+    /// Without a prefix, only attributes without a namespace are matched.
+    /// An undeclared prefix never matches.
+    pub fn matches_attribute<E: Element>(&self, element: &E, attr: &Attribute) -> bool {
+        let value = match attr.namespace {
+            None | Some(NamespaceConstraint::None) => element.attribute(None, attr.name),
+            Some(NamespaceConstraint::Any) => element.attribute_in_any_namespace(attr.name),
+            Some(NamespaceConstraint::Prefix(prefix)) => {
+                match self.uri(prefix) {
+                    Some(uri) => element.attribute(Some(uri), attr.name),
+                    None => return false,
+                }
+            }
+        };
+
+        let value = match value {
+            Some(value) => value,
+            None => return false,
+        };
+
+        let (operator, expected) = match attr.operator {
+            Some(op) => op,
+            None => return true,
+        };
+
+        if attr.case_insensitive {
+            let value = value.to_ascii_lowercase();
+            let expected = expected.to_ascii_lowercase();
+            return operator.matches(&value, &expected);
+        }
+
+        operator.matches(value, expected)
+    }
+}
+
+/// A parsed attribute selector, like `[xlink|href^="#"]`.
+///
+/// https://www.w3.org/TR/selectors-4/#attribute-selectors
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct Attribute<'a> {
+    /// Namespace prefix, if any.
+    pub namespace: Option<NamespaceConstraint<'a>>,
+    /// Attribute name.
+    pub name: &'a str,
+    /// Operator and value without quotes. `None` for `[name]`.
+    pub operator: Option<(AttributeOperator, &'a str)>,
+    /// The `i` flag.
+    pub case_insensitive: bool,
+}
+
+/// An attribute selector operator.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum AttributeOperator {
+    /// `=`
+    Equals,
+    /// `~=`
+    Includes,
+    /// `|=`
+    DashMatch,
+    /// `^=`
+    Prefix,
+    /// `$=`
+    Suffix,
+    /// `*=`
+    Substring,
+}
+
+impl AttributeOperator {
+    fn matches(&self, value: &str, expected: &str) -> bool {
+        match *self {
+            AttributeOperator::Equals => value == expected,
+            AttributeOperator::Includes => {
+                !expected.is_empty() && value.split_ascii_whitespace().any(|v| v == expected)
+            }
+            AttributeOperator::DashMatch => {
+                value == expected
+                    || (value.starts_with(expected) && value[expected.len()..].starts_with('-'))
+            }
+            AttributeOperator::Prefix => !expected.is_empty() && value.starts_with(expected),
+            AttributeOperator::Suffix => !expected.is_empty() && value.ends_with(expected),
+            AttributeOperator::Substring => !expected.is_empty() && value.contains(expected),
+        }
+    }
+}
+
+impl<'a> Attribute<'a> {
+    /// Parses the contents of `Token::AttributeSelector`, like `xlink|href`.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<Attribute<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        s.skip_spaces();
+
+        let namespace = consume_namespace_prefix(&mut s)?;
+        let name = s.consume_ident()?;
+        s.skip_spaces();
+
+        let mut operator = None;
+        let mut case_insensitive = false;
+        if !s.at_end() {
+            let pos = s.gen_error_pos();
+            let op = match s.curr_char_raw() {
+                b'=' => AttributeOperator::Equals,
+                b'~' => AttributeOperator::Includes,
+                b'|' => AttributeOperator::DashMatch,
+                b'^' => AttributeOperator::Prefix,
+                b'$' => AttributeOperator::Suffix,
+                b'*' => AttributeOperator::Substring,
+                _ => return Err(Error::UnknownToken(pos)),
+            };
+
+            if op != AttributeOperator::Equals {
+                s.advance_raw(1);
+            }
+            s.consume_char(b'=')?;
+            s.skip_spaces();
+
+            let value = match s.curr_char()? {
+                b'"' | b'\'' => s.consume_url()?,
+                _ => s.consume_ident()?,
+            };
+            operator = Some((op, value));
+            s.skip_spaces();
+
+            if !s.at_end() {
+                let pos = s.gen_error_pos();
+                let flag = s.consume_ident()?;
+                if flag.eq_ignore_ascii_case("i") {
+                    case_insensitive = true;
+                } else if !flag.eq_ignore_ascii_case("s") {
+                    return Err(Error::UnknownToken(pos));
+                }
+                s.skip_spaces();
+            }
+        }
+
+        if !s.at_end() {
+            return Err(Error::UnknownToken(s.gen_error_pos()));
+        }
+
+        Ok(Attribute {
+            namespace,
+            name,
+            operator,
+            case_insensitive,
+        })
+    }
+}
+
+/// Consumes `*|`, `|` or `prefix|`, if any.
+///
+/// `|=` is an operator and not a namespace separator.
+pub(crate) fn consume_namespace_prefix<'a>(
+    s: &mut Stream<'a>,
+) -> Result<Option<NamespaceConstraint<'a>>, Error> {
+    let is_separator = |s: &Stream, offset: usize| {
+        s.char_at(offset) == Some(b'|') && s.char_at(offset + 1) != Some(b'=')
+    };
+
+    if is_separator(s, 0) {
+        s.advance_raw(1);
+        return Ok(Some(NamespaceConstraint::None));
+    }
+
+    if s.char_at(0) == Some(b'*') && is_separator(s, 1) {
+        s.advance_raw(2);
+        return Ok(Some(NamespaceConstraint::Any));
+    }
+
+    let mut tmp = *s;
+    if let Ok(prefix) = tmp.consume_ident() {
+        if is_separator(&tmp, 0) {
+            tmp.advance_raw(1);
+            *s = tmp;
+            return Ok(Some(NamespaceConstraint::Prefix(prefix)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// A comma-separated list of selectors, like `.card > img, svg|a`.
+#[derive(Clone,PartialEq,Debug)]
+pub struct SelectorList<'a> {
+    #[allow(missing_docs)]
+    pub selectors: Vec<Selector<'a>>,
+}
+
+/// A complex selector, like `.card > img`.
+#[derive(Clone,PartialEq,Debug)]
+pub struct Selector<'a> {
+    /// Simple selectors and combinators in the source order.
+    pub components: Vec<SelectorComponent<'a>>,
+}
+
+/// A part of a complex selector.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum SelectorComponent<'a> {
+    /// `*`, optionally with a namespace prefix.
+    Universal(Option<NamespaceConstraint<'a>>),
+    /// A type selector, optionally with a namespace prefix.
+    Type(Option<NamespaceConstraint<'a>>, &'a str),
+    /// `#id`
+    Id(&'a str),
+    /// `.class`
+    Class(&'a str),
+    /// `[attr]`
+    Attribute(Attribute<'a>),
+    /// A pseudo-class, like `:scope` or `:not(.a)`, with the raw argument.
+    PseudoClass(&'a str, Option<&'a str>),
+    /// A pseudo-element, like `::before`, with the raw argument.
+    PseudoElement(&'a str, Option<&'a str>),
+    #[allow(missing_docs)]
+    Combinator(Combinator),
+}
+
+impl<'a> SelectorList<'a> {
+    /// Parses a selector list.
+    ///
+    /// Pseudo-class arguments are not parsed, except by `Selector::matches`.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<SelectorList<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        let mut selectors = Vec::new();
+
+        loop {
+            s.skip_spaces();
+            selectors.push(parse_selector(&mut s)?);
+
+            if s.at_end() {
+                break;
+            }
+
+            s.consume_char(b',')?;
+        }
+
+        Ok(SelectorList { selectors })
+    }
+
+    /// Checks that any of the selectors matches the element. See `Selector::matches`.
+    pub fn matches<E>(&self, element: &E, namespaces: &Namespaces, scope: Option<&E>) -> bool
+        where E: Element + PartialEq
+    {
+        self.selectors.iter().any(|sel| sel.matches(element, namespaces, scope))
+    }
+}
+
+impl<'a> Selector<'a> {
+    /// Checks that the selector contains `:scope`, not counting pseudo-class arguments.
+    pub fn has_scope(&self) -> bool {
+        self.components.iter().any(|c| match *c {
+            SelectorComponent::PseudoClass(name, _) => name.eq_ignore_ascii_case("scope"),
+            _ => false,
+        })
+    }
+
+    /// Returns the selector specificity, as (ID, class, type) counts.
+    ///
+    /// `:is()` and `:not()` count as their most specific argument and `:where()` as zero.
+    ///
+    /// https://www.w3.org/TR/selectors-4/#specificity-rules
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        let mut spec = (0, 0, 0);
+        for c in &self.components {
+            match *c {
+                SelectorComponent::Id(_) => spec.0 += 1,
+                SelectorComponent::Class(_) | SelectorComponent::Attribute(_) => spec.1 += 1,
+                SelectorComponent::PseudoClass(name, arg) => {
+                    if value::is_keyword(name, &["where"]) {
+                        continue;
+                    }
+
+                    if value::is_keyword(name, &["is", "matches", "not"]) {
+                        let list = arg.and_then(|arg| SelectorList::parse(arg).ok());
+                        let max = list.and_then(|list| {
+                            list.selectors.iter().map(|sel| sel.specificity()).max()
+                        });
+
+                        if let Some(max) = max {
+                            spec.0 += max.0;
+                            spec.1 += max.1;
+                            spec.2 += max.2;
+                        }
+                        continue;
+                    }
+
+                    spec.1 += 1;
+                }
+                SelectorComponent::Type(..) | SelectorComponent::PseudoElement(..) => spec.2 += 1,
+                SelectorComponent::Universal(_) | SelectorComponent::Combinator(_) => {}
+            }
+        }
+
+        spec
+    }
+
+    /// Checks that the selector matches the element.
+    ///
+    /// `scope` is the element matched by `:scope`. Without it, `:scope` matches
+    /// the root element, like `:root`.
+    ///
+    /// Supported pseudo-classes are `:scope`, `:root`, `:first-child`, `:is()`,
+    /// `:where()` and `:not()`. Other pseudo-classes and pseudo-elements never match.
+    pub fn matches<E>(&self, element: &E, namespaces: &Namespaces, scope: Option<&E>) -> bool
+        where E: Element + PartialEq
+    {
+        matches_complex(&self.components, element, namespaces, scope)
+    }
+}
+
+fn matches_complex<E>(
+    components: &[SelectorComponent],
+    element: &E,
+    namespaces: &Namespaces,
+    scope: Option<&E>,
+) -> bool
+    where E: Element + PartialEq
+{
+    let (rest, compound) = match components.iter().rposition(|c| {
+        matches!(*c, SelectorComponent::Combinator(_))
+    }) {
+        Some(idx) => (&components[..idx + 1], &components[idx + 1..]),
+        None => (&components[..0], components),
+    };
+
+    if !compound.iter().all(|c| matches_simple(c, element, namespaces, scope)) {
+        return false;
+    }
+
+    let (combinator, rest) = match rest.split_last() {
+        Some((&SelectorComponent::Combinator(c), rest)) => (c, rest),
+        _ => return true,
+    };
+
+    match combinator {
+        Combinator::Space => {
+            let mut curr = element.parent_element();
+            while let Some(parent) = curr {
+                if matches_complex(rest, &parent, namespaces, scope) {
+                    return true;
+                }
+                curr = parent.parent_element();
+            }
+            false
+        }
+        Combinator::GreaterThan => {
+            match element.parent_element() {
+                Some(parent) => matches_complex(rest, &parent, namespaces, scope),
+                None => false,
+            }
+        }
+        Combinator::Plus => {
+            match element.prev_sibling_element() {
+                Some(prev) => matches_complex(rest, &prev, namespaces, scope),
+                None => false,
+            }
+        }
+        Combinator::Tilde => {
+            let mut curr = element.prev_sibling_element();
+            while let Some(prev) = curr {
+                if matches_complex(rest, &prev, namespaces, scope) {
+                    return true;
+                }
+                curr = prev.prev_sibling_element();
+            }
+            false
+        }
+    }
+}
+
+fn matches_simple<E>(
+    component: &SelectorComponent,
+    element: &E,
+    namespaces: &Namespaces,
+    scope: Option<&E>,
+) -> bool
+    where E: Element + PartialEq
+{
+    match *component {
+        SelectorComponent::Universal(ns) => namespaces.matches_type(element, ns, None),
+        SelectorComponent::Type(ns, name) => namespaces.matches_type(element, ns, Some(name)),
+        SelectorComponent::Id(id) => element.id() == Some(id),
+        SelectorComponent::Class(name) => element.has_class(name),
+        SelectorComponent::Attribute(ref attr) => namespaces.matches_attribute(element, attr),
+        SelectorComponent::PseudoClass(name, arg) => {
+            if value::is_keyword(name, &["scope"]) {
+                return match scope {
+                    Some(scope) => element == scope,
+                    None => element.parent_element().is_none(),
+                };
+            }
+
+            if value::is_keyword(name, &["root"]) {
+                return element.parent_element().is_none();
+            }
+
+            if value::is_keyword(name, &["first-child"]) {
+                return element.prev_sibling_element().is_none();
+            }
+
+            let list = match arg.map(SelectorList::parse) {
+                Some(Ok(list)) => list,
+                _ => return false,
+            };
+
+            if value::is_keyword(name, &["is", "matches", "where"]) {
+                list.matches(element, namespaces, scope)
+            } else if value::is_keyword(name, &["not"]) {
+                !list.matches(element, namespaces, scope)
+            } else {
+                false
+            }
+        }
+        SelectorComponent::PseudoElement(..) | SelectorComponent::Combinator(_) => false,
+    }
+}
+
+fn parse_selector<'a>(s: &mut Stream<'a>) -> Result<Selector<'a>, Error> {
+    let mut components = Vec::new();
+
+    loop {
+        parse_compound(s, &mut components)?;
+
+        let start = s.pos();
+        s.skip_spaces();
+        let has_space = s.pos() != start;
+
+        if s.at_end() || s.curr_char_raw() == b',' {
+            break;
+        }
+
+        let combinator = match s.curr_char_raw() {
+            b'>' => Combinator::GreaterThan,
+            b'+' => Combinator::Plus,
+            b'~' => Combinator::Tilde,
+            _ if has_space => Combinator::Space,
+            _ => return Err(Error::UnknownToken(s.gen_error_pos())),
+        };
+
+        if combinator != Combinator::Space {
+            s.advance_raw(1);
+            s.skip_spaces();
+        }
+
+        components.push(SelectorComponent::Combinator(combinator));
+    }
+
+    Ok(Selector { components })
+}
+
+fn parse_compound<'a>(
+    s: &mut Stream<'a>,
+    components: &mut Vec<SelectorComponent<'a>>,
+) -> Result<(), Error> {
+    let pos = s.gen_error_pos();
+    let len = components.len();
+
+    if let Some(ns) = consume_namespace_prefix(s)? {
+        if s.is_char_eq(b'*')? {
+            s.advance_raw(1);
+            components.push(SelectorComponent::Universal(Some(ns)));
+        } else {
+            components.push(SelectorComponent::Type(Some(ns), s.consume_ident()?));
+        }
+    } else if s.is_char_eq(b'*')? {
+        s.advance_raw(1);
+        components.push(SelectorComponent::Universal(None));
+    } else {
+        let mut tmp = *s;
+        if let Ok(name) = tmp.consume_ident() {
+            *s = tmp;
+            components.push(SelectorComponent::Type(None, name));
+        }
+    }
+
+    while !s.at_end() {
+        match s.curr_char_raw() {
+            b'#' => {
+                s.advance_raw(1);
+                components.push(SelectorComponent::Id(s.consume_ident()?));
+            }
+            b'.' => {
+                s.advance_raw(1);
+                components.push(SelectorComponent::Class(s.consume_ident()?));
+            }
+            b'[' => {
+                s.advance_raw(1);
+                let base = s.gen_error_pos();
+                let len = s.length_to(b']')?;
+                let text = s.read_raw_str(len);
+                s.advance_raw(1); // ]
+                let attr = Attribute::parse(text).map_err(|e| e.offset_by(base))?;
+                components.push(SelectorComponent::Attribute(attr));
+            }
+            b':' => {
+                s.advance_raw(1);
+                let is_element = s.is_char_eq(b':')?;
+                if is_element {
+                    s.advance_raw(1);
+                }
+
+                let name = s.consume_ident()?;
+                let mut arg = None;
+                if s.char_at(0) == Some(b'(') {
+                    let block = s.consume_parenthesized()?;
+                    arg = Some(block[1..block.len() - 1].trim());
+                }
+
+                components.push(if is_element {
+                    SelectorComponent::PseudoElement(name, arg)
+                } else {
+                    SelectorComponent::PseudoClass(name, arg)
+                });
+            }
+            _ => break,
+        }
+    }
+
+    if components.len() == len {
+        return Err(Error::UnknownToken(pos));
+    }
+
+    Ok(())
+}
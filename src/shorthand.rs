@@ -0,0 +1,746 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::{CalcNode, CalcType};
+use crate::container::{self, ContainerType};
+use crate::error::Error;
+use crate::value;
+use crate::value::{
+    is_keyword, is_css_wide_keyword, calc_type, is_length, is_length_percentage, is_number, is_time,
+    is_color, is_image, is_custom_ident,
+};
+
+/// Shorthand property.
+///
+/// https://www.w3.org/TR/css-cascade-4/#shorthand-property
+#[derive(Clone,Copy,PartialEq,Debug)]
+#[allow(missing_docs)]
+pub enum Shorthand {
+    Margin,
+    Padding,
+    Inset,
+    Gap,
+    Border,
+    BorderTop,
+    BorderRight,
+    BorderBottom,
+    BorderLeft,
+    BorderWidth,
+    BorderStyle,
+    BorderColor,
+    BorderRadius,
+    Background,
+    Font,
+    Flex,
+    Transition,
+    Animation,
+    Container,
+}
+
+/// List of longhand declarations, as `(name, value)` pairs.
+pub type Longhands = Vec<(&'static str, String)>;
+
+static MARGIN: [&str; 4] = ["margin-top", "margin-right", "margin-bottom", "margin-left"];
+static PADDING: [&str; 4] = ["padding-top", "padding-right", "padding-bottom", "padding-left"];
+static INSET: [&str; 4] = ["top", "right", "bottom", "left"];
+static GAP: [&str; 2] = ["row-gap", "column-gap"];
+static BORDER: [&str; 12] = [
+    "border-top-width", "border-right-width", "border-bottom-width", "border-left-width",
+    "border-top-style", "border-right-style", "border-bottom-style", "border-left-style",
+    "border-top-color", "border-right-color", "border-bottom-color", "border-left-color",
+];
+static BORDER_TOP: [&str; 3] = ["border-top-width", "border-top-style", "border-top-color"];
+static BORDER_RIGHT: [&str; 3] = ["border-right-width", "border-right-style", "border-right-color"];
+static BORDER_BOTTOM: [&str; 3] = ["border-bottom-width", "border-bottom-style",
+                                   "border-bottom-color"];
+static BORDER_LEFT: [&str; 3] = ["border-left-width", "border-left-style", "border-left-color"];
+static BORDER_WIDTH: [&str; 4] = ["border-top-width", "border-right-width",
+                                  "border-bottom-width", "border-left-width"];
+static BORDER_STYLE: [&str; 4] = ["border-top-style", "border-right-style",
+                                  "border-bottom-style", "border-left-style"];
+static BORDER_COLOR: [&str; 4] = ["border-top-color", "border-right-color",
+                                  "border-bottom-color", "border-left-color"];
+static BORDER_RADIUS: [&str; 4] = ["border-top-left-radius", "border-top-right-radius",
+                                   "border-bottom-right-radius", "border-bottom-left-radius"];
+static BACKGROUND: [&str; 8] = [
+    "background-color", "background-image", "background-position", "background-size",
+    "background-repeat", "background-attachment", "background-origin", "background-clip",
+];
+static FONT: [&str; 7] = ["font-style", "font-variant", "font-weight", "font-stretch",
+                          "font-size", "line-height", "font-family"];
+static FLEX: [&str; 3] = ["flex-grow", "flex-shrink", "flex-basis"];
+static TRANSITION: [&str; 4] = ["transition-property", "transition-duration",
+                                "transition-timing-function", "transition-delay"];
+static ANIMATION: [&str; 8] = [
+    "animation-name", "animation-duration", "animation-timing-function", "animation-delay",
+    "animation-iteration-count", "animation-direction", "animation-fill-mode",
+    "animation-play-state",
+];
+static CONTAINER: [&str; 2] = ["container-name", "container-type"];
+
+impl Shorthand {
+    /// Returns a shorthand by the property name.
+    ///
+    /// Returns `None` if the property is not a supported shorthand.
+    pub fn from_name(name: &str) -> Option<Shorthand> {
+        let shorthand = match name.to_ascii_lowercase().as_str() {
+            "margin" => Shorthand::Margin,
+            "padding" => Shorthand::Padding,
+            "inset" => Shorthand::Inset,
+            "gap" => Shorthand::Gap,
+            "border" => Shorthand::Border,
+            "border-top" => Shorthand::BorderTop,
+            "border-right" => Shorthand::BorderRight,
+            "border-bottom" => Shorthand::BorderBottom,
+            "border-left" => Shorthand::BorderLeft,
+            "border-width" => Shorthand::BorderWidth,
+            "border-style" => Shorthand::BorderStyle,
+            "border-color" => Shorthand::BorderColor,
+            "border-radius" => Shorthand::BorderRadius,
+            "background" => Shorthand::Background,
+            "font" => Shorthand::Font,
+            "flex" => Shorthand::Flex,
+            "transition" => Shorthand::Transition,
+            "animation" => Shorthand::Animation,
+            "container" => Shorthand::Container,
+            _ => return None,
+        };
+
+        Some(shorthand)
+    }
+
+    /// Returns names of all longhands this shorthand expands into.
+    pub fn longhands(&self) -> &'static [&'static str] {
+        match *self {
+            Shorthand::Margin => &MARGIN,
+            Shorthand::Padding => &PADDING,
+            Shorthand::Inset => &INSET,
+            Shorthand::Gap => &GAP,
+            Shorthand::Border => &BORDER,
+            Shorthand::BorderTop => &BORDER_TOP,
+            Shorthand::BorderRight => &BORDER_RIGHT,
+            Shorthand::BorderBottom => &BORDER_BOTTOM,
+            Shorthand::BorderLeft => &BORDER_LEFT,
+            Shorthand::BorderWidth => &BORDER_WIDTH,
+            Shorthand::BorderStyle => &BORDER_STYLE,
+            Shorthand::BorderColor => &BORDER_COLOR,
+            Shorthand::BorderRadius => &BORDER_RADIUS,
+            Shorthand::Background => &BACKGROUND,
+            Shorthand::Font => &FONT,
+            Shorthand::Flex => &FLEX,
+            Shorthand::Transition => &TRANSITION,
+            Shorthand::Animation => &ANIMATION,
+            Shorthand::Container => &CONTAINER,
+        }
+    }
+
+    /// Expands a shorthand value into longhands.
+    ///
+    /// Omitted values are set to their initial values. CSS-wide keywords,
+    /// like `initial` and `inherit`, are applied to all longhands.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedToken` if the value contains `var()`,
+    /// which must be substituted first, or if it's a system font keyword.
+    ///
+    /// Returns `Error::InvalidValue` if the value doesn't match the shorthand grammar.
+    ///
+    /// Error positions are relative to `value`.
+    pub fn expand(&self, value: &str) -> Result<Longhands, Error> {
+        if let Some(v) = value::find_var(value) {
+            return Err(Error::UnsupportedToken(value::error_pos(value, v)));
+        }
+
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Err(Error::InvalidValue(value::error_pos(value, trimmed)));
+        }
+
+        if is_css_wide_keyword(trimmed) {
+            let keyword = trimmed.to_ascii_lowercase();
+            return Ok(self.longhands().iter().map(|name| (*name, keyword.clone())).collect());
+        }
+
+        let parts = value::split_components(value);
+        if let Some(part) = parts.iter().find(|p| is_css_wide_keyword(p)) {
+            return Err(invalid(value, part));
+        }
+
+        match *self {
+            Shorthand::Margin => expand_box(value, &parts, &MARGIN, is_margin),
+            Shorthand::Padding => expand_box(value, &parts, &PADDING, is_length_percentage),
+            Shorthand::Inset => expand_box(value, &parts, &INSET, is_margin),
+            Shorthand::BorderWidth => expand_box(value, &parts, &BORDER_WIDTH, is_border_width),
+            Shorthand::BorderStyle => expand_box(value, &parts, &BORDER_STYLE, is_border_style),
+            Shorthand::BorderColor => expand_box(value, &parts, &BORDER_COLOR, is_color),
+            Shorthand::Gap => expand_gap(value, &parts),
+            Shorthand::Border => {
+                let side = parse_border_side(value, &parts)?;
+                let mut list = Vec::with_capacity(12);
+                for (i, name) in BORDER.iter().enumerate() {
+                    list.push((*name, side[i / 4].to_string()));
+                }
+                Ok(list)
+            }
+            Shorthand::BorderTop | Shorthand::BorderRight
+            | Shorthand::BorderBottom | Shorthand::BorderLeft => {
+                let side = parse_border_side(value, &parts)?;
+                Ok(self.longhands().iter().zip(side.iter())
+                    .map(|(name, v)| (*name, v.to_string()))
+                    .collect())
+            }
+            Shorthand::BorderRadius => expand_border_radius(value, &parts),
+            Shorthand::Background => expand_background(value),
+            Shorthand::Font => expand_font(value, &parts),
+            Shorthand::Flex => expand_flex(value, &parts),
+            Shorthand::Transition => expand_transition(value),
+            Shorthand::Animation => expand_animation(value),
+            Shorthand::Container => expand_container(value),
+        }
+    }
+}
+
+fn invalid(value: &str, part: &str) -> Error {
+    Error::InvalidValue(value::error_pos(value, part))
+}
+
+/// Returns a slice of `value` from the start of `first` to the end of `last`.
+fn span<'a>(value: &'a str, first: &'a str, last: &'a str) -> &'a str {
+    let start = first.as_ptr() as usize - value.as_ptr() as usize;
+    let end = last.as_ptr() as usize - value.as_ptr() as usize + last.len();
+    &value[start..end]
+}
+
+fn is_margin(text: &str) -> bool {
+    text.eq_ignore_ascii_case("auto") || is_length_percentage(text)
+}
+
+fn is_border_width(text: &str) -> bool {
+    is_keyword(text, &["thin", "medium", "thick"]) || is_length(text)
+}
+
+fn is_border_style(text: &str) -> bool {
+    is_keyword(text, &["none", "hidden", "dotted", "dashed", "solid", "double",
+                       "groove", "ridge", "inset", "outset"])
+}
+
+/// Applies the 1-to-4 value rule: `top [right [bottom [left]]]`.
+fn box_values<'a>(value: &str, parts: &[&'a str]) -> Result<[&'a str; 4], Error> {
+    match *parts {
+        [a] => Ok([a, a, a, a]),
+        [a, b] => Ok([a, b, a, b]),
+        [a, b, c] => Ok([a, b, c, b]),
+        [a, b, c, d] => Ok([a, b, c, d]),
+        _ => {
+            let part = parts.get(4).copied().unwrap_or(value);
+            Err(invalid(value, part))
+        }
+    }
+}
+
+fn expand_box(
+    value: &str,
+    parts: &[&str],
+    names: &[&'static str; 4],
+    check: fn(&str) -> bool,
+) -> Result<Longhands, Error> {
+    if let Some(part) = parts.iter().find(|p| !check(p)) {
+        return Err(invalid(value, part));
+    }
+
+    let values = box_values(value, parts)?;
+    Ok(names.iter().zip(values.iter()).map(|(name, v)| (*name, v.to_string())).collect())
+}
+
+fn expand_gap(value: &str, parts: &[&str]) -> Result<Longhands, Error> {
+    let is_gap = |p: &str| p.eq_ignore_ascii_case("normal") || is_length_percentage(p);
+    if let Some(part) = parts.iter().find(|p| !is_gap(p)) {
+        return Err(invalid(value, part));
+    }
+
+    let (row, column) = match *parts {
+        [a] => (a, a),
+        [a, b] => (a, b),
+        _ => return Err(invalid(value, parts[2])),
+    };
+
+    Ok(vec![(GAP[0], row.to_string()), (GAP[1], column.to_string())])
+}
+
+/// Parses `<line-width> || <line-style> || <color>`.
+fn parse_border_side<'a>(value: &str, parts: &[&'a str]) -> Result<[&'a str; 3], Error> {
+    let mut side: [Option<&str>; 3] = [None; 3];
+
+    for part in parts {
+        let idx = if is_border_width(part) {
+            0
+        } else if is_border_style(part) {
+            1
+        } else if is_color(part) {
+            2
+        } else {
+            return Err(invalid(value, part));
+        };
+
+        if side[idx].is_some() {
+            return Err(invalid(value, part));
+        }
+        side[idx] = Some(part);
+    }
+
+    Ok([
+        side[0].unwrap_or("medium"),
+        side[1].unwrap_or("none"),
+        side[2].unwrap_or("currentcolor"),
+    ])
+}
+
+fn expand_border_radius(value: &str, parts: &[&str]) -> Result<Longhands, Error> {
+    let (horizontal, vertical) = match parts.iter().position(|p| *p == "/") {
+        Some(idx) => (&parts[..idx], &parts[idx + 1..]),
+        None => (parts, parts),
+    };
+
+    if horizontal.is_empty() || vertical.is_empty() {
+        return Err(invalid(value, parts.last().unwrap()));
+    }
+
+    for part in horizontal.iter().chain(vertical.iter()) {
+        if !is_length_percentage(part) {
+            return Err(invalid(value, part));
+        }
+    }
+
+    let h = box_values(value, horizontal)?;
+    let v = box_values(value, vertical)?;
+
+    let mut list = Vec::with_capacity(4);
+    for i in 0..4 {
+        let radius = if h[i] == v[i] {
+            h[i].to_string()
+        } else {
+            format!("{} {}", h[i], v[i])
+        };
+        list.push((BORDER_RADIUS[i], radius));
+    }
+
+    Ok(list)
+}
+
+/// Expands `container: <name> [/ <type>]?`.
+fn expand_container(value: &str) -> Result<Longhands, Error> {
+    let (names, container_type) = match value.find('/') {
+        Some(idx) => (value[..idx].trim(), Some(value[idx + 1..].trim())),
+        None => (value.trim(), None),
+    };
+
+    if container::parse_container_names(names).is_none() {
+        return Err(invalid(value, names));
+    }
+
+    let container_type = match container_type {
+        Some(t) if ContainerType::from_name(t).is_some() => t,
+        Some(t) => return Err(invalid(value, t)),
+        None => "normal",
+    };
+
+    Ok(vec![
+        (CONTAINER[0], names.to_string()),
+        (CONTAINER[1], container_type.to_string()),
+    ])
+}
+
+fn expand_flex(value: &str, parts: &[&str]) -> Result<Longhands, Error> {
+    let make = |grow: &str, shrink: &str, basis: &str| {
+        vec![
+            (FLEX[0], grow.to_string()),
+            (FLEX[1], shrink.to_string()),
+            (FLEX[2], basis.to_string()),
+        ]
+    };
+
+    if let [single] = *parts {
+        if single.eq_ignore_ascii_case("none") {
+            return Ok(make("0", "0", "auto"));
+        }
+
+        if single.eq_ignore_ascii_case("auto") {
+            return Ok(make("1", "1", "auto"));
+        }
+    }
+
+    let is_basis = |p: &str| is_keyword(p, &["auto", "content"]) || is_length_percentage(p);
+
+    let mut grow = None;
+    let mut shrink = None;
+    let mut basis = None;
+    let mut prev_is_grow = false;
+
+    for part in parts {
+        if is_number(part) && grow.is_none() {
+            grow = Some(*part);
+            prev_is_grow = true;
+            continue;
+        }
+
+        if is_number(part) && prev_is_grow && shrink.is_none() {
+            shrink = Some(*part);
+            prev_is_grow = false;
+            continue;
+        }
+
+        prev_is_grow = false;
+
+        // A unitless zero is a flex factor, unless both factors are already set.
+        if is_basis(part) && basis.is_none() && (!is_number(part) || shrink.is_some()) {
+            basis = Some(*part);
+            continue;
+        }
+
+        return Err(invalid(value, part));
+    }
+
+    Ok(make(grow.unwrap_or("1"), shrink.unwrap_or("1"), basis.unwrap_or("0%")))
+}
+
+fn expand_font(value: &str, parts: &[&str]) -> Result<Longhands, Error> {
+    if let [single] = *parts {
+        if is_keyword(single, &["caption", "icon", "menu", "message-box",
+                                "small-caption", "status-bar"]) {
+            return Err(Error::UnsupportedToken(value::error_pos(value, single)));
+        }
+    }
+
+    let mut style = None;
+    let mut variant = None;
+    let mut weight = None;
+    let mut stretch = None;
+    let mut normals = 0;
+    let mut idx = 0;
+
+    // `[ <font-style> || <font-variant-css2> || <font-weight> || <font-stretch-css3> ]?`
+    while idx < parts.len() {
+        let part = parts[idx];
+
+        if part.eq_ignore_ascii_case("normal") {
+            normals += 1;
+        } else if style.is_none() && is_keyword(part, &["italic", "oblique"]) {
+            // `oblique` can be followed by an angle.
+            match parts.get(idx + 1) {
+                Some(next) if calc_type(next) == Some(CalcType::Angle) => {
+                    style = Some(span(value, part, next));
+                    idx += 1;
+                }
+                _ => style = Some(part),
+            }
+        } else if variant.is_none() && part.eq_ignore_ascii_case("small-caps") {
+            variant = Some(part);
+        } else if weight.is_none() && is_font_weight(part) {
+            weight = Some(part);
+        } else if stretch.is_none() && is_keyword(part, &[
+            "ultra-condensed", "extra-condensed", "condensed", "semi-condensed",
+            "semi-expanded", "expanded", "extra-expanded", "ultra-expanded"]) {
+            stretch = Some(part);
+        } else {
+            break;
+        }
+
+        idx += 1;
+    }
+
+    let prefix_count = [style, variant, weight, stretch].iter().filter(|v| v.is_some()).count();
+    if prefix_count + normals > 4 {
+        return Err(invalid(value, parts[idx - 1]));
+    }
+
+    let size = match parts.get(idx) {
+        Some(p) if is_font_size(p) => *p,
+        Some(p) => return Err(invalid(value, p)),
+        None => return Err(invalid(value, parts[parts.len() - 1])),
+    };
+    idx += 1;
+
+    let mut line_height = "normal";
+    if parts.get(idx) == Some(&"/") {
+        match parts.get(idx + 1) {
+            Some(p) if p.eq_ignore_ascii_case("normal") || is_number(p)
+                       || is_length_percentage(p) => line_height = p,
+            Some(p) => return Err(invalid(value, p)),
+            None => return Err(invalid(value, parts[idx])),
+        }
+        idx += 2;
+    }
+
+    let family = match parts.get(idx) {
+        Some(first) => span(value, first, parts[parts.len() - 1]),
+        None => return Err(invalid(value, parts[parts.len() - 1])),
+    };
+
+    Ok(vec![
+        (FONT[0], style.unwrap_or("normal").to_string()),
+        (FONT[1], variant.unwrap_or("normal").to_string()),
+        (FONT[2], weight.unwrap_or("normal").to_string()),
+        (FONT[3], stretch.unwrap_or("normal").to_string()),
+        (FONT[4], size.to_string()),
+        (FONT[5], line_height.to_string()),
+        (FONT[6], family.to_string()),
+    ])
+}
+
+fn is_font_weight(text: &str) -> bool {
+    if is_keyword(text, &["bold", "bolder", "lighter"]) {
+        return true;
+    }
+
+    match CalcNode::parse(text) {
+        Ok(CalcNode::Number(n)) => (1.0..=1000.0).contains(&n),
+        _ => false,
+    }
+}
+
+fn is_font_size(text: &str) -> bool {
+    is_keyword(text, &["xx-small", "x-small", "small", "medium", "large", "x-large",
+                       "xx-large", "xxx-large", "larger", "smaller"])
+        || is_length_percentage(text)
+}
+
+/// Joins per-layer values of each longhand with commas.
+fn join_layers(names: &'static [&'static str], layers: &[Vec<String>]) -> Longhands {
+    names.iter().enumerate().map(|(i, name)| {
+        let values: Vec<&str> = layers.iter().map(|layer| layer[i].as_str()).collect();
+        (*name, values.join(", "))
+    }).collect()
+}
+
+fn expand_background(value: &str) -> Result<Longhands, Error> {
+    let list = value::split_list(value);
+    let mut layers = Vec::with_capacity(list.len());
+
+    for (i, layer) in list.iter().enumerate() {
+        let is_last = i + 1 == list.len();
+        layers.push(parse_background_layer(value, layer, is_last)?);
+    }
+
+    // `background-color` is not a list.
+    let mut longhands = join_layers(&BACKGROUND, &layers);
+    longhands[0].1 = layers.last().unwrap()[0].clone();
+    Ok(longhands)
+}
+
+fn is_position(text: &str) -> bool {
+    is_keyword(text, &["left", "right", "top", "bottom", "center"]) || is_length_percentage(text)
+}
+
+fn is_box(text: &str) -> bool {
+    is_keyword(text, &["border-box", "padding-box", "content-box"])
+}
+
+fn parse_background_layer(value: &str, layer: &str, is_last: bool) -> Result<Vec<String>, Error> {
+    let parts = value::split_components(layer);
+    if parts.is_empty() {
+        return Err(invalid(value, layer));
+    }
+
+    let mut color = None;
+    let mut image = None;
+    let mut position = None;
+    let mut size = None;
+    let mut repeat = None;
+    let mut attachment = None;
+    let mut origin = None;
+    let mut clip = None;
+    let mut idx = 0;
+
+    while idx < parts.len() {
+        let part = parts[idx];
+
+        if image.is_none() && is_image(part) {
+            image = Some(part);
+        } else if position.is_none() && is_position(part) {
+            let start = idx;
+            while idx + 1 < parts.len() && idx - start < 3 && is_position(parts[idx + 1]) {
+                idx += 1;
+            }
+            position = Some(span(value, part, parts[idx]));
+
+            // `<bg-position> [ / <bg-size> ]?`
+            if parts.get(idx + 1) == Some(&"/") {
+                let first = match parts.get(idx + 2) {
+                    Some(p) => *p,
+                    None => return Err(invalid(value, parts[idx + 1])),
+                };
+
+                let is_size = |p: &str| p.eq_ignore_ascii_case("auto") || is_length_percentage(p);
+                if is_keyword(first, &["cover", "contain"]) {
+                    size = Some(first);
+                    idx += 2;
+                } else if is_size(first) {
+                    idx += 2;
+                    let mut last = first;
+                    if let Some(second) = parts.get(idx + 1) {
+                        if is_size(second) {
+                            last = second;
+                            idx += 1;
+                        }
+                    }
+                    size = Some(span(value, first, last));
+                } else {
+                    return Err(invalid(value, first));
+                }
+            }
+        } else if repeat.is_none() && is_keyword(part, &["repeat-x", "repeat-y"]) {
+            repeat = Some(part);
+        } else if repeat.is_none() && is_keyword(part, &["repeat", "space", "round", "no-repeat"]) {
+            let mut last = part;
+            if let Some(next) = parts.get(idx + 1) {
+                if is_keyword(next, &["repeat", "space", "round", "no-repeat"]) {
+                    last = next;
+                    idx += 1;
+                }
+            }
+            repeat = Some(span(value, part, last));
+        } else if attachment.is_none() && is_keyword(part, &["scroll", "fixed", "local"]) {
+            attachment = Some(part);
+        } else if origin.is_none() && is_box(part) {
+            origin = Some(part);
+        } else if origin.is_some() && clip.is_none() && is_box(part) {
+            clip = Some(part);
+        } else if is_last && color.is_none() && is_color(part) {
+            color = Some(part);
+        } else {
+            return Err(invalid(value, part));
+        }
+
+        idx += 1;
+    }
+
+    Ok(vec![
+        color.unwrap_or("transparent").to_string(),
+        image.unwrap_or("none").to_string(),
+        position.unwrap_or("0% 0%").to_string(),
+        size.unwrap_or("auto").to_string(),
+        repeat.unwrap_or("repeat").to_string(),
+        attachment.unwrap_or("scroll").to_string(),
+        origin.unwrap_or("padding-box").to_string(),
+        clip.or(origin).unwrap_or("border-box").to_string(),
+    ])
+}
+
+fn is_easing_function(text: &str) -> bool {
+    if is_keyword(text, &["ease", "linear", "ease-in", "ease-out", "ease-in-out",
+                          "step-start", "step-end"]) {
+        return true;
+    }
+
+    let lower = text.to_ascii_lowercase();
+    (lower.starts_with("cubic-bezier(") || lower.starts_with("steps(")
+        || lower.starts_with("linear(")) && lower.ends_with(')')
+}
+
+fn expand_transition(value: &str) -> Result<Longhands, Error> {
+    let list = value::split_list(value);
+    let mut layers = Vec::with_capacity(list.len());
+
+    for layer in &list {
+        let parts = value::split_components(layer);
+        if parts.is_empty() {
+            return Err(invalid(value, layer));
+        }
+
+        let mut property = None;
+        let mut duration = None;
+        let mut easing = None;
+        let mut delay = None;
+
+        for part in parts {
+            if is_time(part) && duration.is_none() {
+                duration = Some(part);
+            } else if is_time(part) && delay.is_none() {
+                delay = Some(part);
+            } else if easing.is_none() && is_easing_function(part) {
+                easing = Some(part);
+            } else if property.is_none() && is_custom_ident(part) {
+                // `none` is only valid as a single layer.
+                if part.eq_ignore_ascii_case("none") && list.len() > 1 {
+                    return Err(invalid(value, part));
+                }
+                property = Some(part);
+            } else {
+                return Err(invalid(value, part));
+            }
+        }
+
+        layers.push(vec![
+            property.unwrap_or("all").to_string(),
+            duration.unwrap_or("0s").to_string(),
+            easing.unwrap_or("ease").to_string(),
+            delay.unwrap_or("0s").to_string(),
+        ]);
+    }
+
+    Ok(join_layers(&TRANSITION, &layers))
+}
+
+fn expand_animation(value: &str) -> Result<Longhands, Error> {
+    let list = value::split_list(value);
+    let mut layers = Vec::with_capacity(list.len());
+
+    for layer in &list {
+        let parts = value::split_components(layer);
+        if parts.is_empty() {
+            return Err(invalid(value, layer));
+        }
+
+        let mut name = None;
+        let mut duration = None;
+        let mut easing = None;
+        let mut delay = None;
+        let mut iteration_count = None;
+        let mut direction = None;
+        let mut fill_mode = None;
+        let mut play_state = None;
+
+        // Keywords are assigned to the first property they are valid for,
+        // and the name is the last resort.
+        for part in parts {
+            if is_time(part) && duration.is_none() {
+                duration = Some(part);
+            } else if is_time(part) && delay.is_none() {
+                delay = Some(part);
+            } else if easing.is_none() && is_easing_function(part) {
+                easing = Some(part);
+            } else if iteration_count.is_none()
+                && (part.eq_ignore_ascii_case("infinite") || is_number(part)) {
+                iteration_count = Some(part);
+            } else if direction.is_none()
+                && is_keyword(part, &["normal", "reverse", "alternate", "alternate-reverse"]) {
+                direction = Some(part);
+            } else if fill_mode.is_none()
+                && is_keyword(part, &["none", "forwards", "backwards", "both"]) {
+                fill_mode = Some(part);
+            } else if play_state.is_none() && is_keyword(part, &["running", "paused"]) {
+                play_state = Some(part);
+            } else if name.is_none() && (is_custom_ident(part) || part.starts_with('"')
+                                         || part.starts_with('\'')) {
+                name = Some(part);
+            } else {
+                return Err(invalid(value, part));
+            }
+        }
+
+        layers.push(vec![
+            name.unwrap_or("none").to_string(),
+            duration.unwrap_or("0s").to_string(),
+            easing.unwrap_or("ease").to_string(),
+            delay.unwrap_or("0s").to_string(),
+            iteration_count.unwrap_or("1").to_string(),
+            direction.unwrap_or("normal").to_string(),
+            fill_mode.unwrap_or("none").to_string(),
+            play_state.unwrap_or("running").to_string(),
+        ]);
+    }
+
+    Ok(join_layers(&ANIMATION, &layers))
+}
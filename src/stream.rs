@@ -19,30 +19,29 @@ pub struct Stream<'a> {
 
 #[inline]
 fn is_letter(c: u8) -> bool {
-    match c {
-        b'A'..=b'Z' => true,
-        b'a'..=b'z' => true,
-        _ => false,
-    }
+    c.is_ascii_alphabetic()
 }
 
 #[inline]
 fn is_digit(c: u8) -> bool {
-    match c {
-        b'0'..=b'9' => true,
-        _ => false,
-    }
+    c.is_ascii_digit()
 }
 
 #[inline]
 pub fn is_space(c: u8) -> bool {
-    match c {
-          b' '
-        | b'\t'
-        | b'\n'
-        | b'\r' => true,
-        _ => false,
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Returns the contents of a quoted string, if `text` is one.
+pub fn unquote(text: &str) -> Option<&str> {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 2 && (bytes[0] == b'"' || bytes[0] == b'\'')
+        && bytes[bytes.len() - 1] == bytes[0]
+    {
+        return Some(&text[1..text.len() - 1]);
     }
+
+    None
 }
 
 impl<'a> Stream<'a> {
@@ -59,7 +58,7 @@ impl<'a> Stream<'a> {
     /// Constructs a new `Stream` from data.
     #[inline]
     pub fn new_bound(text: &[u8], start: usize, end: usize) -> Stream<'_> {
-        assert!(start < end);
+        assert!(start <= end);
 
         Stream {
             text,
@@ -74,6 +73,12 @@ impl<'a> Stream<'a> {
         self.pos
     }
 
+    /// Returns the end position (exclusive).
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
     /// Returns `true` if we are at the end of the stream.
     ///
     /// Any [`pos()`] value larger than original text length indicates stream end.
@@ -172,6 +177,212 @@ impl<'a> Stream<'a> {
         }
     }
 
+    /// Returns a char at `offset` from the current position, if any.
+    #[inline]
+    pub fn char_at(&self, offset: usize) -> Option<u8> {
+        if self.pos + offset < self.end {
+            Some(self.text[self.pos + offset])
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the text at the current position starts with `text`.
+    #[inline]
+    pub fn starts_with(&self, text: &[u8]) -> bool {
+        self.text[self.pos..self.end].starts_with(text)
+    }
+
+    /// Consumes the selected char.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownToken` if the current char is not `c`.
+    pub fn consume_char(&mut self, c: u8) -> Result<(), Error> {
+        if !self.is_char_eq(c)? {
+            return Err(Error::UnknownToken(self.gen_error_pos()));
+        }
+
+        self.advance_raw(1);
+        Ok(())
+    }
+
+    /// Skips a quoted string, including the quotes.
+    ///
+    /// Escaped chars are skipped too. An unterminated string is skipped up to the stream end.
+    pub fn skip_quoted_string(&mut self) {
+        let quote = self.curr_char_raw();
+        self.advance_raw(1);
+        while !self.at_end() {
+            let c = self.curr_char_raw();
+            self.advance_raw(1);
+            if c == quote {
+                break;
+            }
+            if c == b'\\' && !self.at_end() {
+                self.advance_raw(1); // skip escaped char
+            }
+        }
+    }
+
+    /// Consumes an ident.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownToken` if there is no ident at the current position.
+    pub fn consume_ident(&mut self) -> Result<&'a str, Error> {
+        let start = self.pos;
+
+        while !self.at_end() && self.is_ident_raw() {
+            self.advance_raw(1);
+        }
+
+        if start == self.pos {
+            return Err(Error::UnknownToken(self.gen_error_pos()));
+        }
+
+        Ok(self.slice_region_raw_str(start, self.pos))
+    }
+
+    /// Consumes the selected ident, ASCII case-insensitive.
+    ///
+    /// Returns `false` and consumes nothing if the next ident is different.
+    pub fn try_consume_keyword(&mut self, keyword: &str) -> bool {
+        let mut s = *self;
+        match s.consume_ident() {
+            Ok(ident) if ident.eq_ignore_ascii_case(keyword) => {
+                *self = s;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes a parenthesized block, like `(min-width: 800px)`, and returns it
+    /// including the parentheses.
+    ///
+    /// Nested parentheses and strings are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownToken` if the current char is not `(`
+    /// or the block is not closed.
+    pub fn consume_parenthesized(&mut self) -> Result<&'a str, Error> {
+        if !self.is_char_eq(b'(')? {
+            return Err(Error::UnknownToken(self.gen_error_pos()));
+        }
+
+        let start = self.pos;
+        self.advance_raw(1);
+
+        let mut depth = 1;
+        while !self.at_end() && depth > 0 {
+            match self.curr_char_raw() {
+                b'(' => depth += 1,
+                b')' => depth -= 1,
+                b'"' | b'\'' => {
+                    self.skip_quoted_string();
+                    continue;
+                }
+                _ => {}
+            }
+
+            self.advance_raw(1);
+        }
+
+        if depth != 0 {
+            return Err(Error::UnknownToken(self.gen_error_pos()));
+        }
+
+        Ok(self.slice_region_raw_str(start, self.pos))
+    }
+
+    /// Consumes a string or a `url()` and returns the URL without quotes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownToken` if there is no string or `url()` at the current position.
+    pub fn consume_url(&mut self) -> Result<&'a str, Error> {
+        let start = self.pos;
+
+        if let b'"' | b'\'' = self.curr_char()? {
+            self.skip_quoted_string();
+            let text = self.slice_region_raw_str(start, self.pos);
+            return unquote(text).ok_or(Error::UnexpectedEndOfStream(self.gen_error_pos()));
+        }
+
+        if !self.try_consume_keyword("url") || self.curr_char() != Ok(b'(') {
+            return Err(Error::UnknownToken(self.gen_error_pos_at(start)));
+        }
+
+        let block = self.consume_parenthesized()?;
+        let url = block[1..block.len() - 1].trim();
+        Ok(unquote(url).unwrap_or(url))
+    }
+
+    /// Consumes a number, like `1`, `-0.5`, `.5` or `1e3`.
+    ///
+    /// Units are not consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownToken` if there is no number at the current position.
+    pub fn consume_number(&mut self) -> Result<f32, Error> {
+        let start = self.pos;
+
+        if let Some(b'+') | Some(b'-') = self.char_at(0) {
+            self.advance_raw(1);
+        }
+
+        let mut has_digits = false;
+        while let Some(c) = self.char_at(0) {
+            if !is_digit(c) {
+                break;
+            }
+            has_digits = true;
+            self.advance_raw(1);
+        }
+
+        if self.char_at(0) == Some(b'.') && self.char_at(1).is_some_and(is_digit) {
+            self.advance_raw(1);
+            while let Some(c) = self.char_at(0) {
+                if !is_digit(c) {
+                    break;
+                }
+                has_digits = true;
+                self.advance_raw(1);
+            }
+        }
+
+        if !has_digits {
+            self.pos = start;
+            return Err(Error::UnknownToken(self.gen_error_pos()));
+        }
+
+        // The exponent is only consumed when followed by digits,
+        // so units like `em` and `ex` are left untouched.
+        if let Some(b'e') | Some(b'E') = self.char_at(0) {
+            let n = match self.char_at(1) {
+                Some(b'+') | Some(b'-') => 2,
+                _ => 1,
+            };
+
+            if self.char_at(n).is_some_and(is_digit) {
+                self.advance_raw(n);
+                while let Some(c) = self.char_at(0) {
+                    if !is_digit(c) {
+                        break;
+                    }
+                    self.advance_raw(1);
+                }
+            }
+        }
+
+        let s = self.slice_region_raw_str(start, self.pos);
+        // Can't fail, since we checked the number syntax above.
+        Ok(s.parse().unwrap())
+    }
+
     #[inline]
     fn get_char_raw(&self, pos: usize) -> u8 {
         self.text[pos]
@@ -220,15 +431,15 @@ impl<'a> Stream<'a> {
         str::from_utf8(&self.text[start..end]).unwrap()
     }
 
-    fn calc_current_row(&self) -> usize {
+    fn calc_row(&self, pos: usize) -> usize {
         let mut row = 1;
-        row += self.text.iter().take(self.pos).filter(|c| **c == b'\n').count();
+        row += self.text.iter().take(pos).filter(|c| **c == b'\n').count();
         row
     }
 
-    fn calc_current_col(&self) -> usize {
+    fn calc_col(&self, pos: usize) -> usize {
         let mut col = 1;
-        for n in 0..self.pos {
+        for n in 0..pos {
             if n > 0 && self.text[n-1] == b'\n' {
                 col = 2;
             } else {
@@ -241,9 +452,17 @@ impl<'a> Stream<'a> {
 
     /// Calculates a current absolute position.
     pub fn gen_error_pos(&self) -> ErrorPos {
-        let row = self.calc_current_row();
-        let col = self.calc_current_col();
-        ErrorPos::new(row, col)
+        self.gen_error_pos_at(self.pos)
+    }
+
+    /// Calculates an absolute position of the selected stream position.
+    pub fn gen_error_pos_at(&self, pos: usize) -> ErrorPos {
+        ErrorPos::new(self.calc_row(pos), self.calc_col(pos))
+    }
+
+    /// Returns a stream position of the `text`, which must be a subslice of the stream data.
+    pub fn offset_of(&self, text: &str) -> usize {
+        text.as_ptr() as usize - self.text.as_ptr() as usize
     }
 
     /// Generates a new `UnexpectedEndOfStream` error from the current position.
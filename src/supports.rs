@@ -0,0 +1,184 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::stream::Stream;
+use crate::error::Error;
+
+/// An `@supports` condition.
+///
+/// https://www.w3.org/TR/css-conditional-3/#at-supports
+#[derive(Clone,PartialEq,Debug)]
+pub enum SupportsCondition<'a> {
+    /// A feature test, like `(display: grid)` or `selector(:has(a))`.
+    Feature(SupportsFeature<'a>),
+    /// `not <condition>`
+    Not(Box<SupportsCondition<'a>>),
+    /// `<condition> and <condition> ...`
+    And(Vec<SupportsCondition<'a>>),
+    /// `<condition> or <condition> ...`
+    Or(Vec<SupportsCondition<'a>>),
+    /// Unknown content in parentheses or a function, like `(foo bar)` or `baz()`.
+    ///
+    /// Always evaluates to false. Contains the raw text, including the parentheses.
+    Unknown(&'a str),
+}
+
+/// An `@supports` feature test.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum SupportsFeature<'a> {
+    /// A declaration test, like `(display: grid)`.
+    ///
+    /// The value is trimmed, but otherwise stored as is.
+    Declaration(&'a str, &'a str),
+    /// A selector test, like `selector(:has(a))`. Contains the trimmed selector text.
+    Selector(&'a str),
+}
+
+impl<'a> SupportsCondition<'a> {
+    /// Parses an `@supports` condition, like the `@supports` prelude.
+    ///
+    /// Error positions are relative to `text`.
+    pub fn parse(text: &'a str) -> Result<SupportsCondition<'a>, Error> {
+        let mut s = Stream::new(text.as_bytes());
+        let condition = parse_condition(&mut s)?;
+
+        s.skip_spaces();
+        if !s.at_end() {
+            return Err(Error::UnknownToken(s.gen_error_pos()));
+        }
+
+        Ok(condition)
+    }
+
+    /// Evaluates the condition.
+    ///
+    /// `is_supported` is called for every feature test that affects the result.
+    /// `validate_declaration` can be used to check declarations against the built-in
+    /// property registry.
+    pub fn eval<F>(&self, is_supported: &F) -> bool
+        where F: Fn(SupportsFeature<'a>) -> bool
+    {
+        match *self {
+            SupportsCondition::Feature(f) => is_supported(f),
+            SupportsCondition::Not(ref c) => !c.eval(is_supported),
+            SupportsCondition::And(ref list) => list.iter().all(|c| c.eval(is_supported)),
+            SupportsCondition::Or(ref list) => list.iter().any(|c| c.eval(is_supported)),
+            SupportsCondition::Unknown(_) => false,
+        }
+    }
+}
+
+fn parse_condition<'a>(s: &mut Stream<'a>) -> Result<SupportsCondition<'a>, Error> {
+    s.skip_spaces();
+
+    if s.try_consume_keyword("not") {
+        s.skip_spaces();
+        return Ok(SupportsCondition::Not(Box::new(parse_in_parens(s)?)));
+    }
+
+    let mut list = vec![parse_in_parens(s)?];
+    let mut is_or = None;
+
+    loop {
+        let mut tmp = *s;
+        tmp.skip_spaces();
+        let op_pos = tmp.gen_error_pos();
+
+        let next_is_or = if tmp.try_consume_keyword("and") {
+            false
+        } else if tmp.try_consume_keyword("or") {
+            true
+        } else {
+            break;
+        };
+
+        // `and` and `or` can't be mixed without parentheses.
+        if is_or.is_some() && is_or != Some(next_is_or) {
+            return Err(Error::UnknownToken(op_pos));
+        }
+        is_or = Some(next_is_or);
+
+        *s = tmp;
+        s.skip_spaces();
+        list.push(parse_in_parens(s)?);
+    }
+
+    Ok(match is_or {
+        None => list.pop().unwrap(),
+        Some(false) => SupportsCondition::And(list),
+        Some(true) => SupportsCondition::Or(list),
+    })
+}
+
+fn parse_in_parens<'a>(s: &mut Stream<'a>) -> Result<SupportsCondition<'a>, Error> {
+    let start = s.pos();
+
+    if !s.is_char_eq(b'(')? {
+        // A function, like `selector()`.
+        let name = s.consume_ident()?;
+        let block = s.consume_parenthesized()?;
+
+        if name.eq_ignore_ascii_case("selector") {
+            let selector = block[1..block.len() - 1].trim();
+            if !selector.is_empty() {
+                return Ok(SupportsCondition::Feature(SupportsFeature::Selector(selector)));
+            }
+        }
+
+        return Ok(SupportsCondition::Unknown(s.slice_region_raw_str(start, s.pos())));
+    }
+
+    let block = s.consume_parenthesized()?;
+    let inner = &block[1..block.len() - 1];
+
+    let mut inner_stream = Stream::new(inner.as_bytes());
+    inner_stream.skip_spaces();
+    let is_nested = starts_with_condition(inner_stream);
+
+    if is_nested {
+        let mut tmp = inner_stream;
+        if let Ok(condition) = parse_condition(&mut tmp) {
+            tmp.skip_spaces();
+            if tmp.at_end() {
+                return Ok(condition);
+            }
+        }
+    } else if let Some(feature) = parse_declaration(inner) {
+        return Ok(SupportsCondition::Feature(feature));
+    }
+
+    Ok(SupportsCondition::Unknown(block))
+}
+
+fn starts_with_condition(mut s: Stream) -> bool {
+    if s.curr_char() == Ok(b'(') {
+        return true;
+    }
+
+    if s.try_consume_keyword("not") {
+        s.skip_spaces();
+        if s.curr_char() == Ok(b'(') {
+            return true;
+        }
+    }
+
+    // A function, like `selector()`.
+    s.consume_ident().is_ok() && s.curr_char() == Ok(b'(')
+}
+
+/// Parses `name: value`. The value can't be empty.
+pub(crate) fn parse_declaration(text: &str) -> Option<SupportsFeature<'_>> {
+    let mut s = Stream::new(text.as_bytes());
+    s.skip_spaces();
+    let name = s.consume_ident().ok()?;
+    s.skip_spaces();
+    s.consume_char(b':').ok()?;
+
+    let value = text[s.pos()..].trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    Some(SupportsFeature::Declaration(name, value))
+}
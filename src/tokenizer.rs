@@ -4,10 +4,11 @@
 
 use crate::stream;
 use crate::stream::Stream;
-use crate::error::Error;
+use crate::error::{Error, ErrorPos};
+use crate::selector::{self, NamespaceConstraint, Namespaces};
 
 /// CSS combinator.
-#[derive(PartialEq,Debug)]
+#[derive(Clone,Copy,PartialEq,Debug)]
 pub enum Combinator {
     /// Descendant selector
     Space,
@@ -30,6 +31,19 @@ pub enum Token<'a> {
     ///
     /// https://www.w3.org/TR/CSS21/selector.html#type-selectors
     TypeSelector(&'a str),
+    /// Universal selector with a namespace prefix, like `svg|*` or `*|*`
+    ///
+    /// https://www.w3.org/TR/selectors-3/#univnmsp
+    QualifiedUniversalSelector(NamespaceConstraint<'a>),
+    /// Type selector with a namespace prefix, like `svg|circle` or `|p`
+    ///
+    /// https://www.w3.org/TR/selectors-3/#typenmsp
+    QualifiedTypeSelector {
+        /// Namespace prefix
+        namespace: NamespaceConstraint<'a>,
+        /// Element name
+        name: &'a str,
+    },
     /// ID selector
     ///
     /// Value contains ident without `#`.
@@ -44,7 +58,7 @@ pub enum Token<'a> {
     ClassSelector(&'a str),
     /// Attribute selector
     ///
-    /// We do not parse it's content yet, so value contains everything between `[]`.
+    /// Value contains everything between `[]`. Use `Attribute::parse` to parse it.
     ///
     /// https://www.w3.org/TR/CSS21/selector.html#attribute-selectors
     AttributeSelector(&'a str),
@@ -87,8 +101,36 @@ pub enum Token<'a> {
     /// `@` rule (excluding the `@` sign itself). The content is not parsed,
     /// for example `@keyframes mymove` = `AtRule("keyframes"), AtStr("mymove")`.
     AtRule(&'a str),
+    /// End of a block-less at-rule, like `@import "a.css";`.
+    AtRuleEnd,
+    /// `@charset` rule with the encoding name without quotes.
+    ///
+    /// https://www.w3.org/TR/css-syntax-3/#charset-rule
+    Charset(&'a str),
+    /// `@namespace` rule.
+    ///
+    /// https://www.w3.org/TR/css-namespaces-3/
+    Namespace {
+        /// Namespace prefix. `None` for the default namespace.
+        prefix: Option<&'a str>,
+        /// Namespace URI without quotes.
+        uri: &'a str,
+    },
     /// Raw Str inside of block
     DeclarationStr(&'a str),
+    /// Custom property declaration
+    ///
+    /// Contains property name with the leading `--` and the value as written,
+    /// with leading and trailing spaces removed. The value can be empty.
+    ///
+    /// https://www.w3.org/TR/css-variables-1/#defining-variables
+    CustomProperty(&'a str, &'a str),
+    /// Keyframe selector inside a `@keyframes` block, as a percentage.
+    ///
+    /// `from` is `0.0` and `to` is `100.0`.
+    ///
+    /// https://www.w3.org/TR/css-animations-1/#typedef-keyframe-selector
+    KeyframeSelector(f32),
     /// String following an @rule
     AtStr(&'a str),
     /// Same as PseudoClass, but with two colons (`::thing`).
@@ -98,6 +140,16 @@ pub enum Token<'a> {
         /// The optional value inside parentheses
         value: Option<&'a str>,
     },
+    /// `<!--` between top-level rules.
+    ///
+    /// Only returned when enabled by `Tokenizer::set_cdo_cdc_tokens`.
+    ///
+    /// https://www.w3.org/TR/css-syntax-3/#CDO-token-diagram
+    Cdo,
+    /// `-->` between top-level rules.
+    ///
+    /// Only returned when enabled by `Tokenizer::set_cdo_cdc_tokens`.
+    Cdc,
     /// End of stream
     ///
     /// Parsing is finished.
@@ -121,6 +173,20 @@ pub struct Tokenizer<'a> {
     /// Track nesting depth for nested @-rules support
     /// Each entry is true if the block at that level was started by an @-rule
     nesting_stack: Vec<bool>,
+    /// Set after a `@keyframes` rule, until its block starts.
+    keyframes_pending: bool,
+    /// Nesting depth of the current `@keyframes` block.
+    keyframes_depth: Option<usize>,
+    /// Namespaces declared by `@namespace` rules so far.
+    namespaces: Namespaces<'a>,
+    /// Set for a declaration list, like a `style` attribute.
+    declaration_list: bool,
+    /// Set for a standalone selector list, like a `querySelector` argument.
+    selector_list: bool,
+    /// Set between top-level rules, where CDO and CDC are allowed.
+    rule_start: bool,
+    /// Return CDO and CDC as tokens instead of skipping them.
+    cdo_cdc_tokens: bool,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -133,6 +199,13 @@ impl<'a> Tokenizer<'a> {
             has_at_rule: false,
             at_start: true,
             nesting_stack: Vec::new(),
+            keyframes_pending: false,
+            keyframes_depth: None,
+            namespaces: Namespaces::new(),
+            declaration_list: false,
+            selector_list: false,
+            rule_start: true,
+            cdo_cdc_tokens: false,
         }
     }
 
@@ -151,25 +224,144 @@ impl<'a> Tokenizer<'a> {
             has_at_rule: false,
             at_start: true,
             nesting_stack: Vec::new(),
+            keyframes_pending: false,
+            keyframes_depth: None,
+            namespaces: Namespaces::new(),
+            declaration_list: false,
+            selector_list: false,
+            rule_start: true,
+            cdo_cdc_tokens: false,
         }
     }
 
+    /// Constructs a new `Tokenizer` for a declaration list, like the value of
+    /// a `style` attribute: `color: red; margin: 0`.
+    ///
+    /// Only `Token::Declaration`, `Token::CustomProperty` and `Token::EndOfStream`
+    /// are returned. The last declaration doesn't require a `;`.
+    /// Selectors, blocks and at-rules are errors.
+    pub fn new_declaration_list(text: &str) -> Tokenizer<'_> {
+        let mut tokenizer = Tokenizer::new(text);
+        tokenizer.state = State::Declaration;
+        tokenizer.declaration_list = true;
+        tokenizer
+    }
+
+    /// Constructs a new bounded `Tokenizer` for a declaration list.
+    ///
+    /// See [`new_bound()`] and [`new_declaration_list()`].
+    ///
+    /// [`new_bound()`]: #method.new_bound
+    /// [`new_declaration_list()`]: #method.new_declaration_list
+    pub fn new_declaration_list_bound(text: &str, start: usize, end: usize) -> Tokenizer<'_> {
+        let mut tokenizer = Tokenizer::new_bound(text, start, end);
+        tokenizer.state = State::Declaration;
+        tokenizer.declaration_list = true;
+        tokenizer
+    }
+
+    /// Constructs a new `Tokenizer` for a standalone selector list,
+    /// like `.list > li:nth-child(2)`.
+    ///
+    /// Only selector tokens, `Token::Comma` and `Token::EndOfStream` are returned.
+    /// Blocks, declarations, at-rules, an empty list and a trailing combinator or comma
+    /// are errors. Use `SelectorList::parse` to get a parsed selector list instead.
+    pub fn new_selector_list(text: &str) -> Tokenizer<'_> {
+        let mut tokenizer = Tokenizer::new(text);
+        tokenizer.selector_list = true;
+        tokenizer.rule_start = false;
+        tokenizer
+    }
+
+    /// Constructs a new bounded `Tokenizer` for a selector list.
+    ///
+    /// See [`new_bound()`] and [`new_selector_list()`].
+    ///
+    /// [`new_bound()`]: #method.new_bound
+    /// [`new_selector_list()`]: #method.new_selector_list
+    pub fn new_selector_list_bound(text: &str, start: usize, end: usize) -> Tokenizer<'_> {
+        let mut tokenizer = Tokenizer::new_bound(text, start, end);
+        tokenizer.selector_list = true;
+        tokenizer.rule_start = false;
+        tokenizer
+    }
+
+    /// Sets whether `<!--` and `-->` between top-level rules are returned
+    /// as `Token::Cdo` and `Token::Cdc`.
+    ///
+    /// By default they are skipped, like the spec requires.
+    /// Tokens are useful for writing the stylesheet back without losses.
+    pub fn set_cdo_cdc_tokens(&mut self, enabled: bool) {
+        self.cdo_cdc_tokens = enabled;
+    }
+
     /// Returns a current position in the text.
     pub fn pos(&self) -> usize {
         self.stream.pos()
     }
 
+    /// Returns an absolute position of the `text`, which must be a subslice
+    /// of a token returned by this tokenizer.
+    pub(crate) fn error_pos_of(&self, text: &str) -> ErrorPos {
+        self.stream.gen_error_pos_at(self.stream.offset_of(text))
+    }
+
+    /// Returns a namespace URI declared for the `prefix` by a `@namespace` rule
+    /// that was already parsed.
+    ///
+    /// Prefixes are case-sensitive.
+    pub fn namespace_uri(&self, prefix: &str) -> Option<&'a str> {
+        self.namespaces.uri(prefix)
+    }
+
+    /// Returns a default namespace URI declared by a `@namespace` rule
+    /// that was already parsed.
+    pub fn default_namespace(&self) -> Option<&'a str> {
+        self.namespaces.default_uri()
+    }
+
+    /// Returns namespaces declared by `@namespace` rules that were already parsed.
+    ///
+    /// Can be used to match namespace-qualified selectors.
+    pub fn namespaces(&self) -> &Namespaces<'a> {
+        &self.namespaces
+    }
+
+    /// Returns a byte offset of the `text`, which must be a subslice
+    /// of a token returned by this tokenizer.
+    pub(crate) fn offset_of(&self, text: &str) -> usize {
+        self.stream.offset_of(text)
+    }
+
     /// Parses a next token.
     pub fn parse_next(&mut self) -> Result<Token<'a>, Error> {
+        let token = self.consume_token()?;
+
+        self.rule_start = self.nesting_stack.is_empty() && matches!(token,
+            Token::BlockEnd | Token::AtRuleEnd | Token::Charset(_) | Token::Namespace { .. }
+            | Token::Cdo | Token::Cdc);
+
+        Ok(token)
+    }
+
+    fn consume_token(&mut self) -> Result<Token<'a>, Error> {
         if self.at_start {
             self.stream.skip_spaces();
             self.at_start = false;
         }
 
+        if self.selector_list {
+            return self.consume_selector_list_item();
+        }
+
         if self.stream.at_end() {
             return Ok(Token::EndOfStream);
         }
 
+        if self.declaration_list {
+            return self.consume_declaration_list_item();
+        }
+
         match self.state {
             State::Rule         => self.consume_rule(),
             State::Declaration  => self.consume_declaration(),
@@ -177,18 +369,124 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Parses a raw at-rule prelude.
+    ///
+    /// Should be called right after `Token::AtRule`, instead of parsing `Token::AtStr` tokens.
+    /// Returns everything up to the `{` or `;` that ends the prelude, without surrounding spaces.
+    /// For example, `screen and (min-width: 800px)` for
+    /// `@media screen and (min-width: 800px) { ... }`. The prelude can be empty.
+    ///
+    /// The next `parse_next()` call will return `Token::BlockStart` if the prelude ends with `{`
+    /// or `Token::AtRuleEnd` if it ends with `;`, like in `@import "a.css";`.
+    pub fn parse_at_rule_prelude(&mut self) -> Result<&'a str, Error> {
+        if !self.has_at_rule {
+            return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+        }
+
+        self.stream.skip_spaces();
+
+        let start = self.stream.pos();
+        let mut depth = 0;
+
+        loop {
+            match self.stream.curr_char()? {
+                b'(' | b'[' => depth += 1,
+                b')' | b']' => {
+                    if depth == 0 {
+                        return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+                    }
+                    depth -= 1;
+                }
+                b'{' | b';' if depth == 0 => break,
+                b'"' | b'\'' => {
+                    self.stream.skip_quoted_string();
+                    continue;
+                }
+                _ => {}
+            }
+
+            self.stream.advance_raw(1);
+        }
+
+        Ok(trim_end(self.stream.slice_region_raw_str(start, self.stream.pos())))
+    }
+
+    /// Parses declarations up to and including `Token::BlockEnd`.
+    ///
+    /// Only declarations and custom properties for which `accept` returns `true`
+    /// are collected. Nested blocks are skipped.
+    pub(crate) fn parse_declarations(
+        &mut self,
+        accept: impl Fn(&str, &str) -> bool,
+    ) -> Result<Vec<(&'a str, &'a str)>, Error> {
+        let mut declarations = Vec::new();
+
+        loop {
+            match self.parse_next()? {
+                Token::Declaration(name, value) | Token::CustomProperty(name, value)
+                    if accept(name, value) => declarations.push((name, value)),
+                Token::BlockStart => self.skip_block()?,
+                Token::BlockEnd | Token::EndOfStream => break,
+                _ => {}
+            }
+        }
+
+        Ok(declarations)
+    }
+
+    /// Skips tokens up to and including the `Token::BlockEnd` of the current block.
+    ///
+    /// Should be called after `Token::BlockStart`. Stops at the end of stream.
+    pub(crate) fn skip_block(&mut self) -> Result<(), Error> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.parse_next()? {
+                Token::BlockStart => depth += 1,
+                Token::BlockEnd => depth -= 1,
+                Token::EndOfStream => return Ok(()),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
     fn consume_rule(&mut self) -> Result<Token<'a>, Error> {
+        if self.rule_start {
+            if let Some(token) = self.consume_cdo_cdc() {
+                if self.cdo_cdc_tokens {
+                    return Ok(token);
+                }
+
+                return self.parse_next();
+            }
+        }
+
         match self.stream.curr_char_raw() {
             b'@' => {
                 self.after_selector = true;
                 self.has_at_rule = true;
                 self.stream.advance_raw(1);
                 let s = self.consume_ident()?;
+
+                if s.eq_ignore_ascii_case("charset") {
+                    return self.consume_charset();
+                }
+
+                if s.eq_ignore_ascii_case("namespace") {
+                    return self.consume_namespace();
+                }
+
+                self.keyframes_pending = is_keyframes_rule(s);
                 
                 // Don't consume parentheses here - let the next parse_next() call handle it
                 // Just return the @rule name
                 return Ok(Token::AtRule(s));
             }
+            b'#' | b'.' if self.has_at_rule => {
+                self.after_selector = true;
+                return Ok(Token::AtStr(self.consume_at_rule_ident()?));
+            }
             b'#' => {
                 self.after_selector = true;
                 self.has_at_rule = false;
@@ -203,7 +501,17 @@ impl<'a> Tokenizer<'a> {
                 let s = self.consume_ident()?;
                 return Ok(Token::ClassSelector(s));
             }
-            b'*' => {
+            b'*' | b'|' => {
+                if !self.has_at_rule {
+                    if let Some(token) = self.consume_qualified_selector()? {
+                        return Ok(token);
+                    }
+                }
+
+                if self.stream.curr_char_raw() == b'|' {
+                    return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+                }
+
                 self.after_selector = true;
                 self.has_at_rule = false;
                 self.stream.advance_raw(1);
@@ -211,8 +519,9 @@ impl<'a> Tokenizer<'a> {
                 return Ok(Token::UniversalSelector);
             }
             b':' => {
+                // Keeps `has_at_rule`, since page selectors like `@page :first`
+                // are a part of the at-rule prelude.
                 self.after_selector = true;
-                self.has_at_rule = false;
                 self.stream.advance_raw(1);
 
                 // Whether this selector is a ::selector.
@@ -253,8 +562,9 @@ impl<'a> Tokenizer<'a> {
                 return Ok(Token::AttributeSelector(s));
             }
             b',' => {
+                // Keeps `has_at_rule`, since at-rule preludes can be comma-separated,
+                // like `@layer a, b;`.
                 self.after_selector = false;
-                self.has_at_rule = false;
                 self.stream.advance_raw(1);
                 self.stream.skip_spaces();
                 return Ok(Token::Comma);
@@ -262,6 +572,7 @@ impl<'a> Tokenizer<'a> {
             b'{' => {
                 // Track if this block was started by an @-rule
                 self.nesting_stack.push(self.has_at_rule);
+                self.start_keyframes_block();
                 self.after_selector = false;
                 self.has_at_rule = false;
                 self.state = State::Declaration;
@@ -308,6 +619,12 @@ impl<'a> Tokenizer<'a> {
                     return Err(Error::UnknownToken(self.stream.gen_error_pos()));
                 }
             }
+            b';' if self.has_at_rule => {
+                return Ok(self.consume_at_rule_end());
+            }
+            b'"' | b'\'' if self.has_at_rule => {
+                return Ok(self.consume_at_rule_string());
+            }
             b'(' if self.has_at_rule => {
                 // Parenthesized content in @-rule like @media (min-width: 800px)
                 let s = self.consume_parenthesized_content()?;
@@ -323,7 +640,12 @@ impl<'a> Tokenizer<'a> {
                     }
 
                     match self.stream.curr_char()? {
-                        b'{' | b'/' | b'>' | b'+' | b'~' | b'*' | b'(' => { return self.parse_next(); },
+                        b'{' | b',' | b'/' | b'>' | b'+' | b'~' | b'*' | b'(' | b';' | b'"' | b'\'' => {
+                            return self.parse_next();
+                        },
+                        b':' if self.has_at_rule => {
+                            return self.parse_next();
+                        },
                         _ => {
                             self.after_selector = false;
                             if !self.has_at_rule {
@@ -333,13 +655,16 @@ impl<'a> Tokenizer<'a> {
                     }
                 }
 
-                let s = self.consume_ident()?;
+                if !self.has_at_rule {
+                    if let Some(token) = self.consume_qualified_selector()? {
+                        return Ok(token);
+                    }
+                }
+
                 let token_type = if self.has_at_rule {
-                    self.has_at_rule = true;
-                    Token::AtStr(s)
+                    Token::AtStr(self.consume_at_rule_ident()?)
                 } else {
-                    self.has_at_rule = false;
-                    Token::TypeSelector(s)
+                    Token::TypeSelector(self.consume_ident()?)
                 };
 
                 self.after_selector = true;
@@ -351,8 +676,31 @@ impl<'a> Tokenizer<'a> {
     fn consume_declaration(&mut self) -> Result<Token<'a>, Error> {
         self.stream.skip_spaces();
 
+        if self.keyframes_depth == Some(self.nesting_stack.len()) {
+            match self.stream.curr_char_raw() {
+                b'}' | b'{' | b'/' => {}
+                b',' => {
+                    self.stream.advance_raw(1);
+                    self.stream.skip_spaces();
+                    return Ok(Token::Comma);
+                }
+                c => {
+                    let mut s = self.stream;
+                    let is_selector = !c.is_ascii_alphabetic() || s.try_consume_keyword("from")
+                        || s.try_consume_keyword("to");
+                    if is_selector {
+                        return self.consume_keyframe_selector();
+                    }
+                }
+            }
+        }
+
         match self.stream.curr_char_raw() {
             b'}' => {
+                if self.keyframes_depth == Some(self.nesting_stack.len()) {
+                    self.keyframes_depth = None;
+                }
+
                 // Pop nesting level
                 self.nesting_stack.pop();
                 
@@ -375,6 +723,7 @@ impl<'a> Tokenizer<'a> {
             b'{' => {
                 // Track if this block was started by an @-rule
                 self.nesting_stack.push(self.has_at_rule);
+                self.start_keyframes_block();
                 self.has_at_rule = false;
                 
                 if self.state == State::Rule {
@@ -392,13 +741,14 @@ impl<'a> Tokenizer<'a> {
                 self.has_at_rule = true;
                 self.stream.advance_raw(1);
                 let s = self.consume_ident()?;
+                self.keyframes_pending = is_keyframes_rule(s);
                 self.stream.skip_spaces();
                 return Ok(Token::AtRule(s));
             },
             b':' => {
                 // Nested pseudo-class selector (e.g., :hover { } inside .button { })
+                // or a page selector (e.g., @page :first { } inside @media print { })
                 self.after_selector = true;
-                self.has_at_rule = false;
                 self.stream.advance_raw(1);
 
                 // Check for ::pseudo-element
@@ -427,6 +777,13 @@ impl<'a> Tokenizer<'a> {
                     });
                 }
             },
+            b'#' | b'.' if self.has_at_rule => {
+                // Dotted names in a nested @-rule prelude, like `@layer a.b;`
+                let s = self.consume_at_rule_ident()?;
+                self.stream.skip_spaces();
+                self.after_selector = true;
+                return Ok(Token::AtStr(s));
+            },
             b'.' => {
                 // Nested class selector (e.g., .inner { } inside .outer { })
                 self.after_selector = true;
@@ -443,8 +800,18 @@ impl<'a> Tokenizer<'a> {
                 let s = self.consume_ident()?;
                 return Ok(Token::IdSelector(s));
             },
-            b'*' => {
+            b'*' | b'|' => {
                 // Nested universal selector
+                if !self.has_at_rule {
+                    if let Some(token) = self.consume_qualified_selector()? {
+                        return Ok(token);
+                    }
+                }
+
+                if self.stream.curr_char_raw() == b'|' {
+                    return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+                }
+
                 self.after_selector = true;
                 self.has_at_rule = false;
                 self.stream.advance_raw(1);
@@ -487,13 +854,18 @@ impl<'a> Tokenizer<'a> {
                 return Ok(Token::Combinator(Combinator::Tilde));
             },
             b',' => {
-                // Comma in nested context (multiple selectors)
+                // Comma in nested context (multiple selectors or a nested at-rule prelude)
                 self.after_selector = false;
-                self.has_at_rule = false;
                 self.stream.advance_raw(1);
                 self.stream.skip_spaces();
                 return Ok(Token::Comma);
             },
+            b';' if self.has_at_rule => {
+                return Ok(self.consume_at_rule_end());
+            },
+            b'"' | b'\'' if self.has_at_rule => {
+                return Ok(self.consume_at_rule_string());
+            },
             b'(' if self.has_at_rule => {
                 // Parenthesized content in nested @-rule
                 let s = self.consume_parenthesized_content()?;
@@ -510,20 +882,29 @@ impl<'a> Tokenizer<'a> {
             _ => {
                 // Check for @-rule content (identifier after @rule like "@media screen")
                 if self.has_at_rule {
-                    let s = self.consume_ident()?;
+                    let s = self.consume_at_rule_ident()?;
                     self.stream.skip_spaces();
                     self.after_selector = true;
                     return Ok(Token::AtStr(s));
                 }
-                
+
+                // Nested namespace-qualified selector (e.g., "svg|rect { }")
+                if let Some(token) = self.consume_qualified_selector()? {
+                    return Ok(token);
+                }
+
                 let name = self.consume_ident()?;
 
                 self.stream.skip_spaces();
 
-                if self.stream.is_char_eq(b'/')? {
-                    if !self.consume_comment()? {
-                        return Err(Error::UnknownToken(self.stream.gen_error_pos()));
-                    }
+                if self.stream.is_char_eq(b'/')? && !self.consume_comment()? {
+                    return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+                }
+
+                if name.starts_with("--") {
+                    self.stream.consume_char(b':')?;
+                    let value = self.consume_custom_property_value()?;
+                    return Ok(Token::CustomProperty(name, value));
                 }
 
                 if self.stream.is_char_eq(b'{')? {
@@ -546,52 +927,285 @@ impl<'a> Tokenizer<'a> {
                 self.stream.advance_raw(1); // :
                 self.stream.skip_spaces();
 
-                if self.stream.is_char_eq(b'/')? {
-                    if !self.consume_comment()? {
-                        return Err(Error::UnknownToken(self.stream.gen_error_pos()));
-                    }
+                if self.stream.is_char_eq(b'/')? && !self.consume_comment()? {
+                    return Err(Error::UnknownToken(self.stream.gen_error_pos()));
                 }
 
-                let len = self.stream.length_to_either(&[b';', b'}'])?;
+                let len = match self.stream.length_to_either(b";}") {
+                    Ok(len) => len,
+                    // The last declaration of a declaration list can end without `;`.
+                    Err(_) if self.declaration_list => self.stream.end() - self.stream.pos(),
+                    Err(e) => return Err(e),
+                };
 
                 if len == 0 {
                     return Err(Error::UnknownToken(self.stream.gen_error_pos()));
                 }
 
-                let mut value = self.stream.read_raw_str(len);
-                // trim spaces at the end of the value
-                if let Some(p) = value.as_bytes().iter().rposition(|c| !stream::is_space(*c)) {
-                    value = &value[0..(p + 1)];
-                }
+                let value = trim_end(self.stream.read_raw_str(len));
+                self.skip_semicolons()?;
+
+                Ok(Token::Declaration(name, value))
+            }
+        }
+    }
+
+    /// Consumes a type or universal selector with a namespace prefix, if any.
+    fn consume_qualified_selector(&mut self) -> Result<Option<Token<'a>>, Error> {
+        let namespace = match selector::consume_namespace_prefix(&mut self.stream)? {
+            Some(namespace) => namespace,
+            None => return Ok(None),
+        };
+
+        self.after_selector = true;
+        self.has_at_rule = false;
+
+        if self.stream.is_char_eq(b'*')? {
+            self.stream.advance_raw(1);
+            return Ok(Some(Token::QualifiedUniversalSelector(namespace)));
+        }
+
+        let name = self.consume_ident()?;
+        Ok(Some(Token::QualifiedTypeSelector { namespace, name }))
+    }
 
+    /// Consumes `@charset` after the name.
+    fn consume_charset(&mut self) -> Result<Token<'a>, Error> {
+        self.stream.skip_spaces();
+
+        let pos = self.stream.gen_error_pos();
+        if !matches!(self.stream.curr_char()?, b'"' | b'\'') {
+            return Err(Error::UnknownToken(pos));
+        }
+
+        let encoding = self.stream.consume_url()?;
+        self.consume_statement_end()?;
+        Ok(Token::Charset(encoding))
+    }
+
+    /// Consumes `@namespace` after the name.
+    fn consume_namespace(&mut self) -> Result<Token<'a>, Error> {
+        self.stream.skip_spaces();
+
+        let mut prefix = None;
+        let mut s = self.stream;
+        if let Ok(ident) = s.consume_ident() {
+            let is_url = ident.eq_ignore_ascii_case("url") && s.curr_char() == Ok(b'(');
+            if !is_url {
+                prefix = Some(ident);
+                self.stream = s;
                 self.stream.skip_spaces();
-                while self.stream.is_char_eq(b';')? {
-                    self.stream.advance_raw(1);
-                    self.stream.skip_spaces();
+            }
+        }
+
+        let uri = self.stream.consume_url()?;
+        self.consume_statement_end()?;
+        self.namespaces.insert(prefix, uri);
+        Ok(Token::Namespace { prefix, uri })
+    }
+
+    /// Consumes `<!--` or `-->`.
+    fn consume_cdo_cdc(&mut self) -> Option<Token<'a>> {
+        let (token, len) = if self.stream.starts_with(b"<!--") {
+            (Token::Cdo, 4)
+        } else if self.stream.starts_with(b"-->") {
+            (Token::Cdc, 3)
+        } else {
+            return None;
+        };
+
+        self.stream.advance_raw(len);
+        self.stream.skip_spaces();
+        Some(token)
+    }
+
+    fn consume_statement_end(&mut self) -> Result<(), Error> {
+        self.stream.skip_spaces();
+        self.stream.consume_char(b';')?;
+        self.stream.skip_spaces();
+        self.after_selector = false;
+        self.has_at_rule = false;
+        Ok(())
+    }
+
+    fn consume_at_rule_end(&mut self) -> Token<'a> {
+        self.after_selector = false;
+        self.has_at_rule = false;
+        self.keyframes_pending = false;
+        self.stream.advance_raw(1);
+        self.stream.skip_spaces();
+        Token::AtRuleEnd
+    }
+
+    fn consume_at_rule_string(&mut self) -> Token<'a> {
+        let start = self.stream.pos();
+        self.stream.skip_quoted_string();
+        let s = self.stream.slice_region_raw_str(start, self.stream.pos());
+        self.after_selector = true;
+        Token::AtStr(s)
+    }
+
+    fn start_keyframes_block(&mut self) {
+        if self.keyframes_pending {
+            self.keyframes_pending = false;
+            self.keyframes_depth = Some(self.nesting_stack.len());
+        }
+    }
+
+    /// Consumes `from`, `to` or a percentage.
+    fn consume_keyframe_selector(&mut self) -> Result<Token<'a>, Error> {
+        let start = self.stream.pos();
+
+        let value = if self.stream.try_consume_keyword("from") {
+            0.0
+        } else if self.stream.try_consume_keyword("to") {
+            100.0
+        } else {
+            let n = self.stream.consume_number()?;
+            self.stream.consume_char(b'%')?;
+            if !(0.0..=100.0).contains(&n) {
+                return Err(Error::InvalidValue(self.stream.gen_error_pos_at(start)));
+            }
+            n
+        };
+
+        self.stream.skip_spaces();
+        Ok(Token::KeyframeSelector(value))
+    }
+
+    /// Consumes a custom property value up to the `;` or `}` that ends the declaration.
+    ///
+    /// Unlike regular values, `;` and `{}` are allowed inside brackets and strings,
+    /// and the value can be empty.
+    fn consume_custom_property_value(&mut self) -> Result<&'a str, Error> {
+        self.stream.skip_spaces();
+
+        let start = self.stream.pos();
+        let mut depth = 0;
+
+        loop {
+            if self.declaration_list && depth == 0 && self.stream.at_end() {
+                break;
+            }
+
+            match self.stream.curr_char()? {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' => {
+                    if depth == 0 {
+                        return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+                    }
+                    depth -= 1;
                 }
+                b'}' => {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                }
+                b';' if depth == 0 => break,
+                b'"' | b'\'' => {
+                    self.stream.skip_quoted_string();
+                    continue;
+                }
+                _ => {}
+            }
 
-                Ok(Token::Declaration(name, value))
+            self.stream.advance_raw(1);
+        }
+
+        let value = trim_end(self.stream.slice_region_raw_str(start, self.stream.pos()));
+        self.skip_semicolons()?;
+
+        Ok(value)
+    }
+
+    fn skip_semicolons(&mut self) -> Result<(), Error> {
+        loop {
+            self.stream.skip_spaces();
+
+            // A declaration list ends without `}`.
+            if self.declaration_list && self.stream.at_end() {
+                return Ok(());
+            }
+
+            if !self.stream.is_char_eq(b';')? {
+                return Ok(());
             }
+
+            self.stream.advance_raw(1);
+        }
+    }
+
+    /// Consumes a token of a standalone selector list.
+    fn consume_selector_list_item(&mut self) -> Result<Token<'a>, Error> {
+        // Trailing spaces are not a combinator.
+        let mut s = self.stream;
+        s.skip_spaces();
+        if s.at_end() {
+            self.stream = s;
+            if !self.after_selector {
+                return Err(Error::UnexpectedEndOfStream(self.stream.gen_error_pos()));
+            }
+
+            return Ok(Token::EndOfStream);
+        }
+
+        match self.stream.curr_char_raw() {
+            b'{' | b'}' | b';' | b'@' => return Err(Error::UnknownToken(self.stream.gen_error_pos())),
+            b',' if !self.after_selector => {
+                return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+            }
+            _ => {}
+        }
+
+        self.consume_rule()
+    }
+
+    /// Consumes a declaration of a declaration list.
+    fn consume_declaration_list_item(&mut self) -> Result<Token<'a>, Error> {
+        self.skip_semicolons()?;
+        if self.stream.at_end() {
+            return Ok(Token::EndOfStream);
+        }
+
+        let pos = self.stream.gen_error_pos();
+        match self.stream.curr_char_raw() {
+            b'/' => return self.consume_declaration(),
+            c if c.is_ascii_alphabetic() || c == b'-' || c == b'_' => {}
+            _ => return Err(Error::UnknownToken(pos)),
+        }
+
+        match self.consume_declaration()? {
+            token @ Token::Declaration(..) | token @ Token::CustomProperty(..) => Ok(token),
+            _ => Err(Error::UnknownToken(pos)),
         }
     }
 
     fn consume_ident(&mut self) -> Result<&'a str, Error> {
+        self.stream.consume_ident()
+    }
+
+    /// Consumes an ident in an at-rule prelude, including `.` and `#` parts,
+    /// like `base.reset` in `@layer base.reset;`.
+    fn consume_at_rule_ident(&mut self) -> Result<&'a str, Error> {
         let start = self.stream.pos();
+        if let b'.' | b'#' = self.stream.curr_char_raw() {
+            self.stream.advance_raw(1);
+        }
 
-        while !self.stream.at_end() {
-            if self.stream.is_ident_raw() {
-                self.stream.advance(1)?;
-            } else {
+        self.consume_ident()?;
+
+        while let Some(b'.') | Some(b'#') = self.stream.char_at(0) {
+            let mut s = self.stream;
+            s.advance_raw(1);
+            if s.consume_ident().is_err() {
                 break;
             }
-        }
 
-        if start == self.stream.pos() {
-            return Err(Error::UnknownToken(self.stream.gen_error_pos()));
+            self.stream = s;
         }
 
-        let s = self.stream.slice_region_raw_str(start, self.stream.pos());
-        Ok(s)
+        Ok(self.stream.slice_region_raw_str(start, self.stream.pos()))
     }
 
     fn consume_comment(&mut self) -> Result<bool, Error>  {
@@ -618,54 +1232,28 @@ impl<'a> Tokenizer<'a> {
     /// Consumes parenthesized content like "(min-width: 800px)" or "(linux)"
     /// Handles nested parentheses correctly.
     fn consume_parenthesized_content(&mut self) -> Result<&'a str, Error> {
-        if !self.stream.is_char_eq(b'(')? {
-            return Err(Error::UnknownToken(self.stream.gen_error_pos()));
-        }
-        
-        let start = self.stream.pos();
-        self.stream.advance_raw(1); // consume opening (
-        
-        let mut depth = 1;
-        
-        while !self.stream.at_end() && depth > 0 {
-            match self.stream.curr_char_raw() {
-                b'(' => {
-                    depth += 1;
-                    self.stream.advance_raw(1);
-                }
-                b')' => {
-                    depth -= 1;
-                    self.stream.advance_raw(1);
-                }
-                b'"' | b'\'' => {
-                    // Skip quoted strings
-                    let quote = self.stream.curr_char_raw();
-                    self.stream.advance_raw(1);
-                    while !self.stream.at_end() {
-                        let c = self.stream.curr_char_raw();
-                        self.stream.advance_raw(1);
-                        if c == quote {
-                            break;
-                        }
-                        if c == b'\\' && !self.stream.at_end() {
-                            self.stream.advance_raw(1); // skip escaped char
-                        }
-                    }
-                }
-                _ => {
-                    self.stream.advance_raw(1);
-                }
-            }
-        }
-        
-        if depth != 0 {
-            return Err(Error::UnknownToken(self.stream.gen_error_pos()));
-        }
-        
-        // Return content including the parentheses
-        let end = self.stream.pos();
-        let s = self.stream.slice_region_raw_str(start, end);
+        let s = self.stream.consume_parenthesized()?;
         self.stream.skip_spaces();
         Ok(s)
     }
 }
+
+/// Checks that the at-rule is `@keyframes`, with an optional vendor prefix.
+fn is_keyframes_rule(name: &str) -> bool {
+    let name = match name.strip_prefix('-') {
+        Some(rest) => rest.find('-').map_or(name, |idx| &rest[idx + 1..]),
+        None => name,
+    };
+
+    name.eq_ignore_ascii_case("keyframes")
+}
+
+/// Trims spaces at the end of the value.
+///
+/// The result is always a subslice of `value`, so `error_pos_of` works on it.
+fn trim_end(value: &str) -> &str {
+    match value.as_bytes().iter().rposition(|c| !stream::is_space(*c)) {
+        Some(p) => &value[0..(p + 1)],
+        None => &value[0..0],
+    }
+}
@@ -0,0 +1,182 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::calc::{CalcNode, CalcType};
+use crate::color::Color;
+use crate::stream;
+use crate::stream::Stream;
+pub use crate::stream::unquote;
+use crate::error::ErrorPos;
+
+/// Splits a value by top-level commas.
+///
+/// Commas inside brackets and strings are ignored. Parts are trimmed.
+pub fn split_list(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut s = Stream::new(text.as_bytes());
+    let mut start = 0;
+    let mut depth = 0;
+
+    while !s.at_end() {
+        match s.curr_char_raw() {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b'"' | b'\'' => {
+                s.skip_quoted_string();
+                continue;
+            }
+            b',' if depth == 0 => {
+                parts.push(text[start..s.pos()].trim());
+                start = s.pos() + 1;
+            }
+            _ => {}
+        }
+
+        s.advance_raw(1);
+    }
+
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// Splits a value by top-level whitespace.
+///
+/// Whitespace inside brackets and strings is ignored.
+/// A top-level `/` is always returned as a separate component.
+pub fn split_components(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut s = Stream::new(text.as_bytes());
+    let mut start = None;
+    let mut depth = 0;
+
+    while !s.at_end() {
+        let c = s.curr_char_raw();
+
+        if depth == 0 && (stream::is_space(c) || c == b'/') {
+            if let Some(start) = start.take() {
+                parts.push(&text[start..s.pos()]);
+            }
+
+            if c == b'/' {
+                parts.push(&text[s.pos()..s.pos() + 1]);
+            }
+
+            s.advance_raw(1);
+            continue;
+        }
+
+        if start.is_none() {
+            start = Some(s.pos());
+        }
+
+        match c {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b'"' | b'\'' => {
+                s.skip_quoted_string();
+                continue;
+            }
+            _ => {}
+        }
+
+        s.advance_raw(1);
+    }
+
+    if let Some(start) = start {
+        parts.push(&text[start..]);
+    }
+
+    parts
+}
+
+/// Returns a position of `part` inside `text`.
+///
+/// `part` must be a subslice of `text`.
+pub fn error_pos(text: &str, part: &str) -> ErrorPos {
+    let s = Stream::new(text.as_bytes());
+    s.gen_error_pos_at(s.offset_of(part))
+}
+
+/// Returns a `var()` reference inside `text`, if any.
+pub fn find_var(text: &str) -> Option<&str> {
+    let bytes = text.as_bytes();
+    for i in 0..bytes.len() {
+        if bytes[i..].len() >= 4
+            && bytes[i..i + 3].eq_ignore_ascii_case(b"var")
+            && bytes[i + 3] == b'('
+            && (i == 0 || !is_ident_char(bytes[i - 1]))
+        {
+            return Some(&text[i..]);
+        }
+    }
+
+    None
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-' || c == b'_'
+}
+
+pub fn is_keyword(text: &str, list: &[&str]) -> bool {
+    list.iter().any(|k| text.eq_ignore_ascii_case(k))
+}
+
+pub fn is_css_wide_keyword(text: &str) -> bool {
+    is_keyword(text, &["initial", "inherit", "unset", "revert", "revert-layer"])
+}
+
+pub fn calc_type(text: &str) -> Option<CalcType> {
+    CalcNode::parse(text).ok()?.value_type()
+}
+
+pub fn is_length_percentage(text: &str) -> bool {
+    match CalcNode::parse(text) {
+        Ok(CalcNode::Number(n)) => n == 0.0,
+        Ok(node) => matches!(node.value_type(),
+            Some(CalcType::Length) | Some(CalcType::Percentage)
+            | Some(CalcType::LengthPercentage)),
+        Err(_) => false,
+    }
+}
+
+pub fn is_length(text: &str) -> bool {
+    match CalcNode::parse(text) {
+        Ok(CalcNode::Number(n)) => n == 0.0,
+        Ok(node) => node.value_type() == Some(CalcType::Length),
+        Err(_) => false,
+    }
+}
+
+pub fn is_number(text: &str) -> bool {
+    calc_type(text) == Some(CalcType::Number)
+}
+
+pub fn is_time(text: &str) -> bool {
+    calc_type(text) == Some(CalcType::Time)
+}
+
+pub fn is_color(text: &str) -> bool {
+    Color::parse(text).is_ok()
+}
+
+pub fn is_image(text: &str) -> bool {
+    if text.eq_ignore_ascii_case("none") {
+        return true;
+    }
+
+    let name = match text.find('(') {
+        Some(idx) if text.ends_with(')') => text[..idx].to_ascii_lowercase(),
+        _ => return false,
+    };
+
+    name == "url" || name == "image" || name == "image-set" || name == "cross-fade"
+        || name.ends_with("-gradient")
+}
+
+pub fn is_custom_ident(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    !bytes.is_empty()
+        && !bytes[0].is_ascii_digit()
+        && bytes.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'-' || *c == b'_')
+}
@@ -0,0 +1,338 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use crate::stream::Stream;
+use crate::error::Error;
+use crate::property_rule::PropertyRule;
+
+/// Computed custom properties of an element.
+///
+/// Names are stored with the leading `--`.
+///
+/// https://www.w3.org/TR/css-variables-1/
+#[derive(Clone,Default,PartialEq,Debug)]
+pub struct CustomProperties {
+    map: HashMap<String, String>,
+}
+
+enum State {
+    InProgress,
+    Done(Option<String>),
+}
+
+impl CustomProperties {
+    /// Constructs a new, empty `CustomProperties`.
+    pub fn new() -> CustomProperties {
+        CustomProperties::default()
+    }
+
+    /// Returns a value of the custom property.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.map.get(name).map(|v| v.as_str())
+    }
+
+    /// Sets a value of the custom property.
+    ///
+    /// The value is stored as is, without `var()` substitution.
+    pub fn insert(&mut self, name: &str, value: &str) {
+        self.map.insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns `true` if there are no custom properties.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Computes custom properties of an element from the inherited ones
+    /// and the declared ones, like `Token::CustomProperty`.
+    ///
+    /// `var()` references in declared values are substituted. Properties that reference
+    /// each other in a cycle, or reference a missing property without a fallback,
+    /// are invalid at computed-value time and therefore removed.
+    ///
+    /// https://www.w3.org/TR/css-variables-1/#cycles
+    pub fn cascade<'a, I>(inherited: &CustomProperties, declared: I) -> CustomProperties
+        where I: IntoIterator<Item = (&'a str, &'a str)>
+    {
+        // Later declarations win.
+        let declared: HashMap<&str, &str> = declared.into_iter().collect();
+
+        let mut states: HashMap<&str, State> = HashMap::new();
+        for name in declared.keys() {
+            resolve(name, &declared, inherited, &mut states);
+        }
+
+        let mut computed = inherited.clone();
+        for (name, state) in states {
+            match state {
+                State::Done(Some(value)) => {
+                    computed.map.insert(name.to_string(), value);
+                }
+                _ => {
+                    computed.map.remove(name);
+                }
+            }
+        }
+
+        computed
+    }
+
+    /// Like `cascade`, but applies custom property registrations from `@property` rules.
+    ///
+    /// Registered properties that don't inherit start from their initial value
+    /// instead of the inherited one. A registered property whose computed value
+    /// doesn't match its syntax is invalid at computed-value time, so it falls back
+    /// to the inherited value if the property inherits, and to the initial value otherwise.
+    ///
+    /// https://www.w3.org/TR/css-properties-values-api-1/#calculation-of-computed-values
+    pub fn cascade_registered<'a, I>(
+        inherited: &CustomProperties,
+        declared: I,
+        registrations: &[PropertyRule],
+    ) -> CustomProperties
+        where I: IntoIterator<Item = (&'a str, &'a str)>
+    {
+        let mut parent = inherited.clone();
+        for reg in registrations {
+            let value = if reg.inherits { inherited.get(reg.name) } else { None };
+            match value.or(reg.initial_value) {
+                Some(value) => parent.insert(reg.name, value),
+                None => {
+                    parent.map.remove(reg.name);
+                }
+            }
+        }
+
+        let mut computed = CustomProperties::cascade(&parent, declared);
+        for reg in registrations {
+            if computed.get(reg.name).is_some_and(|v| reg.syntax.matches(v)) {
+                continue;
+            }
+
+            match parent.get(reg.name) {
+                Some(value) => computed.insert(reg.name, value),
+                None => {
+                    computed.map.remove(reg.name);
+                }
+            }
+        }
+
+        computed
+    }
+
+    /// Substitutes `var()` references in a declaration value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidValue` pointing at `var(` if a referenced property
+    /// doesn't exist and there is no fallback.
+    ///
+    /// Returns `Error::UnknownToken` or `Error::UnexpectedEndOfStream` on a malformed `var()`.
+    ///
+    /// Error positions are relative to `value`.
+    pub fn substitute(&self, value: &str) -> Result<String, Error> {
+        substitute_with(value, &mut |name| self.get(name).map(|v| v.to_string()))
+    }
+}
+
+fn resolve<'a>(
+    name: &'a str,
+    declared: &HashMap<&'a str, &'a str>,
+    inherited: &CustomProperties,
+    states: &mut HashMap<&'a str, State>,
+) -> Option<String> {
+    match states.get(name) {
+        Some(&State::InProgress) => {
+            // A cycle. Every property in it is invalid.
+            return None;
+        }
+        Some(State::Done(value)) => return value.clone(),
+        None => {}
+    }
+
+    let value = match declared.get(name) {
+        Some(value) => *value,
+        None => return inherited.get(name).map(|v| v.to_string()),
+    };
+
+    states.insert(name, State::InProgress);
+
+    let mut in_cycle = false;
+    let resolved = substitute_with(value, &mut |dep| {
+        let key = declared.get_key_value(dep).map(|(k, _)| *k);
+        match key {
+            Some(key) => {
+                if let Some(&State::InProgress) = states.get(key) {
+                    in_cycle = true;
+                    return None;
+                }
+
+                resolve(key, declared, inherited, states)
+            }
+            None => inherited.get(dep).map(|v| v.to_string()),
+        }
+    });
+
+    // A fallback can't break a cycle.
+    let resolved = if in_cycle { None } else { resolved.ok() };
+
+    // Dependencies could have marked us as a part of a cycle.
+    if let Some(State::Done(None)) = states.get(name) {
+        return None;
+    }
+
+    if in_cycle {
+        mark_cycle(name, value, declared, states);
+        return None;
+    }
+
+    states.insert(name, State::Done(resolved.clone()));
+    resolved
+}
+
+/// Marks all in-progress properties referenced from `value` as invalid,
+/// since they are part of the same cycle.
+fn mark_cycle<'a>(
+    name: &'a str,
+    value: &str,
+    declared: &HashMap<&'a str, &'a str>,
+    states: &mut HashMap<&'a str, State>,
+) {
+    states.insert(name, State::Done(None));
+
+    let mut refs = Vec::new();
+    let _ = substitute_with(value, &mut |dep| {
+        refs.push(dep.to_string());
+        None
+    });
+
+    for dep in refs {
+        if let Some((key, value)) = declared.get_key_value(dep.as_str()) {
+            if let Some(&State::InProgress) = states.get(key) {
+                mark_cycle(key, value, declared, states);
+            }
+        }
+    }
+}
+
+/// Substitutes `var()` references using `lookup`.
+///
+/// All references are visited, even after an error, so `lookup` sees every dependency.
+fn substitute_with(
+    value: &str,
+    lookup: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<String, Error> {
+    let mut s = Stream::new(value.as_bytes());
+    let mut out = String::with_capacity(value.len());
+    let mut error = None;
+    let mut prev_is_ident = false;
+    let mut chunk_start = 0;
+
+    while !s.at_end() {
+        let c = s.curr_char_raw();
+
+        if c == b'"' || c == b'\'' {
+            s.skip_quoted_string();
+            prev_is_ident = false;
+            continue;
+        }
+
+        if !prev_is_ident && is_var_function(&s) {
+            out.push_str(s.slice_region_raw_str(chunk_start, s.pos()));
+            let var_pos = s.gen_error_pos();
+            s.advance_raw(4); // var(
+
+            match consume_var(&mut s, lookup)? {
+                Some(v) => out.push_str(&v),
+                None => {
+                    if error.is_none() {
+                        error = Some(Error::InvalidValue(var_pos));
+                    }
+                }
+            }
+
+            chunk_start = s.pos();
+            prev_is_ident = false;
+            continue;
+        }
+
+        prev_is_ident = s.is_ident_raw();
+        s.advance_raw(1);
+    }
+
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    out.push_str(s.slice_region_raw_str(chunk_start, s.pos()));
+    Ok(out)
+}
+
+fn is_var_function(s: &Stream) -> bool {
+    let name = [s.char_at(0), s.char_at(1), s.char_at(2), s.char_at(3)];
+    match name {
+        [Some(v), Some(a), Some(r), Some(b'(')] => {
+            v.eq_ignore_ascii_case(&b'v')
+                && a.eq_ignore_ascii_case(&b'a')
+                && r.eq_ignore_ascii_case(&b'r')
+        }
+        _ => false,
+    }
+}
+
+/// Consumes `var()` arguments after `var(` up to and including `)`.
+///
+/// Returns `None` if the property doesn't exist and there is no fallback.
+fn consume_var(
+    s: &mut Stream,
+    lookup: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<Option<String>, Error> {
+    s.skip_spaces();
+
+    if !(s.char_at(0) == Some(b'-') && s.char_at(1) == Some(b'-')) {
+        return Err(Error::UnknownToken(s.gen_error_pos()));
+    }
+
+    let name = s.consume_ident()?;
+    let value = lookup(name);
+    s.skip_spaces();
+
+    if s.is_char_eq(b')')? {
+        s.advance_raw(1);
+        return Ok(value);
+    }
+
+    s.consume_char(b',')?;
+
+    // The fallback is everything up to the matching `)`.
+    let start = s.pos();
+    let mut depth = 0;
+    loop {
+        match s.curr_char()? {
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            b'"' | b'\'' => {
+                s.skip_quoted_string();
+                continue;
+            }
+            _ => {}
+        }
+
+        s.advance_raw(1);
+    }
+
+    let fallback = s.slice_region_raw_str(start, s.pos()).trim();
+    s.advance_raw(1); // )
+
+    if value.is_some() {
+        // Fallback is still checked for references, so cycles through it are detected.
+        let _ = substitute_with(fallback, lookup);
+        return Ok(value);
+    }
+
+    Ok(substitute_with(fallback, lookup).ok())
+}
@@ -0,0 +1,91 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{CalcNode, CalcType, CalcContext, CalcValue, Unit, Error, ErrorPos};
+
+macro_rules! test_eval {
+    ($name:ident, $text:expr, $ctx:expr, $value:expr) => {
+        #[test]
+        fn $name() {
+            let node = CalcNode::parse($text).unwrap();
+            assert_eq!(node.eval(&$ctx), $value);
+        }
+    };
+}
+
+macro_rules! test_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(CalcNode::parse($text).unwrap_err(), $err);
+        }
+    };
+}
+
+fn ctx() -> CalcContext {
+    CalcContext {
+        percentage_basis: Some(200.0),
+        font_size: 10.0,
+        root_font_size: 20.0,
+        viewport_width: 1000.0,
+        viewport_height: 500.0,
+    }
+}
+
+#[test]
+fn parse_1() {
+    assert_eq!(CalcNode::parse("calc(100% - 2 * 10px)").unwrap(),
+        CalcNode::Sub(
+            Box::new(CalcNode::Percentage(100.0)),
+            Box::new(CalcNode::Mul(
+                Box::new(CalcNode::Number(2.0)),
+                Box::new(CalcNode::Dimension(10.0, Unit::Px)),
+            )),
+        ));
+}
+
+#[test]
+fn parse_2() {
+    let node = CalcNode::parse("clamp(1rem, 2.5vw, 2rem)").unwrap();
+    assert_eq!(node.value_type(), Some(CalcType::Length));
+}
+
+#[test]
+fn parse_3() {
+    let node = CalcNode::parse("min(100%, 500px)").unwrap();
+    assert_eq!(node.value_type(), Some(CalcType::LengthPercentage));
+}
+
+test_eval!(eval_1, "calc(100% - 2 * 10px)", ctx(), Some(CalcValue::Length(180.0)));
+test_eval!(eval_2, "calc((1em + 1rem) / 2)", ctx(), Some(CalcValue::Length(15.0)));
+test_eval!(eval_3, "max(10vw, 60vh)", ctx(), Some(CalcValue::Length(300.0)));
+test_eval!(eval_4, "clamp(10px, 50%, 50px)", ctx(), Some(CalcValue::Length(50.0)));
+test_eval!(eval_5, "calc(0.5turn - 90deg)", ctx(), Some(CalcValue::Angle(90.0)));
+test_eval!(eval_6, "calc(100ms * 3)", CalcContext::default(), Some(CalcValue::Time(0.3)));
+test_eval!(eval_7, "calc(50% / 2)", CalcContext::default(), Some(CalcValue::Percentage(25.0)));
+test_eval!(eval_8, "calc(50% + 1px)", CalcContext::default(), None);
+
+#[test]
+fn simplify_1() {
+    let node = CalcNode::parse("calc(1in + 2 * (4px - 2px))").unwrap();
+    assert_eq!(node.simplify(), CalcNode::Dimension(100.0, Unit::Px));
+}
+
+#[test]
+fn simplify_2() {
+    let node = CalcNode::parse("calc(100% - 1in / 2)").unwrap();
+    assert_eq!(node.simplify(), CalcNode::Sub(
+        Box::new(CalcNode::Percentage(100.0)),
+        Box::new(CalcNode::Dimension(48.0, Unit::Px)),
+    ));
+}
+
+test_err!(invalid_1, "calc(1px + 2s)", Error::InvalidValue(ErrorPos::new(1, 10)));
+test_err!(invalid_2, "calc(1px * 2px)", Error::InvalidValue(ErrorPos::new(1, 10)));
+test_err!(invalid_3, "calc(1px -2px)", Error::UnknownToken(ErrorPos::new(1, 10)));
+test_err!(invalid_4, "calc(1px + 2foo)", Error::UnknownToken(ErrorPos::new(1, 13)));
+test_err!(invalid_5, "clamp(1px, 2px)", Error::InvalidValue(ErrorPos::new(1, 1)));
+test_err!(invalid_6, "calc(var(--gap))", Error::UnsupportedToken(ErrorPos::new(1, 6)));
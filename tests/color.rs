@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{Color, Error, ErrorPos};
+
+macro_rules! test {
+    ($name:ident, $text:expr, $rgba:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(Color::parse($text).unwrap().to_rgba8(), Some($rgba));
+        }
+    };
+}
+
+test!(named_1, "red", [255, 0, 0, 255]);
+test!(named_2, "RebeccaPurple", [102, 51, 153, 255]);
+test!(transparent, "transparent", [0, 0, 0, 0]);
+test!(hex_1, "#f00", [255, 0, 0, 255]);
+test!(hex_2, "#f008", [255, 0, 0, 136]);
+test!(hex_3, "#1e90ff", [30, 144, 255, 255]);
+test!(hex_4, "#1E90FF80", [30, 144, 255, 128]);
+test!(rgb_1, "rgb(255, 128, 0)", [255, 128, 0, 255]);
+test!(rgb_2, "rgba(255, 128, 0, 0.5)", [255, 128, 0, 128]);
+test!(rgb_3, "rgb(100% 50% 0% / 50%)", [255, 128, 0, 128]);
+test!(rgb_4, " rgb( 10 20 30 ) ", [10, 20, 30, 255]);
+test!(rgb_5, "rgba(10%, 20%, 30%, 50%)", [26, 51, 77, 128]);
+test!(hsl_1, "hsl(120, 100%, 50%)", [0, 255, 0, 255]);
+test!(hsl_2, "hsla(0.5turn 100% 25% / 0.5)", [0, 128, 128, 128]);
+test!(hsl_3, "hsl(-120deg 100% 50%)", [0, 0, 255, 255]);
+test!(hwb_1, "hwb(0 0% 0%)", [255, 0, 0, 255]);
+test!(hwb_2, "hwb(0 60% 60%)", [128, 128, 128, 255]);
+
+#[test]
+fn current_color() {
+    let c = Color::parse("currentColor").unwrap();
+    assert_eq!(c, Color::CurrentColor);
+    assert_eq!(c.to_rgba8(), None);
+}
+
+#[test]
+fn to_f32() {
+    assert_eq!(Color::parse("#ff000080").unwrap().to_rgba_f32(),
+               Some([1.0, 0.0, 0.0, 128.0 / 255.0]));
+}
+
+#[test]
+fn invalid_1() {
+    assert_eq!(Color::parse("#12345").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 2)));
+}
+
+#[test]
+fn invalid_2() {
+    assert_eq!(Color::parse("reddish").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 1)));
+}
+
+#[test]
+fn invalid_3() {
+    assert_eq!(Color::parse("rgb(1, 2 3)").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 10)));
+}
+
+#[test]
+fn invalid_4() {
+    assert_eq!(Color::parse("red blue").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn invalid_5() {
+    assert_eq!(Color::parse("rgb(0 0 0 / 10deg)").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn invalid_6() {
+    // The legacy syntax doesn't allow mixing numbers and percentages.
+    assert_eq!(Color::parse("rgb(10%, 20, 30)").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 5)));
+    assert_eq!(Color::parse("rgb(10% 20 30)").unwrap().to_rgba8(), Some([26, 20, 30, 255]));
+}
@@ -0,0 +1,266 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    ContainerQuery, ContainerCondition, StyleQuery, ContainerType, Container, CustomProperties,
+    MediaFeature, MediaBound, MediaValue, Shorthand, Tokenizer, Token, Error, ErrorPos,
+    parse_container_names, validate_declaration,
+};
+
+fn parse(text: &str) -> ContainerQuery<'_> {
+    ContainerQuery::parse(text).unwrap()
+}
+
+fn size(width: f32, height: f32) -> Container<'static> {
+    Container {
+        container_type: ContainerType::Size,
+        width,
+        height,
+        ..Container::default()
+    }
+}
+
+#[test]
+fn parse_1() {
+    let q = parse("sidebar (min-width: 400px)");
+    assert_eq!(q.name, Some("sidebar"));
+    assert_eq!(q.condition, ContainerCondition::Feature(MediaFeature::Range {
+        name: "width",
+        start: Some(MediaBound { value: MediaValue::Dimension(400.0, "px"), inclusive: true }),
+        end: None,
+    }));
+}
+
+#[test]
+fn parse_2() {
+    let q = parse("(inline-size > 30em)");
+    assert_eq!(q.name, None);
+    assert_eq!(q.required_type(), ContainerType::InlineSize);
+}
+
+#[test]
+fn parse_3() {
+    let q = parse("style(--theme: dark)");
+    assert_eq!(q.name, None);
+    assert_eq!(q.condition, ContainerCondition::Style(StyleQuery::Declaration("--theme", "dark")));
+    assert_eq!(q.required_type(), ContainerType::Normal);
+}
+
+#[test]
+fn parse_4() {
+    let q = parse("card (width > 400px) and style(--compact)");
+    assert_eq!(q.name, Some("card"));
+    assert_eq!(q.condition, ContainerCondition::And(vec![
+        ContainerCondition::Feature(MediaFeature::Range {
+            name: "width",
+            start: Some(MediaBound { value: MediaValue::Dimension(400.0, "px"), inclusive: false }),
+            end: None,
+        }),
+        ContainerCondition::Style(StyleQuery::Property("--compact")),
+    ]));
+}
+
+#[test]
+fn parse_5() {
+    let q = parse("not (orientation: landscape)");
+    assert_eq!(q.name, None);
+    assert_eq!(q.required_type(), ContainerType::Size);
+    assert!(matches!(q.condition, ContainerCondition::Not(_)));
+}
+
+#[test]
+fn parse_6() {
+    let q = parse("(style(--a: 1) or style(--b: 2))");
+    assert_eq!(q.condition, ContainerCondition::Or(vec![
+        ContainerCondition::Style(StyleQuery::Declaration("--a", "1")),
+        ContainerCondition::Style(StyleQuery::Declaration("--b", "2")),
+    ]));
+}
+
+#[test]
+fn parse_7() {
+    let q = parse("style((--a: 1px 2px) and (not (--b)))");
+    assert_eq!(q.condition, ContainerCondition::Style(StyleQuery::And(vec![
+        StyleQuery::Declaration("--a", "1px 2px"),
+        StyleQuery::Not(Box::new(StyleQuery::Property("--b"))),
+    ])));
+}
+
+#[test]
+fn parse_8() {
+    let q = parse("scroll-state(stuck: top)");
+    assert_eq!(q.condition, ContainerCondition::Unknown("scroll-state(stuck: top)"));
+}
+
+#[test]
+fn parse_9() {
+    let q = parse("style(--a: 1 and)");
+    assert_eq!(q.condition, ContainerCondition::Style(StyleQuery::Declaration("--a", "1 and")));
+}
+
+#[test]
+fn parse_err_1() {
+    assert_eq!(ContainerQuery::parse("none (width > 1px)").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 1)));
+}
+
+#[test]
+fn parse_err_2() {
+    assert_eq!(ContainerQuery::parse("sidebar").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 8)));
+}
+
+#[test]
+fn parse_err_3() {
+    assert_eq!(ContainerQuery::parse("a (width > 1px) b").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 17)));
+}
+
+#[test]
+fn style_query_err_1() {
+    assert_eq!(StyleQuery::parse("--a:").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn style_query_err_2() {
+    assert_eq!(StyleQuery::parse("(--a) and (--b) or (--c)").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 17)));
+}
+
+#[test]
+fn eval_1() {
+    let q = parse("(min-width: 400px)");
+    assert!(q.matches(|_, _| Some(size(500.0, 100.0))));
+    assert!(!q.matches(|_, _| Some(size(300.0, 100.0))));
+    assert!(!q.matches(|_, _| None));
+}
+
+#[test]
+fn eval_2() {
+    // `em` is resolved against the container font size.
+    let q = parse("(width >= 20em)");
+    let mut c = size(400.0, 100.0);
+    c.font_size = 20.0;
+    assert!(q.matches(|_, _| Some(c)));
+    c.font_size = 21.0;
+    assert!(!q.matches(|_, _| Some(c)));
+}
+
+#[test]
+fn eval_3() {
+    // An inline-size container can't answer height queries.
+    let q = parse("(height > 10px)");
+    let mut c = size(400.0, 100.0);
+    c.container_type = ContainerType::InlineSize;
+    assert!(!q.matches(|_, _| Some(c)));
+    assert!(!parse("not (height > 10px)").matches(|_, _| Some(c)));
+    assert!(parse("(width > 10px)").matches(|_, _| Some(c)));
+}
+
+#[test]
+fn eval_4() {
+    let q = parse("sidebar (orientation: portrait)");
+    let lookup = |name: Option<&str>, t: ContainerType| {
+        assert_eq!(name, Some("sidebar"));
+        assert_eq!(t, ContainerType::Size);
+        Some(size(200.0, 600.0))
+    };
+    assert!(q.matches(lookup));
+}
+
+#[test]
+fn eval_5() {
+    let q = parse("(aspect-ratio > 16/9)");
+    assert!(q.matches(|_, _| Some(size(1920.0, 800.0))));
+    assert!(!q.matches(|_, _| Some(size(1920.0, 1080.0))));
+}
+
+#[test]
+fn eval_6() {
+    let mut props = CustomProperties::new();
+    props.insert("--theme", " dark");
+
+    let c = Container { custom_properties: Some(&props), ..Container::default() };
+    assert!(parse("style(--theme: dark)").matches(|_, _| Some(c)));
+    assert!(!parse("style(--theme: light)").matches(|_, _| Some(c)));
+    assert!(parse("style(--theme)").matches(|_, _| Some(c)));
+    assert!(parse("style(not (--compact))").matches(|_, _| Some(c)));
+    assert!(parse("style((--theme: dark) or (--x: 1))").matches(|_, _| Some(c)));
+    // Only custom properties can be queried.
+    assert!(!parse("style(color: red)").matches(|_, _| Some(c)));
+    assert!(!parse("not style(color: red)").matches(|_, _| Some(c)));
+    // No custom properties.
+    assert!(!parse("style(--theme)").matches(|_, _| Some(Container::default())));
+}
+
+#[test]
+fn eval_7() {
+    let mut props = CustomProperties::new();
+    props.insert("--compact", "1");
+
+    let mut c = size(500.0, 100.0);
+    c.custom_properties = Some(&props);
+    assert!(parse("(width > 400px) and style(--compact: 1)").matches(|_, _| Some(c)));
+    assert!(!parse("(width > 600px) and style(--compact: 1)").matches(|_, _| Some(c)));
+    assert!(parse("(width > 600px) or style(--compact: 1)").matches(|_, _| Some(c)));
+}
+
+#[test]
+fn container_type_1() {
+    assert_eq!(ContainerType::from_name("Inline-Size"), Some(ContainerType::InlineSize));
+    assert_eq!(ContainerType::from_name("size"), Some(ContainerType::Size));
+    assert_eq!(ContainerType::from_name("block-size"), None);
+}
+
+#[test]
+fn container_names_1() {
+    assert_eq!(parse_container_names("sidebar  card"), Some(vec!["sidebar", "card"]));
+    assert_eq!(parse_container_names("none"), Some(vec![]));
+    assert_eq!(parse_container_names("a none"), None);
+    assert_eq!(parse_container_names("inherit"), None);
+    assert_eq!(parse_container_names(""), None);
+}
+
+#[test]
+fn shorthand_1() {
+    assert_eq!(Shorthand::Container.expand("sidebar / inline-size").unwrap(), vec![
+        ("container-name", "sidebar".to_string()),
+        ("container-type", "inline-size".to_string()),
+    ]);
+}
+
+#[test]
+fn shorthand_2() {
+    assert_eq!(Shorthand::Container.expand("a b").unwrap(), vec![
+        ("container-name", "a b".to_string()),
+        ("container-type", "normal".to_string()),
+    ]);
+}
+
+#[test]
+fn shorthand_err_1() {
+    assert_eq!(Shorthand::Container.expand("a / block").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn validate_1() {
+    assert!(validate_declaration("container-type", "inline-size").is_ok());
+    assert!(validate_declaration("container-type", "block-size").is_err());
+    assert!(validate_declaration("container-name", "sidebar card").is_ok());
+    assert!(validate_declaration("container", "sidebar / size").is_ok());
+}
+
+#[test]
+fn tokenize_1() {
+    let mut t = Tokenizer::new("@container sidebar (min-width: 400px) { p { color: red } }");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("container"));
+    let prelude = t.parse_at_rule_prelude().unwrap();
+    assert_eq!(parse(prelude).name, Some("sidebar"));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+}
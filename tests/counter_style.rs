@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    CounterStyle, CounterSystem, CounterSymbol, CounterRange, Tokenizer, Token, Error, ErrorPos,
+};
+
+fn parse(text: &str) -> Result<CounterStyle<'_>, Error> {
+    let mut t = Tokenizer::new(text);
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("counter-style"));
+    CounterStyle::parse(&mut t)
+}
+
+#[test]
+fn parse_1() {
+    let style = parse("@counter-style thumbs { system: cyclic; symbols: \"👍\" star url(a.png); \
+                       suffix: \" \" }").unwrap();
+    assert_eq!(style, CounterStyle {
+        name: "thumbs",
+        system: CounterSystem::Cyclic,
+        symbols: vec![
+            CounterSymbol::String("👍"),
+            CounterSymbol::Ident("star"),
+            CounterSymbol::Image("url(a.png)"),
+        ],
+        additive_symbols: vec![],
+        range: CounterRange::Auto,
+        descriptors: vec![("suffix", "\" \"")],
+    });
+}
+
+#[test]
+fn parse_2() {
+    let style = parse("@counter-style roman { system: additive; range: 1 3999; \
+                       additive-symbols: 1000 M, 900 CM, 'D' 500, 1 I }").unwrap();
+    assert_eq!(style.system, CounterSystem::Additive);
+    assert_eq!(style.range, CounterRange::Ranges(vec![(Some(1), Some(3999))]));
+    assert_eq!(style.additive_symbols, vec![
+        (1000, CounterSymbol::Ident("M")),
+        (900, CounterSymbol::Ident("CM")),
+        (500, CounterSymbol::String("D")),
+        (1, CounterSymbol::Ident("I")),
+    ]);
+}
+
+#[test]
+fn parse_3() {
+    // Invalid values are ignored.
+    let style = parse("@counter-style a { system: fixed 5; system: bogus; symbols: a b; \
+                       range: 5 1; symbols: 1 }").unwrap();
+    assert_eq!(style.system, CounterSystem::Fixed(5));
+    assert_eq!(style.symbols, vec![CounterSymbol::Ident("a"), CounterSymbol::Ident("b")]);
+    assert_eq!(style.range, CounterRange::Auto);
+}
+
+#[test]
+fn parse_4() {
+    let style = parse("@counter-style b { system: extends decimal; suffix: ') ' }").unwrap();
+    assert_eq!(style.system, CounterSystem::Extends("decimal"));
+    assert!(style.symbols.is_empty());
+}
+
+#[test]
+fn parse_err_1() {
+    assert_eq!(parse("@counter-style none { symbols: a }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 16)));
+    assert_eq!(parse("@counter-style decimal { symbols: a }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 16)));
+}
+
+#[test]
+fn parse_err_2() {
+    assert_eq!(parse("@counter-style a { system: alphabetic; symbols: x }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 16)));
+    assert_eq!(parse("@counter-style a { system: additive; symbols: x }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 16)));
+    assert_eq!(parse("@counter-style a { system: extends b; symbols: x }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 16)));
+    assert_eq!(parse("@counter-style a { }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 16)));
+}
+
+#[test]
+fn system_1() {
+    assert_eq!(CounterSystem::parse("Numeric"), Some(CounterSystem::Numeric));
+    assert_eq!(CounterSystem::parse("fixed"), Some(CounterSystem::Fixed(1)));
+    assert_eq!(CounterSystem::parse("fixed -3"), Some(CounterSystem::Fixed(-3)));
+    assert_eq!(CounterSystem::parse("extends lower-roman"),
+               Some(CounterSystem::Extends("lower-roman")));
+    assert_eq!(CounterSystem::parse("extends none"), None);
+    assert_eq!(CounterSystem::parse("fixed 1.5"), None);
+    assert_eq!(CounterSystem::parse("cyclic 1"), None);
+}
+
+#[test]
+fn range_1() {
+    assert_eq!(CounterRange::parse(" auto "), Some(CounterRange::Auto));
+    assert_eq!(CounterRange::parse("infinite -1, 5 infinite"),
+               Some(CounterRange::Ranges(vec![(None, Some(-1)), (Some(5), None)])));
+    assert_eq!(CounterRange::parse("1"), None);
+    assert_eq!(CounterRange::parse("2 1"), None);
+    assert_eq!(CounterRange::parse("auto, 1 2"), None);
+}
+
+#[test]
+fn in_range_1() {
+    let style = parse("@counter-style a { system: alphabetic; symbols: a b }").unwrap();
+    assert!(!style.in_range(0));
+    assert!(style.in_range(1));
+
+    let style = parse("@counter-style a { symbols: a; range: infinite -1, 5 10 }").unwrap();
+    assert!(style.in_range(-100));
+    assert!(!style.in_range(0));
+    assert!(style.in_range(10));
+    assert!(!style.in_range(11));
+}
@@ -55,6 +55,13 @@ test_selectors!(selectors_2,
     Token::TypeSelector("p")
 );
 
+test_selectors!(selectors_space_comma_1,
+    "a , b { color: red }",
+    Token::TypeSelector("a"),
+    Token::Comma,
+    Token::TypeSelector("b")
+);
+
 test_selectors!(selectors_3,
     ":first-child { color: red }",
     Token::PseudoClass { selector: "first-child", value: None }
@@ -352,12 +359,11 @@ test!(blocks_6,
     Token::AtRule("keyframes"),
     Token::AtStr("hello"),
     Token::BlockStart,
-    // With nesting support, 'from' and 'to' are now parsed as TypeSelector (like nested selectors)
-    Token::TypeSelector("from"),
+    Token::KeyframeSelector(0.0),
     Token::BlockStart,
     Token::Declaration("width", "500px"),
     Token::BlockEnd,
-    Token::TypeSelector("to"),
+    Token::KeyframeSelector(100.0),
     Token::BlockStart,
     Token::Declaration("width", "600px"),
     Token::BlockEnd,
@@ -849,3 +855,472 @@ fn nested_comma_selectors() {
     assert_eq!(t.parse_next().unwrap(), Token::BlockEnd);
     assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
 }
+
+// =====================================================================
+// CUSTOM PROPERTY TESTS
+// =====================================================================
+
+test!(custom_property_1,
+    ":root { --gap: 4px; margin: var(--gap) }",
+    Token::PseudoClass { selector: "root", value: None },
+    Token::BlockStart,
+    Token::CustomProperty("--gap", "4px"),
+    Token::Declaration("margin", "var(--gap)"),
+    Token::BlockEnd
+);
+
+test!(custom_property_2,
+    "a { --json: { \"a\": [1; 2] }; --s: ';}'; color: red }",
+    Token::TypeSelector("a"),
+    Token::BlockStart,
+    Token::CustomProperty("--json", "{ \"a\": [1; 2] }"),
+    Token::CustomProperty("--s", "';}'"),
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test!(custom_property_3,
+    "a { --empty:; --last: 1 }",
+    Token::TypeSelector("a"),
+    Token::BlockStart,
+    Token::CustomProperty("--empty", ""),
+    Token::CustomProperty("--last", "1"),
+    Token::BlockEnd
+);
+
+#[test]
+fn custom_property_4() {
+    let mut t = Tokenizer::new("a { --x: (1; }");
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("a"));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnexpectedEndOfStream(ErrorPos::new(1, 15)));
+}
+
+test!(at_rule_statement_1,
+    "@import \"a.css\"; p { color: red }",
+    Token::AtRule("import"),
+    Token::AtStr("\"a.css\""),
+    Token::AtRuleEnd,
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test!(at_rule_statement_2,
+    "@import url(a.css) screen ;@import 'b.css';",
+    Token::AtRule("import"),
+    Token::AtStr("url"),
+    Token::AtStr("(a.css)"),
+    Token::AtStr("screen"),
+    Token::AtRuleEnd,
+    Token::AtRule("import"),
+    Token::AtStr("'b.css'"),
+    Token::AtRuleEnd
+);
+
+test!(at_rule_statement_3,
+    ".a { @layer b; color: red }",
+    Token::ClassSelector("a"),
+    Token::BlockStart,
+    Token::AtRule("layer"),
+    Token::AtStr("b"),
+    Token::AtRuleEnd,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test!(at_rule_statement_4,
+    "@layer reset, base, theme;",
+    Token::AtRule("layer"),
+    Token::AtStr("reset"),
+    Token::Comma,
+    Token::AtStr("base"),
+    Token::Comma,
+    Token::AtStr("theme"),
+    Token::AtRuleEnd
+);
+
+test!(at_rule_statement_5,
+    "@import \"x.css\" screen, print;",
+    Token::AtRule("import"),
+    Token::AtStr("\"x.css\""),
+    Token::AtStr("screen"),
+    Token::Comma,
+    Token::AtStr("print"),
+    Token::AtRuleEnd
+);
+
+test!(at_rule_comma_1,
+    "@media screen, print { p { color: red } }",
+    Token::AtRule("media"),
+    Token::AtStr("screen"),
+    Token::Comma,
+    Token::AtStr("print"),
+    Token::BlockStart,
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+test!(at_rule_comma_2,
+    "a { @layer b, c; }",
+    Token::TypeSelector("a"),
+    Token::BlockStart,
+    Token::AtRule("layer"),
+    Token::AtStr("b"),
+    Token::Comma,
+    Token::AtStr("c"),
+    Token::AtRuleEnd,
+    Token::BlockEnd
+);
+
+test!(at_rule_dotted_1,
+    "@layer a.b, c; x {}",
+    Token::AtRule("layer"),
+    Token::AtStr("a.b"),
+    Token::Comma,
+    Token::AtStr("c"),
+    Token::AtRuleEnd,
+    Token::TypeSelector("x"),
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(at_rule_dotted_2,
+    "@layer a.b { e { f: g } }",
+    Token::AtRule("layer"),
+    Token::AtStr("a.b"),
+    Token::BlockStart,
+    Token::TypeSelector("e"),
+    Token::BlockStart,
+    Token::Declaration("f", "g"),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+test!(at_rule_dotted_3,
+    "@layer base.reset; x { y: z }",
+    Token::AtRule("layer"),
+    Token::AtStr("base.reset"),
+    Token::AtRuleEnd,
+    Token::TypeSelector("x"),
+    Token::BlockStart,
+    Token::Declaration("y", "z"),
+    Token::BlockEnd
+);
+
+test!(at_rule_dotted_4,
+    "a { @layer b.c.d; }",
+    Token::TypeSelector("a"),
+    Token::BlockStart,
+    Token::AtRule("layer"),
+    Token::AtStr("b.c.d"),
+    Token::AtRuleEnd,
+    Token::BlockEnd
+);
+
+test!(charset_1,
+    "@charset \"UTF-8\";\np { color: red }",
+    Token::Charset("UTF-8"),
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test_err!(charset_2,
+    "@charset UTF-8;",
+    Error::UnknownToken(ErrorPos::new(1, 10))
+);
+
+test_err!(charset_3,
+    "@charset \"UTF-8\" p {}",
+    Error::UnknownToken(ErrorPos::new(1, 18))
+);
+
+test!(namespace_1,
+    "@namespace svg url(http://www.w3.org/2000/svg);\n@namespace \"http://www.w3.org/1999/xhtml\";",
+    Token::Namespace { prefix: Some("svg"), uri: "http://www.w3.org/2000/svg" },
+    Token::Namespace { prefix: None, uri: "http://www.w3.org/1999/xhtml" }
+);
+
+test!(namespace_2,
+    "@NAMESPACE url( 'x' ) ;",
+    Token::Namespace { prefix: None, uri: "x" }
+);
+
+#[test]
+fn namespace_3() {
+    let mut t = Tokenizer::new("@namespace svg url(a); @namespace url(b); @namespace svg url(c);");
+    assert_eq!(t.namespace_uri("svg"), None);
+    t.parse_next().unwrap();
+    assert_eq!(t.namespace_uri("svg"), Some("a"));
+    assert_eq!(t.namespace_uri("SVG"), None);
+    t.parse_next().unwrap();
+    t.parse_next().unwrap();
+    assert_eq!(t.namespace_uri("svg"), Some("c"));
+    assert_eq!(t.default_namespace(), Some("b"));
+}
+
+test_err!(namespace_4,
+    "@namespace svg;",
+    Error::UnknownToken(ErrorPos::new(1, 15))
+);
+
+macro_rules! test_declarations {
+    ($name:ident, $text:expr, $( $token:expr ),*) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new_declaration_list($text);
+            $(
+                assert_eq!(t.parse_next().unwrap(), $token);
+            )*
+            assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+        }
+    };
+}
+
+macro_rules! test_declarations_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new_declaration_list($text);
+            loop {
+                match t.parse_next() {
+                    Ok(Token::EndOfStream) => panic!("no error"),
+                    Ok(_) => {}
+                    Err(e) => {
+                        assert_eq!(e, $err);
+                        break;
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_declarations!(declaration_list_1,
+    "color: red; margin: 0",
+    Token::Declaration("color", "red"),
+    Token::Declaration("margin", "0")
+);
+
+test_declarations!(declaration_list_2,
+    " ;; color:red ; /* c */ --x: { a; b } ;--y:; ",
+    Token::Declaration("color", "red"),
+    Token::CustomProperty("--x", "{ a; b }"),
+    Token::CustomProperty("--y", "")
+);
+
+test_declarations!(declaration_list_3,
+    "--x: 1px",
+    Token::CustomProperty("--x", "1px")
+);
+
+test_declarations!(declaration_list_4,
+    "  ",
+);
+
+test_declarations!(declaration_list_5,
+    "font: 12px/1.5 \"Helvetica Neue\" !important;",
+    Token::Declaration("font", "12px/1.5 \"Helvetica Neue\" !important")
+);
+
+test_declarations_err!(declaration_list_err_1,
+    "color: red; p { color: red }",
+    Error::UnknownToken(ErrorPos::new(1, 13))
+);
+
+test_declarations_err!(declaration_list_err_2,
+    "color: red }",
+    Error::UnknownToken(ErrorPos::new(1, 12))
+);
+
+test_declarations_err!(declaration_list_err_3,
+    "@media print { color: red }",
+    Error::UnknownToken(ErrorPos::new(1, 1))
+);
+
+test_declarations_err!(declaration_list_err_4,
+    "color: red; .a { }",
+    Error::UnknownToken(ErrorPos::new(1, 13))
+);
+
+test_declarations_err!(declaration_list_err_5,
+    "color:",
+    Error::UnexpectedEndOfStream(ErrorPos::new(1, 7))
+);
+
+#[test]
+fn declaration_list_bound_1() {
+    let text = "<p style=\"color: red; margin 0\">";
+    let mut t = Tokenizer::new_declaration_list_bound(text, 10, 30);
+    assert_eq!(t.parse_next().unwrap(), Token::Declaration("color", "red"));
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 23)));
+}
+
+#[test]
+fn declaration_list_bound_2() {
+    let text = "<p style=\"color: red; margin: 0\">";
+    let mut t = Tokenizer::new_declaration_list_bound(text, 10, 31);
+    assert_eq!(t.parse_next().unwrap(), Token::Declaration("color", "red"));
+    assert_eq!(t.parse_next().unwrap(), Token::Declaration("margin", "0"));
+    assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+}
+
+macro_rules! test_selector_list {
+    ($name:ident, $text:expr, $( $token:expr ),*) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new_selector_list($text);
+            $(
+                assert_eq!(t.parse_next().unwrap(), $token);
+            )*
+            assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+        }
+    };
+}
+
+macro_rules! test_selector_list_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new_selector_list($text);
+            loop {
+                match t.parse_next() {
+                    Ok(Token::EndOfStream) => panic!("no error"),
+                    Ok(_) => {}
+                    Err(e) => {
+                        assert_eq!(e, $err);
+                        break;
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_selector_list!(selector_list_1,
+    ".list > li:nth-child(2)",
+    Token::ClassSelector("list"),
+    Token::Combinator(Combinator::GreaterThan),
+    Token::TypeSelector("li"),
+    Token::PseudoClass { selector: "nth-child", value: Some("2") }
+);
+
+test_selector_list!(selector_list_2,
+    "  a b, #c /* d */  ",
+    Token::TypeSelector("a"),
+    Token::Combinator(Combinator::Space),
+    Token::TypeSelector("b"),
+    Token::Comma,
+    Token::IdSelector("c")
+);
+
+test_selector_list!(selector_list_3,
+    "a , b ,c",
+    Token::TypeSelector("a"),
+    Token::Comma,
+    Token::TypeSelector("b"),
+    Token::Comma,
+    Token::TypeSelector("c")
+);
+
+test_selector_list_err!(selector_list_err_1,
+    ".a { color: red }",
+    Error::UnknownToken(ErrorPos::new(1, 4))
+);
+
+test_selector_list_err!(selector_list_err_2,
+    ".a, ",
+    Error::UnexpectedEndOfStream(ErrorPos::new(1, 5))
+);
+
+test_selector_list_err!(selector_list_err_3,
+    "",
+    Error::UnexpectedEndOfStream(ErrorPos::new(1, 1))
+);
+
+test_selector_list_err!(selector_list_err_4,
+    "a >",
+    Error::UnexpectedEndOfStream(ErrorPos::new(1, 4))
+);
+
+test_selector_list_err!(selector_list_err_5,
+    ", a",
+    Error::UnknownToken(ErrorPos::new(1, 1))
+);
+
+test_selector_list_err!(selector_list_err_6,
+    "a; b",
+    Error::UnknownToken(ErrorPos::new(1, 2))
+);
+
+test_selector_list_err!(selector_list_err_7,
+    "a }",
+    Error::UnknownToken(ErrorPos::new(1, 3))
+);
+
+#[test]
+fn selector_list_bound_1() {
+    let text = "query_selector(\".list > li {\")";
+    let mut t = Tokenizer::new_selector_list_bound(text, 16, 28);
+    assert_eq!(t.parse_next().unwrap(), Token::ClassSelector("list"));
+    assert_eq!(t.parse_next().unwrap(), Token::Combinator(Combinator::GreaterThan));
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("li"));
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 28)));
+}
+
+test!(cdo_cdc_1,
+    "<!-- p { color: red } -->",
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test!(cdo_cdc_2,
+    "<!--\n@import \"a.css\";\n/* c */ -->\n<!--p {}-->-->",
+    Token::AtRule("import"),
+    Token::AtStr("\"a.css\""),
+    Token::AtRuleEnd,
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(cdo_cdc_3,
+    "<!-- -->",
+);
+
+#[test]
+fn cdo_cdc_err_1() {
+    let mut t = Tokenizer::new("p <!-- {}");
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+    assert_eq!(t.parse_next().unwrap(), Token::Combinator(Combinator::Space));
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 3)));
+}
+
+#[test]
+fn cdo_cdc_err_2() {
+    let mut t = Tokenizer::new("p { <!-- }");
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn cdo_cdc_tokens_1() {
+    let mut t = Tokenizer::new("<!-- p {} --> <!--");
+    t.set_cdo_cdc_tokens(true);
+    assert_eq!(t.parse_next().unwrap(), Token::Cdo);
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap(), Token::BlockEnd);
+    assert_eq!(t.parse_next().unwrap(), Token::Cdc);
+    assert_eq!(t.parse_next().unwrap(), Token::Cdo);
+    assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+}
@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{DescriptorBlock, Tokenizer, Token, Error, ErrorPos, is_descriptor_at_rule};
+
+fn parse(text: &str) -> Result<DescriptorBlock<'_>, Error> {
+    let mut t = Tokenizer::new(text);
+    match t.parse_next().unwrap() {
+        Token::AtRule(name) => DescriptorBlock::parse(&mut t, name),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_1() {
+    let block = parse("@font-palette-values --x { font-family: Bixa; override-colors: 0 red, 1 blue; }")
+        .unwrap();
+    assert_eq!(block, DescriptorBlock {
+        name: "font-palette-values",
+        prelude: "--x",
+        descriptors: vec![("font-family", "Bixa"), ("override-colors", "0 red, 1 blue")],
+        blocks: vec![],
+    });
+}
+
+#[test]
+fn parse_2() {
+    let block = parse("@font-feature-values Font One { font-display: swap; \
+                       @styleset { nice-style: 12; other: 3 5 } @swash { fancy: 1 } }").unwrap();
+    assert_eq!(block.prelude, "Font One");
+    assert_eq!(block.descriptors, vec![("font-display", "swap")]);
+    assert_eq!(block.blocks, vec![
+        DescriptorBlock {
+            name: "styleset",
+            prelude: "",
+            descriptors: vec![("nice-style", "12"), ("other", "3 5")],
+            blocks: vec![],
+        },
+        DescriptorBlock {
+            name: "swash",
+            prelude: "",
+            descriptors: vec![("fancy", "1")],
+            blocks: vec![],
+        },
+    ]);
+    assert_eq!(block.blocks_named("SWASH").count(), 1);
+}
+
+#[test]
+fn parse_3() {
+    let block = parse("@view-transition { navigation: auto; types: a b }").unwrap();
+    assert_eq!(block.prelude, "");
+    assert_eq!(block.descriptor("types"), Some("a b"));
+    assert_eq!(block.descriptor("Navigation"), Some("auto"));
+    assert_eq!(block.descriptor("foo"), None);
+}
+
+#[test]
+fn parse_4() {
+    // Style rules and statement at-rules are skipped.
+    let mut t = Tokenizer::new("@counter-style x { p { color: red } @foo; system: cyclic; \
+                                .a > b { c: d } symbols: a } div {}");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("counter-style"));
+    let block = DescriptorBlock::parse(&mut t, "counter-style").unwrap();
+    assert_eq!(block.descriptors, vec![("system", "cyclic"), ("symbols", "a")]);
+    assert!(block.blocks.is_empty());
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("div"));
+}
+
+#[test]
+fn parse_5() {
+    // Descriptor blocks can be nested in style rules.
+    let mut t = Tokenizer::new(".a { @counter-style foo { system: fixed 3 } color: red }");
+    assert_eq!(t.parse_next().unwrap(), Token::ClassSelector("a"));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("counter-style"));
+    let block = DescriptorBlock::parse(&mut t, "counter-style").unwrap();
+    assert_eq!(block.descriptor("system"), Some("fixed 3"));
+    assert_eq!(t.parse_next().unwrap(), Token::Declaration("color", "red"));
+}
+
+#[test]
+fn parse_err_1() {
+    assert_eq!(parse("@view-transition;").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 17)));
+}
+
+#[test]
+fn is_descriptor_at_rule_1() {
+    assert!(is_descriptor_at_rule("counter-style"));
+    assert!(is_descriptor_at_rule("Styleset"));
+    assert!(!is_descriptor_at_rule("media"));
+    assert!(!is_descriptor_at_rule("page"));
+}
@@ -0,0 +1,141 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{FontFace, FontSource, FontStyle, FontDisplay, Tokenizer, Token, Error, ErrorPos};
+
+macro_rules! test_descriptor {
+    ($name:ident, $descriptor:expr, $value:expr, $field:ident, $result:expr) => {
+        #[test]
+        fn $name() {
+            let mut face = FontFace::default();
+            assert!(face.set_descriptor($descriptor, $value));
+            assert_eq!(face.$field, $result);
+        }
+    };
+}
+
+macro_rules! test_invalid {
+    ($name:ident, $descriptor:expr, $value:expr) => {
+        #[test]
+        fn $name() {
+            let mut face = FontFace::default();
+            assert!(!face.set_descriptor($descriptor, $value));
+            assert_eq!(face, FontFace::default());
+        }
+    };
+}
+
+fn parse(text: &str) -> FontFace<'_> {
+    let mut t = Tokenizer::new(text);
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("font-face"));
+    let face = FontFace::parse(&mut t).unwrap();
+    assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+    face
+}
+
+#[test]
+fn font_face_1() {
+    let face = parse("@font-face {
+        font-family: \"Open Sans\";
+        src: url(a.woff2) format(\"woff2\"), local(Foo);
+        unicode-range: U+0-7F;
+        font-weight: 100 900;
+        font-display: swap;
+    }");
+
+    assert_eq!(face, FontFace {
+        family: Some("Open Sans".to_string()),
+        sources: vec![
+            FontSource::Url { url: "a.woff2", format: Some("woff2"), tech: vec![] },
+            FontSource::Local("Foo".to_string()),
+        ],
+        weight: Some((100.0, 900.0)),
+        stretch: None,
+        style: None,
+        display: FontDisplay::Swap,
+        unicode_range: vec![(0, 0x7F)],
+    });
+}
+
+#[test]
+fn font_face_2() {
+    let face = parse("@font-face { font-family: A; foo: bar; font-weight: 1200; font-style: italic }");
+    assert_eq!(face.family, Some("A".to_string()));
+    assert_eq!(face.weight, None);
+    assert_eq!(face.style, Some(FontStyle::Italic));
+}
+
+test_descriptor!(family_1, "font-family", "'My Font'", family, Some("My Font".to_string()));
+
+test_descriptor!(family_2, "font-family", "My   Font", family, Some("My Font".to_string()));
+
+test_invalid!(family_3, "font-family", "serif");
+
+test_invalid!(family_4, "font-family", "1Font");
+
+test_descriptor!(src_1, "src", "url('a b.ttf')", sources,
+    vec![FontSource::Url { url: "a b.ttf", format: None, tech: vec![] }]);
+
+test_descriptor!(src_2, "src", "url(a.otf) format(opentype) tech(color-COLRv1, variations)", sources,
+    vec![FontSource::Url { url: "a.otf", format: Some("opentype"), tech: vec!["color-COLRv1", "variations"] }]);
+
+test_descriptor!(src_3, "src", "local(\"Foo Bold\"), url(a.woff) bar(), URL(b.woff)", sources,
+    vec![
+        FontSource::Local("Foo Bold".to_string()),
+        FontSource::Url { url: "b.woff", format: None, tech: vec![] },
+    ]);
+
+test_invalid!(src_4, "src", "url(a.woff) format()");
+
+test_invalid!(src_5, "src", "\"a.woff\"");
+
+test_descriptor!(weight_1, "font-weight", "bold", weight, Some((700.0, 700.0)));
+
+test_descriptor!(weight_2, "font-weight", "900 normal", weight, Some((400.0, 900.0)));
+
+test_descriptor!(weight_3, "font-weight", "auto", weight, None);
+
+test_invalid!(weight_4, "font-weight", "0");
+
+test_descriptor!(stretch_1, "font-stretch", "condensed expanded", stretch, Some((75.0, 125.0)));
+
+test_descriptor!(stretch_2, "font-stretch", "110%", stretch, Some((110.0, 110.0)));
+
+test_invalid!(stretch_3, "font-stretch", "-10%");
+
+test_descriptor!(style_1, "font-style", "oblique", style, Some(FontStyle::Oblique(14.0, 14.0)));
+
+test_descriptor!(style_2, "font-style", "oblique 20deg 10deg", style,
+    Some(FontStyle::Oblique(10.0, 20.0)));
+
+test_invalid!(style_3, "font-style", "oblique 100deg");
+
+test_descriptor!(display_1, "font-display", "OPTIONAL", display, FontDisplay::Optional);
+
+test_invalid!(display_2, "font-display", "none");
+
+test_descriptor!(unicode_range_1, "unicode-range", "U+26", unicode_range, vec![(0x26, 0x26)]);
+
+test_descriptor!(unicode_range_2, "unicode-range", "u+0025-00FF, U+4??", unicode_range,
+    vec![(0x25, 0xFF), (0x400, 0x4FF)]);
+
+test_descriptor!(unicode_range_3, "unicode-range", "U+1e3, U+10FFFF", unicode_range,
+    vec![(0x1E3, 0x1E3), (0x10FFFF, 0x10FFFF)]);
+
+test_invalid!(unicode_range_4, "unicode-range", "U+110000");
+
+test_invalid!(unicode_range_5, "unicode-range", "U+FF-00");
+
+test_invalid!(unicode_range_6, "unicode-range", "U+4?4");
+
+test_invalid!(unknown_1, "font-feature-settings", "\"liga\"");
+
+#[test]
+fn parse_err_empty_prelude() {
+    let mut t = Tokenizer::new("@font-face;");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("font-face"));
+    assert_eq!(FontFace::parse(&mut t).unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 11)));
+}
@@ -0,0 +1,117 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    EmbeddedStyleKind, Device, Tokenizer, Token, Error, ErrorPos, extract_styles,
+};
+
+fn tokens(mut t: Tokenizer) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    loop {
+        match t.parse_next().unwrap() {
+            Token::EndOfStream => return tokens,
+            token => tokens.push(token),
+        }
+    }
+}
+
+#[test]
+fn extract_1() {
+    let doc = "<html><head>\n\
+               <style media=\"print\">p { color: red }</style>\n\
+               </head><body style='margin: 0'><p STYLE=color:blue>a</p></body></html>";
+    let styles = extract_styles(doc);
+    assert_eq!(styles.len(), 3);
+
+    assert_eq!(styles[0].kind, EmbeddedStyleKind::StyleElement);
+    assert_eq!(styles[0].element, "style");
+    assert_eq!(styles[0].media, Some("print"));
+    assert_eq!(styles[0].text(), "p { color: red }");
+
+    assert_eq!(styles[1].kind, EmbeddedStyleKind::StyleAttribute);
+    assert_eq!(styles[1].element, "body");
+    assert_eq!(styles[1].text(), "margin: 0");
+    assert_eq!(tokens(styles[1].tokenizer()), vec![Token::Declaration("margin", "0")]);
+
+    assert_eq!(styles[2].element, "p");
+    assert_eq!(styles[2].text(), "color:blue");
+}
+
+#[test]
+fn extract_2() {
+    let doc = "<svg xmlns=\"http://www.w3.org/2000/svg\">\n\
+               <style type=\"text/css\"><![CDATA[\n  circle > a { fill: red }\n]]></style>\n\
+               <circle style=\"fill: blue\"/>\n\
+               </svg>";
+    let styles = extract_styles(doc);
+    assert_eq!(styles.len(), 2);
+    assert_eq!(styles[0].text(), "\n  circle > a { fill: red }\n");
+    assert_eq!(tokens(styles[0].tokenizer()), vec![
+        Token::TypeSelector("circle"),
+        Token::Combinator(azul_simplecss::Combinator::GreaterThan),
+        Token::TypeSelector("a"),
+        Token::BlockStart,
+        Token::Declaration("fill", "red"),
+        Token::BlockEnd,
+    ]);
+    assert_eq!(styles[1].element, "circle");
+}
+
+#[test]
+fn extract_3() {
+    // Comments, scripts and other style types are skipped.
+    let doc = "<!-- <style>a {}</style> -->\
+               <script>let s = '<style>b {}</style>';</script>\
+               <style type=\"text/less\">@x: 1;</style>\
+               <svg:style>c { d: e }</SVG:STYLE>\
+               <style></style>";
+    let styles = extract_styles(doc);
+    assert_eq!(styles.len(), 2);
+    assert_eq!(styles[0].element, "svg:style");
+    assert_eq!(styles[0].text(), "c { d: e }");
+    assert_eq!(styles[1].text(), "");
+    assert_eq!(tokens(styles[1].tokenizer()), vec![]);
+}
+
+#[test]
+fn extract_4() {
+    // Unterminated input doesn't panic.
+    assert_eq!(extract_styles("<p style=\"a: b").len(), 1);
+    assert_eq!(extract_styles("<style>a {}").len(), 1);
+    assert_eq!(extract_styles("<p style").len(), 0);
+    assert_eq!(extract_styles("<").len(), 0);
+    assert_eq!(extract_styles("<!--").len(), 0);
+}
+
+#[test]
+fn error_pos_1() {
+    let doc = "<html>\n<style>\na { color: red }\nb { : red }\n</style>\n<p style=\"x: 1; y\"></p>";
+    let styles = extract_styles(doc);
+
+    let mut t = styles[0].tokenizer();
+    let err = loop {
+        match t.parse_next() {
+            Ok(Token::EndOfStream) => panic!("no error"),
+            Ok(_) => {}
+            Err(e) => break e,
+        }
+    };
+    assert_eq!(err, Error::UnknownToken(ErrorPos::new(4, 6)));
+
+    let mut t = styles[1].tokenizer();
+    assert_eq!(t.parse_next().unwrap(), Token::Declaration("x", "1"));
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnexpectedEndOfStream(ErrorPos::new(6, 18)));
+}
+
+#[test]
+fn media_1() {
+    let doc = "<style media=\"print\"></style><style media=\"screen and (min-width: 100px)\">\
+               </style><style></style><style media=\"(\"></style>";
+    let styles = extract_styles(doc);
+    let device = Device { width: 200.0, ..Device::default() };
+    let matches: Vec<_> = styles.iter().map(|s| s.matches(&device)).collect();
+    assert_eq!(matches, vec![false, true, true, false]);
+}
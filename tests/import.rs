@@ -0,0 +1,218 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use std::collections::HashMap;
+
+use azul_simplecss::{
+    Import, ImportLayer, ImportError, StylesheetLoader, MediaQueryList, SupportsCondition,
+    SupportsFeature, Tokenizer, Token, Error, ErrorPos, resolve_imports,
+};
+
+struct MemoryLoader(HashMap<&'static str, &'static str>);
+
+impl StylesheetLoader for MemoryLoader {
+    // Resolves paths relative to the parent's directory, with `.` and `..` segments.
+    fn resolve(&self, url: &str, parent: Option<&str>) -> String {
+        let mut path: Vec<&str> = match parent {
+            Some(parent) if !url.starts_with('/') => parent.split('/').collect(),
+            _ => Vec::new(),
+        };
+        path.pop();
+
+        for segment in url.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    path.pop();
+                }
+                _ => path.push(segment),
+            }
+        }
+
+        path.join("/")
+    }
+
+    fn load(&self, url: &str) -> Option<String> {
+        self.0.get(url).map(|s| s.to_string())
+    }
+}
+
+fn loader(files: &[(&'static str, &'static str)]) -> MemoryLoader {
+    MemoryLoader(files.iter().cloned().collect())
+}
+
+macro_rules! test_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(Import::parse($text).unwrap_err(), $err);
+        }
+    };
+}
+
+#[test]
+fn import_1() {
+    assert_eq!(Import::parse("\"a.css\"").unwrap(), Import {
+        url: "a.css",
+        layer: None,
+        supports: None,
+        media: MediaQueryList { queries: vec![] },
+    });
+}
+
+#[test]
+fn import_2() {
+    let import = Import::parse("url( 'a b.css' ) layer supports(display: grid) screen, print").unwrap();
+    assert_eq!(import.url, "a b.css");
+    assert_eq!(import.layer, Some(ImportLayer::Anonymous));
+    assert_eq!(import.supports,
+               Some(SupportsCondition::Feature(SupportsFeature::Declaration("display", "grid"))));
+    assert_eq!(import.media, MediaQueryList::parse("screen, print").unwrap());
+}
+
+#[test]
+fn import_3() {
+    let import = Import::parse("url(a.css) layer(base.reset) supports(not (display: grid))").unwrap();
+    assert_eq!(import.url, "a.css");
+    assert_eq!(import.layer, Some(ImportLayer::Named("base.reset")));
+    assert!(matches!(import.supports, Some(SupportsCondition::Not(_))));
+    assert!(import.media.queries.is_empty());
+}
+
+#[test]
+fn import_4() {
+    let import = Import::parse("'a.css' (min-width: 800px)").unwrap();
+    assert_eq!(import.media, MediaQueryList::parse("(min-width: 800px)").unwrap());
+}
+
+#[test]
+fn import_5() {
+    let mut t = Tokenizer::new("@import url(\"a.css\") screen;\np { color: red }");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("import"));
+    let import = Import::parse(t.parse_at_rule_prelude().unwrap()).unwrap();
+    assert_eq!(import.url, "a.css");
+    assert_eq!(import.media.queries.len(), 1);
+    assert_eq!(t.parse_next().unwrap(), Token::AtRuleEnd);
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+}
+
+test_err!(invalid_1, "a.css", Error::UnknownToken(ErrorPos::new(1, 1)));
+
+test_err!(invalid_2, "'a.css' layer(1a)", Error::InvalidValue(ErrorPos::new(1, 9)));
+
+test_err!(invalid_3, "'a.css' supports(foo bar)", Error::InvalidValue(ErrorPos::new(1, 9)));
+
+test_err!(invalid_4, "'a.css' screen print", Error::UnknownToken(ErrorPos::new(1, 16)));
+
+test_err!(invalid_5, "'a.css", Error::UnexpectedEndOfStream(ErrorPos::new(1, 7)));
+
+#[test]
+fn resolve_1() {
+    let l = loader(&[("a.css", "a { b: c }")]);
+    assert_eq!(resolve_imports("@import 'a.css';\np { q: r }", &l, 8).unwrap(),
+               "a { b: c }\np { q: r }");
+}
+
+#[test]
+fn resolve_2() {
+    let l = loader(&[("a.css", "a { b: c }")]);
+    let text = "@import url(a.css) layer(base) supports(display: grid) screen and (color);";
+    assert_eq!(resolve_imports(text, &l, 8).unwrap(),
+               "@layer base {\n@supports (display: grid) {\n@media screen and (color) {\n\
+                a { b: c }\n}\n}\n}\n");
+}
+
+#[test]
+fn resolve_3() {
+    let l = loader(&[
+        ("a.css", "@charset \"utf-8\"; @import 'b.css' print; a { b: c }"),
+        ("b.css", "b { c: d }"),
+    ]);
+    assert_eq!(resolve_imports("@import 'a.css' layer;", &l, 8).unwrap(),
+               "@layer {\n@media print {\nb { c: d }\n}\na { b: c }\n}\n");
+}
+
+#[test]
+fn resolve_4() {
+    // Imports after other rules are ignored.
+    let l = loader(&[]);
+    assert_eq!(resolve_imports("p { q: r }\n@import 'a.css';", &l, 8).unwrap(),
+               "p { q: r }\n");
+}
+
+#[test]
+fn resolve_5() {
+    let text = "@import 'a.css';";
+    let l = loader(&[("a.css", "@import 'b.css';"), ("b.css", "@import 'a.css';")]);
+    assert_eq!(resolve_imports(text, &l, 8).unwrap_err(), ImportError::Cycle("a.css".to_string()));
+}
+
+#[test]
+fn resolve_6() {
+    let l = loader(&[("a.css", "@import 'b.css';"), ("b.css", "b { c: d }")]);
+    assert_eq!(resolve_imports("@import 'a.css';", &l, 1).unwrap_err(),
+               ImportError::TooDeep("b.css".to_string()));
+    assert!(resolve_imports("@import 'a.css';", &l, 2).is_ok());
+}
+
+#[test]
+fn resolve_7() {
+    let l = loader(&[]);
+    assert_eq!(resolve_imports("@import 'a.css';", &l, 8).unwrap_err(),
+               ImportError::NotFound("a.css".to_string()));
+}
+
+#[test]
+fn resolve_8() {
+    let l = loader(&[("a.css", "\n@import 'x' layer(1);")]);
+    assert_eq!(resolve_imports("@import 'a.css';", &l, 8).unwrap_err(), ImportError::Parse {
+        url: Some("a.css".to_string()),
+        error: Error::InvalidValue(ErrorPos::new(2, 13)),
+    });
+}
+
+#[test]
+fn resolve_9() {
+    // `@layer` statements are allowed before imports.
+    let l = loader(&[("a.css", "@layer x, y;\na { b: c }")]);
+    let text = "@layer reset, base;\n@import url(a.css) layer(base);\n@media print { p { q: r } }";
+    assert_eq!(resolve_imports(text, &l, 8).unwrap(),
+               "@layer reset, base;\n@layer base {\n@layer x, y;\na { b: c }\n}\n\
+                @media print { p { q: r } }");
+}
+
+#[test]
+fn resolve_10() {
+    // Relative URLs are resolved against the importing stylesheet.
+    let l = loader(&[
+        ("style.css", "@import 'sub/index.css';\na {}"),
+        ("sub/index.css", "@import './style.css';\n@import 'deep/x.css';"),
+        ("sub/style.css", "b {}"),
+        ("sub/deep/x.css", "@import '../../c.css';"),
+        ("c.css", "c {}"),
+    ]);
+    assert_eq!(resolve_imports("@import 'style.css';", &l, 8).unwrap(),
+               "b {}\nc {}\na {}\n");
+}
+
+#[test]
+fn resolve_11() {
+    // Aliases of the same stylesheet are a cycle.
+    let l = loader(&[("a.css", "@import './a.css';")]);
+    assert_eq!(resolve_imports("@import 'a.css';", &l, 8).unwrap_err(),
+               ImportError::Cycle("a.css".to_string()));
+
+    let l = loader(&[("a/b.css", "@import '../a/b.css';")]);
+    assert_eq!(resolve_imports("@import 'a/b.css';", &l, 8).unwrap_err(),
+               ImportError::Cycle("a/b.css".to_string()));
+}
+
+#[test]
+fn resolve_12() {
+    let l = loader(&[("a/b.css", "@import 'c.css';")]);
+    assert_eq!(resolve_imports("@import 'a/b.css';", &l, 8).unwrap_err(),
+               ImportError::NotFound("a/c.css".to_string()));
+}
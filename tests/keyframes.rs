@@ -0,0 +1,146 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{Keyframes, Keyframe, Tokenizer, Token, Error, ErrorPos};
+
+macro_rules! test {
+    ($name:ident, $text:expr, $( $token:expr ),*) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new($text);
+            $(
+                assert_eq!(t.parse_next().unwrap(), $token);
+            )*
+            assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+        }
+    };
+}
+
+fn parse(text: &str) -> Result<Keyframes<'_>, Error> {
+    let mut t = Tokenizer::new(text);
+    match t.parse_next().unwrap() {
+        Token::AtRule(name) => Keyframes::parse(&mut t, name),
+        _ => unreachable!(),
+    }
+}
+
+test!(selectors_1,
+    "@keyframes a { 0% { b: c } 50.5%, 75% { b: d } }",
+    Token::AtRule("keyframes"),
+    Token::AtStr("a"),
+    Token::BlockStart,
+    Token::KeyframeSelector(0.0),
+    Token::BlockStart,
+    Token::Declaration("b", "c"),
+    Token::BlockEnd,
+    Token::KeyframeSelector(50.5),
+    Token::Comma,
+    Token::KeyframeSelector(75.0),
+    Token::BlockStart,
+    Token::Declaration("b", "d"),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+test!(selectors_2,
+    "@-webkit-keyframes a { FROM, 100% { b: c } } p { b: c }",
+    Token::AtRule("-webkit-keyframes"),
+    Token::AtStr("a"),
+    Token::BlockStart,
+    Token::KeyframeSelector(0.0),
+    Token::Comma,
+    Token::KeyframeSelector(100.0),
+    Token::BlockStart,
+    Token::Declaration("b", "c"),
+    Token::BlockEnd,
+    Token::BlockEnd,
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::Declaration("b", "c"),
+    Token::BlockEnd
+);
+
+test!(selectors_3,
+    ".a { @keyframes b { to { c: d } } }",
+    Token::ClassSelector("a"),
+    Token::BlockStart,
+    Token::AtRule("keyframes"),
+    Token::AtStr("b"),
+    Token::BlockStart,
+    Token::KeyframeSelector(100.0),
+    Token::BlockStart,
+    Token::Declaration("c", "d"),
+    Token::BlockEnd,
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+#[test]
+fn selectors_err_1() {
+    let mut t = Tokenizer::new("@keyframes a { 150% { b: c } }");
+    for _ in 0..3 {
+        t.parse_next().unwrap();
+    }
+    assert_eq!(t.parse_next().unwrap_err(), Error::InvalidValue(ErrorPos::new(1, 16)));
+}
+
+#[test]
+fn selectors_err_2() {
+    let mut t = Tokenizer::new("@keyframes a { 50 { b: c } }");
+    for _ in 0..3 {
+        t.parse_next().unwrap();
+    }
+    assert_eq!(t.parse_next().unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 18)));
+}
+
+#[test]
+fn keyframes_1() {
+    let k = parse("@keyframes fade { to { opacity: 0 } from { opacity: 1 } 50% { opacity: 0.5 } }")
+        .unwrap();
+    assert_eq!(k, Keyframes {
+        name: "fade",
+        vendor_prefix: None,
+        keyframes: vec![
+            Keyframe { offset: 0.0, declarations: vec![("opacity", "1")] },
+            Keyframe { offset: 50.0, declarations: vec![("opacity", "0.5")] },
+            Keyframe { offset: 100.0, declarations: vec![("opacity", "0")] },
+        ],
+    });
+}
+
+#[test]
+fn keyframes_2() {
+    let k = parse("@-moz-keyframes \"my anim\" { 0%, 100% { a: b; c: d } 100% { a: e !important; c: f } }")
+        .unwrap();
+    assert_eq!(k.name, "my anim");
+    assert_eq!(k.vendor_prefix, Some("-moz-"));
+    assert_eq!(k.keyframes, vec![
+        Keyframe { offset: 0.0, declarations: vec![("a", "b"), ("c", "d")] },
+        Keyframe { offset: 100.0, declarations: vec![("a", "b"), ("c", "f")] },
+    ]);
+}
+
+#[test]
+fn keyframes_3() {
+    let mut t = Tokenizer::new("@keyframes a { 10% { b: c } } p { d: e }");
+    match t.parse_next().unwrap() {
+        Token::AtRule(name) => assert_eq!(Keyframes::parse(&mut t, name).unwrap().keyframes.len(), 1),
+        _ => unreachable!(),
+    }
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+}
+
+#[test]
+fn keyframes_err_1() {
+    assert_eq!(parse("@keyframes none { }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 12)));
+}
+
+#[test]
+fn keyframes_err_2() {
+    assert_eq!(parse("@media print { }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 2)));
+}
@@ -0,0 +1,195 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use std::cmp::Ordering;
+
+use azul_simplecss::{
+    LayerList, LayerOrder, CascadePriority, CascadeLayers, Tokenizer, Token, Error, ErrorPos,
+};
+
+fn names(text: &str) -> Vec<&str> {
+    LayerList::parse(text).unwrap().names
+}
+
+#[test]
+fn parse_1() {
+    assert_eq!(names("reset, base, theme"), vec!["reset", "base", "theme"]);
+}
+
+#[test]
+fn parse_2() {
+    assert_eq!(names("  base.forms ,theme.dark.high-contrast "),
+               vec!["base.forms", "theme.dark.high-contrast"]);
+}
+
+#[test]
+fn parse_3() {
+    assert_eq!(names(""), Vec::<&str>::new());
+}
+
+#[test]
+fn parse_err_1() {
+    assert_eq!(LayerList::parse("base, initial").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 7)));
+}
+
+#[test]
+fn parse_err_2() {
+    assert_eq!(LayerList::parse("base. forms").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 6)));
+}
+
+#[test]
+fn parse_err_3() {
+    assert_eq!(LayerList::parse("base,").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 6)));
+}
+
+#[test]
+fn parse_err_4() {
+    assert_eq!(LayerList::parse("base theme").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 6)));
+}
+
+#[test]
+fn order_1() {
+    let mut order = LayerOrder::new();
+    let reset = order.declare(None, "reset");
+    let base = order.declare(None, "base");
+    assert_eq!(order.declare(None, "reset"), reset);
+    assert_eq!(order.compare(Some(reset), Some(base)), Ordering::Less);
+    assert_eq!(order.compare(Some(base), None), Ordering::Less);
+    assert_eq!(order.len(), 2);
+}
+
+#[test]
+fn order_2() {
+    // Sub-layers come before the parent's own declarations.
+    let mut order = LayerOrder::new();
+    let a = order.declare(None, "a");
+    let b = order.declare(None, "b");
+    let a_y = order.declare(None, "a.y");
+    let a_x = order.declare(Some(a), "x");
+
+    assert_eq!(order.rank(Some(a_y)), 0);
+    assert_eq!(order.rank(Some(a_x)), 1);
+    assert_eq!(order.rank(Some(a)), 2);
+    assert_eq!(order.rank(Some(b)), 3);
+    assert_eq!(order.rank(None), 4);
+}
+
+#[test]
+fn order_3() {
+    let mut order = LayerOrder::new();
+    let a = order.declare_anonymous(None);
+    let b = order.declare_anonymous(None);
+    assert_ne!(a, b);
+    assert_eq!(order.name(a), None);
+    assert_eq!(order.compare(Some(a), Some(b)), Ordering::Less);
+}
+
+#[test]
+fn order_4() {
+    let mut order = LayerOrder::new();
+    let forms = order.declare(None, "base.forms");
+    assert_eq!(order.find("base.forms"), Some(forms));
+    assert_eq!(order.find("forms"), None);
+    assert_eq!(order.name(forms), Some("base.forms".to_string()));
+    assert!(order.find("base").is_some());
+}
+
+fn priority(important: bool, layer: usize, specificity: (u32, u32, u32), source_order: usize)
+    -> CascadePriority
+{
+    CascadePriority { important, layer, scope_proximity: None, specificity, source_order }
+}
+
+#[test]
+fn priority_1() {
+    // A later layer wins over a more specific selector.
+    assert!(priority(false, 1, (0, 0, 1), 0) > priority(false, 0, (1, 0, 0), 1));
+}
+
+#[test]
+fn priority_2() {
+    // Important declarations reverse the layer order.
+    assert!(priority(true, 0, (0, 0, 1), 0) > priority(true, 1, (1, 0, 0), 1));
+}
+
+#[test]
+fn priority_3() {
+    assert!(priority(true, 0, (0, 0, 0), 0) > priority(false, 5, (1, 0, 0), 1));
+}
+
+#[test]
+fn priority_4() {
+    assert!(priority(false, 1, (0, 1, 0), 0) > priority(false, 1, (0, 0, 3), 1));
+    assert!(priority(false, 1, (0, 1, 0), 2) > priority(false, 1, (0, 1, 0), 1));
+}
+
+fn collect(text: &str) -> (Vec<(Token<'_>, Option<String>)>, LayerOrder) {
+    let mut layers = CascadeLayers::new(Tokenizer::new(text));
+    let mut tokens = Vec::new();
+    loop {
+        let token = layers.parse_next().unwrap();
+        if token == Token::EndOfStream {
+            break;
+        }
+
+        let name = layers.current_layer().map(|l| layers.order().name(l).unwrap_or_default());
+        tokens.push((token, name));
+    }
+
+    (tokens, layers.into_order())
+}
+
+#[test]
+fn tokens_1() {
+    let (tokens, order) = collect("@layer reset, base;\n@layer base { p { color: red } }\na {}");
+    assert_eq!(tokens, vec![
+        (Token::TypeSelector("p"), Some("base".to_string())),
+        (Token::BlockStart, Some("base".to_string())),
+        (Token::Declaration("color", "red"), Some("base".to_string())),
+        (Token::BlockEnd, Some("base".to_string())),
+        (Token::TypeSelector("a"), None),
+        (Token::BlockStart, None),
+        (Token::BlockEnd, None),
+    ]);
+    assert_eq!(order.rank(order.find("reset")), 0);
+    assert_eq!(order.rank(order.find("base")), 1);
+}
+
+#[test]
+fn tokens_2() {
+    let (tokens, order) = collect("@layer a { @layer b { p {} } } @layer { div {} }");
+    assert_eq!(tokens[0], (Token::TypeSelector("p"), Some("a.b".to_string())));
+    assert_eq!(tokens[3], (Token::TypeSelector("div"), Some(String::new())));
+    assert_eq!(order.len(), 3);
+}
+
+#[test]
+fn tokens_3() {
+    let (tokens, _) = collect("@media screen { @layer x { p {} } }");
+    assert_eq!(tokens[0], (Token::AtRule("media"), None));
+    assert_eq!(tokens[2], (Token::BlockStart, None));
+    assert_eq!(tokens[3], (Token::TypeSelector("p"), Some("x".to_string())));
+    assert_eq!(tokens[6], (Token::BlockEnd, None));
+}
+
+#[test]
+fn tokens_err_1() {
+    let mut layers = CascadeLayers::new(Tokenizer::new("@layer a, b { p {} }"));
+    assert_eq!(layers.parse_next().unwrap_err(), Error::InvalidValue(ErrorPos::new(1, 2)));
+}
+
+#[test]
+fn tokens_err_2() {
+    let mut layers = CascadeLayers::new(Tokenizer::new("p {}\n@layer base, 1x;"));
+    assert_eq!(layers.parse_next().unwrap(), Token::TypeSelector("p"));
+    assert_eq!(layers.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(layers.parse_next().unwrap(), Token::BlockEnd);
+    assert_eq!(layers.parse_next().unwrap_err(), Error::InvalidValue(ErrorPos::new(2, 14)));
+}
@@ -0,0 +1,315 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    MediaQueryList, MediaQuery, MediaQualifier, MediaType, MediaCondition, MediaFeature,
+    MediaBound, MediaValue, Device, ColorScheme, Pointer, Tokenizer, Token, Error, ErrorPos,
+};
+
+macro_rules! test {
+    ($name:ident, $text:expr, $( $query:expr ),*) => {
+        #[test]
+        fn $name() {
+            let list = MediaQueryList::parse($text).unwrap();
+            assert_eq!(list.queries, vec![$( $query ),*]);
+        }
+    };
+}
+
+macro_rules! test_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(MediaQueryList::parse($text).unwrap_err(), $err);
+        }
+    };
+}
+
+fn query<'a>(
+    qualifier: Option<MediaQualifier>,
+    media_type: Option<MediaType<'a>>,
+    condition: Option<MediaCondition<'a>>,
+) -> MediaQuery<'a> {
+    MediaQuery { qualifier, media_type, condition }
+}
+
+fn feature(f: MediaFeature) -> MediaCondition {
+    MediaCondition::Feature(f)
+}
+
+fn px(n: f32, inclusive: bool) -> Option<MediaBound<'static>> {
+    Some(MediaBound { value: MediaValue::Dimension(n, "px"), inclusive })
+}
+
+#[test]
+fn empty_1() {
+    assert!(MediaQueryList::parse("").unwrap().queries.is_empty());
+    assert!(MediaQueryList::parse("  ").unwrap().queries.is_empty());
+}
+
+test!(type_1, "screen", query(None, Some(MediaType::Screen), None));
+
+test!(type_2, "PRINT", query(None, Some(MediaType::Print), None));
+
+test!(type_3, "tv", query(None, Some(MediaType::Other("tv")), None));
+
+test!(type_4, "not print", query(Some(MediaQualifier::Not), Some(MediaType::Print), None));
+
+test!(type_5, "only screen", query(Some(MediaQualifier::Only), Some(MediaType::Screen), None));
+
+test!(list_1, "screen, print",
+    query(None, Some(MediaType::Screen), None),
+    query(None, Some(MediaType::Print), None)
+);
+
+test!(feature_1, "(color)",
+    query(None, None, Some(feature(MediaFeature::Boolean("color"))))
+);
+
+test!(feature_2, "(orientation: landscape)",
+    query(None, None, Some(feature(MediaFeature::Plain {
+        name: "orientation",
+        value: MediaValue::Ident("landscape"),
+    })))
+);
+
+test!(feature_3, "(min-width: 400px)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "width",
+        start: px(400.0, true),
+        end: None,
+    })))
+);
+
+test!(feature_4, "(max-width:800px)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "width",
+        start: None,
+        end: px(800.0, true),
+    })))
+);
+
+test!(feature_5, "(aspect-ratio: 16 / 9)",
+    query(None, None, Some(feature(MediaFeature::Plain {
+        name: "aspect-ratio",
+        value: MediaValue::Ratio(16.0, 9.0),
+    })))
+);
+
+test!(feature_6, "(min-resolution: 2dppx)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "resolution",
+        start: Some(MediaBound { value: MediaValue::Dimension(2.0, "dppx"), inclusive: true }),
+        end: None,
+    })))
+);
+
+test!(range_1, "(width >= 400px)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "width",
+        start: px(400.0, true),
+        end: None,
+    })))
+);
+
+test!(range_2, "(400px < width)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "width",
+        start: px(400.0, false),
+        end: None,
+    })))
+);
+
+test!(range_3, "(400px <= width < 800px)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "width",
+        start: px(400.0, true),
+        end: px(800.0, false),
+    })))
+);
+
+test!(range_4, "(800px > width >= 400px)",
+    query(None, None, Some(feature(MediaFeature::Range {
+        name: "width",
+        start: px(400.0, true),
+        end: px(800.0, false),
+    })))
+);
+
+test!(range_5, "(width = 800px)",
+    query(None, None, Some(feature(MediaFeature::Plain {
+        name: "width",
+        value: MediaValue::Dimension(800.0, "px"),
+    })))
+);
+
+test!(condition_1, "screen and (color) and (min-width: 400px)",
+    query(None, Some(MediaType::Screen), Some(MediaCondition::And(vec![
+        feature(MediaFeature::Boolean("color")),
+        feature(MediaFeature::Range { name: "width", start: px(400.0, true), end: None }),
+    ])))
+);
+
+test!(condition_2, "(color) or (hover)",
+    query(None, None, Some(MediaCondition::Or(vec![
+        feature(MediaFeature::Boolean("color")),
+        feature(MediaFeature::Boolean("hover")),
+    ])))
+);
+
+test!(condition_3, "not (color)",
+    query(None, None, Some(MediaCondition::Not(Box::new(
+        feature(MediaFeature::Boolean("color"))
+    ))))
+);
+
+test!(condition_4, "((color) or (hover)) and (width)",
+    query(None, None, Some(MediaCondition::And(vec![
+        MediaCondition::Or(vec![
+            feature(MediaFeature::Boolean("color")),
+            feature(MediaFeature::Boolean("hover")),
+        ]),
+        feature(MediaFeature::Boolean("width")),
+    ])))
+);
+
+test!(condition_5, "screen and not (color)",
+    query(None, Some(MediaType::Screen), Some(MediaCondition::Not(Box::new(
+        feature(MediaFeature::Boolean("color"))
+    ))))
+);
+
+test!(unknown_1, "(foo bar) or (color)",
+    query(None, None, Some(MediaCondition::Or(vec![
+        MediaCondition::Unknown("(foo bar)"),
+        feature(MediaFeature::Boolean("color")),
+    ])))
+);
+
+test!(unknown_2, "baz(1, 2)",
+    query(None, None, Some(MediaCondition::Unknown("baz(1, 2)")))
+);
+
+test_err!(invalid_1, "(color) and (hover) or (width)",
+    Error::UnknownToken(ErrorPos::new(1, 21)));
+
+test_err!(invalid_2, "screen and (color) or (hover)",
+    Error::UnknownToken(ErrorPos::new(1, 20)));
+
+test_err!(invalid_3, "and", Error::UnknownToken(ErrorPos::new(1, 1)));
+
+test_err!(invalid_4, "screen print", Error::UnknownToken(ErrorPos::new(1, 8)));
+
+test_err!(invalid_5, "screen,", Error::UnexpectedEndOfStream(ErrorPos::new(1, 8)));
+
+#[test]
+fn prelude_1() {
+    let mut t = Tokenizer::new("@media screen and (min-width: 400px) { p { color: red } }");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("media"));
+    let prelude = t.parse_at_rule_prelude().unwrap();
+    assert_eq!(prelude, "screen and (min-width: 400px)");
+    assert_eq!(MediaQueryList::parse(prelude).unwrap().queries.len(), 1);
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("p"));
+}
+
+macro_rules! test_match {
+    ($name:ident, $text:expr, $device:expr, $result:expr) => {
+        #[test]
+        fn $name() {
+            let list = MediaQueryList::parse($text).unwrap();
+            assert_eq!(list.matches(&$device), $result);
+        }
+    };
+}
+
+fn desktop() -> Device {
+    Device {
+        width: 1280.0,
+        height: 800.0,
+        ..Device::default()
+    }
+}
+
+fn phone() -> Device {
+    Device {
+        width: 360.0,
+        height: 640.0,
+        resolution: 3.0,
+        color_scheme: ColorScheme::Dark,
+        pointer: Pointer::Coarse,
+        any_pointer: Pointer::Coarse,
+        hover: false,
+        any_hover: false,
+        ..Device::default()
+    }
+}
+
+test_match!(match_1, "", desktop(), true);
+
+test_match!(match_2, "screen", desktop(), true);
+
+test_match!(match_3, "print", desktop(), false);
+
+test_match!(match_4, "not print", desktop(), true);
+
+test_match!(match_5, "tv, screen", desktop(), true);
+
+test_match!(match_6, "(min-width: 800px)", desktop(), true);
+
+test_match!(match_7, "(min-width: 800px)", phone(), false);
+
+test_match!(match_8, "(400px <= width < 1280px)", desktop(), false);
+
+test_match!(match_9, "(400px <= width <= 1280px)", desktop(), true);
+
+test_match!(match_10, "(max-width: 40em)", phone(), true);
+
+test_match!(match_11, "(orientation: portrait)", phone(), true);
+
+test_match!(match_12, "(orientation: portrait)", desktop(), false);
+
+test_match!(match_13, "(aspect-ratio > 1/1)", desktop(), true);
+
+test_match!(match_14, "(min-resolution: 2dppx)", phone(), true);
+
+test_match!(match_15, "(min-resolution: 192dpi)", desktop(), false);
+
+test_match!(match_16, "(prefers-color-scheme: dark)", phone(), true);
+
+test_match!(match_17, "(prefers-reduced-motion)", desktop(), false);
+
+test_match!(match_18, "(pointer: coarse) and (hover: none)", phone(), true);
+
+test_match!(match_19, "(hover)", desktop(), true);
+
+test_match!(match_20, "(color)", desktop(), true);
+
+test_match!(match_21, "(min-color: 10)", desktop(), false);
+
+test_match!(match_22, "(grid)", desktop(), false);
+
+// Unknown features are never true, even when negated.
+test_match!(unknown_match_1, "(foo)", desktop(), false);
+
+test_match!(unknown_match_2, "not (foo)", desktop(), false);
+
+test_match!(unknown_match_3, "not screen and (foo: 1)", desktop(), false);
+
+test_match!(unknown_match_4, "(foo) or (color)", desktop(), true);
+
+test_match!(unknown_match_5, "(min-orientation: portrait)", phone(), false);
+
+test_match!(unknown_match_6, "(width: 100%)", desktop(), false);
+
+#[test]
+fn match_resize() {
+    let list = MediaQueryList::parse("screen and (max-width: 600px)").unwrap();
+    let mut device = desktop();
+    assert!(!list.matches(&device));
+    device.width = 600.0;
+    assert!(list.matches(&device));
+}
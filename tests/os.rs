@@ -0,0 +1,136 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{Os, OsQuery, OsCondition, OsFilter, Tokenizer, Token, Error, ErrorPos};
+
+macro_rules! test_filter {
+    ($name:ident, $text:expr, $os:expr, $( $token:expr ),*) => {
+        #[test]
+        fn $name() {
+            let mut t = OsFilter::new(Tokenizer::new($text), $os);
+            $(
+                assert_eq!(t.parse_next().unwrap(), $token);
+            )*
+            assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+        }
+    };
+}
+
+fn query(negated: bool, os: Os) -> OsQuery {
+    OsQuery { negated, os }
+}
+
+#[test]
+fn parse_1() {
+    assert_eq!(OsCondition::parse("linux").unwrap().queries, vec![query(false, Os::Linux)]);
+}
+
+#[test]
+fn parse_2() {
+    assert_eq!(OsCondition::parse("not Windows").unwrap().queries,
+               vec![query(true, Os::Windows)]);
+}
+
+#[test]
+fn parse_3() {
+    assert_eq!(OsCondition::parse("macos, ios ,android").unwrap().queries,
+               vec![query(false, Os::MacOs), query(false, Os::Ios), query(false, Os::Android)]);
+}
+
+#[test]
+fn parse_4() {
+    assert_eq!(OsCondition::parse("(web), not (linux)").unwrap().queries,
+               vec![query(false, Os::Web), query(true, Os::Linux)]);
+}
+
+#[test]
+fn parse_err_1() {
+    assert_eq!(OsCondition::parse("linux, beos").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 8)));
+}
+
+#[test]
+fn parse_err_2() {
+    assert_eq!(OsCondition::parse("linux windows").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 7)));
+}
+
+#[test]
+fn parse_err_3() {
+    assert_eq!(OsCondition::parse("").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 1)));
+}
+
+#[test]
+fn matches_1() {
+    let c = OsCondition::parse("linux, macos").unwrap();
+    assert!(c.matches(Os::Linux));
+    assert!(c.matches(Os::MacOs));
+    assert!(!c.matches(Os::Windows));
+}
+
+#[test]
+fn matches_2() {
+    let c = OsCondition::parse("not web").unwrap();
+    assert!(c.matches(Os::Linux));
+    assert!(!c.matches(Os::Web));
+}
+
+test_filter!(filter_1, "@os linux { .button { color: red; } }", Os::Linux,
+    Token::ClassSelector("button"),
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test_filter!(filter_2, "@os linux { .button { color: red; } } p { color: blue }", Os::Windows,
+    Token::TypeSelector("p"),
+    Token::BlockStart,
+    Token::Declaration("color", "blue"),
+    Token::BlockEnd
+);
+
+test_filter!(filter_3, ".button { @os linux { background: blue; } color: red }", Os::Linux,
+    Token::ClassSelector("button"),
+    Token::BlockStart,
+    Token::Declaration("background", "blue"),
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test_filter!(filter_4, ".button { @os linux { background: blue; } color: red }", Os::Ios,
+    Token::ClassSelector("button"),
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+test_filter!(filter_5, "@os not windows { @os macos, ios { a { b: c } } }", Os::Ios,
+    Token::TypeSelector("a"),
+    Token::BlockStart,
+    Token::Declaration("b", "c"),
+    Token::BlockEnd
+);
+
+test_filter!(filter_6, "@media print { a { b: c } }", Os::Linux,
+    Token::AtRule("media"),
+    Token::AtStr("print"),
+    Token::BlockStart,
+    Token::TypeSelector("a"),
+    Token::BlockStart,
+    Token::Declaration("b", "c"),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+#[test]
+fn filter_err_1() {
+    let mut t = OsFilter::new(Tokenizer::new("a {}\n@os linux, beos { }"), Os::Linux);
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("a"));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap(), Token::BlockEnd);
+    assert_eq!(t.parse_next().unwrap_err(), Error::InvalidValue(ErrorPos::new(2, 12)));
+}
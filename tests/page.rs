@@ -0,0 +1,217 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    PageSelectorList, PageSelector, PagePseudoClass, PageContext, MarginBox, MarginBoxRule,
+    PageRule, Tokenizer, Token, Error, ErrorPos,
+};
+
+macro_rules! test {
+    ($name:ident, $text:expr, $( $token:expr ),*) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new($text);
+            $(
+                assert_eq!(t.parse_next().unwrap(), $token);
+            )*
+            assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+        }
+    };
+}
+
+fn parse(text: &str) -> Result<PageRule<'_>, Error> {
+    let mut t = Tokenizer::new(text);
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("page"));
+    PageRule::parse(&mut t)
+}
+
+fn page(name: Option<&str>, index: usize) -> PageContext<'_> {
+    PageContext { name, index, ..PageContext::default() }
+}
+
+test!(tokenize_1,
+    "@page :first { margin: 1in }",
+    Token::AtRule("page"),
+    Token::PseudoClass { selector: "first", value: None },
+    Token::BlockStart,
+    Token::Declaration("margin", "1in"),
+    Token::BlockEnd
+);
+
+test!(tokenize_2,
+    "@page toc:left, :blank { margin: 1in; @top-center { content: \"Title\" } }",
+    Token::AtRule("page"),
+    Token::AtStr("toc"),
+    Token::PseudoClass { selector: "left", value: None },
+    Token::Comma,
+    Token::PseudoClass { selector: "blank", value: None },
+    Token::BlockStart,
+    Token::Declaration("margin", "1in"),
+    Token::AtRule("top-center"),
+    Token::BlockStart,
+    Token::Declaration("content", "\"Title\""),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+test!(tokenize_3,
+    "@media print { @page :first { margin: 0 } }",
+    Token::AtRule("media"),
+    Token::AtStr("print"),
+    Token::BlockStart,
+    Token::AtRule("page"),
+    Token::PseudoClass { selector: "first", value: None },
+    Token::BlockStart,
+    Token::Declaration("margin", "0"),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+// Pseudo-classes after a space are still descendant selectors outside of at-rules.
+test!(tokenize_4,
+    "a :hover { color: red }",
+    Token::TypeSelector("a"),
+    Token::Combinator(azul_simplecss::Combinator::Space),
+    Token::PseudoClass { selector: "hover", value: None },
+    Token::BlockStart,
+    Token::Declaration("color", "red"),
+    Token::BlockEnd
+);
+
+#[test]
+fn selectors_1() {
+    assert_eq!(PageSelectorList::parse("").unwrap().selectors, vec![]);
+}
+
+#[test]
+fn selectors_2() {
+    assert_eq!(PageSelectorList::parse(" toc:first:LEFT , :blank, index ").unwrap().selectors, vec![
+        PageSelector {
+            name: Some("toc"),
+            pseudo_classes: vec![PagePseudoClass::First, PagePseudoClass::Left],
+        },
+        PageSelector { name: None, pseudo_classes: vec![PagePseudoClass::Blank] },
+        PageSelector { name: Some("index"), pseudo_classes: vec![] },
+    ]);
+}
+
+#[test]
+fn selectors_err_1() {
+    assert_eq!(PageSelectorList::parse(":last").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 2)));
+    assert_eq!(PageSelectorList::parse("auto").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 1)));
+    assert_eq!(PageSelectorList::parse("toc :first").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 5)));
+    assert_eq!(PageSelectorList::parse(":first,").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 8)));
+}
+
+#[test]
+fn specificity_1() {
+    let spec = |text| PageSelectorList::parse(text).unwrap().selectors[0].specificity();
+    assert_eq!(spec("toc"), (1, 0, 0));
+    assert_eq!(spec(":first:left"), (0, 1, 1));
+    assert_eq!(spec("toc:blank:first:right"), (1, 2, 1));
+}
+
+#[test]
+fn matches_1() {
+    let list = PageSelectorList::parse(":first").unwrap();
+    assert!(list.matches(&page(None, 0)));
+    assert!(!list.matches(&page(None, 1)));
+}
+
+#[test]
+fn matches_2() {
+    let left = PageSelectorList::parse(":left").unwrap();
+    let right = PageSelectorList::parse(":right").unwrap();
+    assert!(right.matches(&page(None, 0)));
+    assert!(left.matches(&page(None, 1)));
+    assert!(!left.matches(&page(None, 2)));
+
+    let rtl = PageContext { right_to_left: true, ..PageContext::default() };
+    assert!(left.matches(&rtl));
+    assert!(!right.matches(&rtl));
+}
+
+#[test]
+fn matches_3() {
+    let list = PageSelectorList::parse("toc:blank, index").unwrap();
+    assert!(list.matches(&page(Some("index"), 3)));
+    assert!(!list.matches(&page(Some("toc"), 3)));
+    assert!(list.matches(&PageContext { name: Some("toc"), blank: true, ..PageContext::default() }));
+    assert!(!list.matches(&page(None, 0)));
+    assert!(PageSelectorList::parse("").unwrap().matches(&page(None, 0)));
+}
+
+#[test]
+fn matches_4() {
+    let list = PageSelectorList::parse("toc, toc:first, :left").unwrap();
+    assert_eq!(list.matching_specificity(&page(Some("toc"), 0)), Some((1, 1, 0)));
+    assert_eq!(list.matching_specificity(&page(Some("toc"), 1)), Some((1, 0, 0)));
+    assert_eq!(list.matching_specificity(&page(None, 1)), Some((0, 0, 1)));
+    assert_eq!(list.matching_specificity(&page(None, 0)), None);
+}
+
+#[test]
+fn margin_box_1() {
+    assert_eq!(MarginBox::from_name("Top-Left-Corner"), Some(MarginBox::TopLeftCorner));
+    assert_eq!(MarginBox::from_name("right-middle"), Some(MarginBox::RightMiddle));
+    assert_eq!(MarginBox::from_name("top-middle"), None);
+    assert_eq!(MarginBox::BottomCenter.name(), "bottom-center");
+}
+
+#[test]
+fn rule_1() {
+    let rule = parse("@page :first { margin: 1in; @top-center { content: \"Title\" } }").unwrap();
+    assert_eq!(rule, PageRule {
+        selectors: PageSelectorList::parse(":first").unwrap(),
+        declarations: vec![("margin", "1in")],
+        margin_boxes: vec![
+            MarginBoxRule {
+                margin_box: MarginBox::TopCenter,
+                declarations: vec![("content", "\"Title\"")],
+            },
+        ],
+    });
+}
+
+#[test]
+fn rule_2() {
+    let rule = parse("@page { size: A4; @bottom-right { content: counter(page); color: gray } \
+                      @top-left-corner { content: none } margin: 0 }").unwrap();
+    assert!(rule.selectors.selectors.is_empty());
+    assert_eq!(rule.declarations, vec![("size", "A4"), ("margin", "0")]);
+    assert_eq!(rule.margin_boxes.len(), 2);
+    assert_eq!(rule.margin_boxes[0].margin_box, MarginBox::BottomRight);
+    assert_eq!(rule.margin_boxes[0].declarations,
+               vec![("content", "counter(page)"), ("color", "gray")]);
+    assert_eq!(rule.margin_boxes[1].margin_box, MarginBox::TopLeftCorner);
+}
+
+#[test]
+fn rule_3() {
+    // Unknown at-rules are skipped.
+    let mut t = Tokenizer::new("@page toc { @footnote { float: bottom } margin: 0 } a {}");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("page"));
+    let rule = PageRule::parse(&mut t).unwrap();
+    assert_eq!(rule.declarations, vec![("margin", "0")]);
+    assert!(rule.margin_boxes.is_empty());
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("a"));
+}
+
+#[test]
+fn rule_err_1() {
+    assert_eq!(parse("@page :nope { }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 8)));
+}
+
+#[test]
+fn rule_err_2() {
+    assert_eq!(parse("@page { @top-left x { } }").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 19)));
+}
@@ -0,0 +1,120 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    Tokenizer, Token, Error, ErrorPos, Validator, ValidationMode, ValidationError,
+    lookup_property, suggest_property, validate_declaration,
+};
+
+macro_rules! test_valid {
+    ($name:ident, $property:expr, $value:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(validate_declaration($property, $value), Ok(()));
+        }
+    };
+}
+
+macro_rules! test_invalid {
+    ($name:ident, $property:expr, $value:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(validate_declaration($property, $value), Err($err));
+        }
+    };
+}
+
+test_valid!(valid_1, "color", "rgb(0 0 0 / 50%)");
+test_valid!(valid_2, "width", "calc(100% - 2em)");
+test_valid!(valid_3, "margin", "0 auto");
+test_valid!(valid_4, "display", "inline flex");
+test_valid!(valid_5, "transition-duration", "1s, 200ms");
+test_valid!(valid_6, "z-index", "-1 !important");
+test_valid!(valid_7, "opacity", "var(--o)");
+test_valid!(valid_8, "--anything", "{ whatever }");
+test_valid!(valid_9, "Font-Size", "inherit");
+
+test_invalid!(invalid_1, "color", "12px", ValidationError::InvalidValue);
+test_invalid!(invalid_2, "z-index", "1.5", ValidationError::InvalidValue);
+test_invalid!(invalid_3, "width", "10px 20px", ValidationError::InvalidValue);
+test_invalid!(invalid_4, "colr", "red",
+    ValidationError::UnknownProperty { suggestion: Some("color") });
+test_invalid!(invalid_5, "backgorund-color", "red",
+    ValidationError::UnknownProperty { suggestion: Some("background-color") });
+test_invalid!(invalid_6, "qwerty", "1",
+    ValidationError::UnknownProperty { suggestion: None });
+
+#[test]
+fn lookup() {
+    let info = lookup_property("line-height").unwrap();
+    assert_eq!(info.name, "line-height");
+    assert!(info.inherited);
+    assert!(!lookup_property("z-index").unwrap().inherited);
+    assert_eq!(suggest_property("widht"), Some("width"));
+}
+
+#[test]
+fn validator_strict() {
+    let mut v = Validator::new(Tokenizer::new("a { color: red; widht: 1px }"),
+                               ValidationMode::Strict);
+    assert_eq!(v.parse_next().unwrap(), Token::TypeSelector("a"));
+    assert_eq!(v.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(v.parse_next().unwrap(), Token::Declaration("color", "red"));
+    assert_eq!(v.parse_next().unwrap_err(), Error::UnknownProperty(ErrorPos::new(1, 17)));
+}
+
+#[test]
+fn validator_strict_value() {
+    let mut v = Validator::new(Tokenizer::new("a {\n  color: 5px }"), ValidationMode::Strict);
+    assert_eq!(v.parse_next().unwrap(), Token::TypeSelector("a"));
+    assert_eq!(v.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(v.parse_next().unwrap_err(), Error::InvalidValue(ErrorPos::new(2, 10)));
+}
+
+#[test]
+fn validator_warn() {
+    let text = "@font-face { src: url(a.woff) } @media screen { a { colr: red; color: 1 } }";
+    let mut v = Validator::new(Tokenizer::new(text), ValidationMode::Warn);
+    loop {
+        if v.parse_next().unwrap() == Token::EndOfStream {
+            break;
+        }
+    }
+
+    let warnings = v.warnings();
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].name, "colr");
+    assert_eq!(warnings[0].pos, ErrorPos::new(1, 53));
+    assert_eq!(warnings[0].error, ValidationError::UnknownProperty { suggestion: Some("color") });
+    assert_eq!(warnings[1].name, "color");
+    assert_eq!(warnings[1].value, "1");
+    assert_eq!(warnings[1].error, ValidationError::InvalidValue);
+}
+
+#[test]
+fn validator_keyframes() {
+    let text = "@keyframes x { from { colr: red } to { opacity: 1 } }";
+    let mut v = Validator::new(Tokenizer::new(text), ValidationMode::Warn);
+    while v.parse_next().unwrap() != Token::EndOfStream {}
+
+    let warnings = v.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].name, "colr");
+    assert_eq!(warnings[0].pos, ErrorPos::new(1, 23));
+}
+
+#[test]
+fn validator_page() {
+    // Page descriptors are skipped, but properties and margin boxes are validated.
+    let text = "@page :first { size: A4; margin: 1in; @top-center { opacty: 1 } } \
+                @font-feature-values Font { @styleset { nice: 12 } }";
+    let mut v = Validator::new(Tokenizer::new(text), ValidationMode::Warn);
+    while v.parse_next().unwrap() != Token::EndOfStream {}
+
+    let warnings = v.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].name, "opacty");
+}
@@ -0,0 +1,181 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    PropertyRule, PropertySyntax, SyntaxComponent, SyntaxComponentName, SyntaxMultiplier,
+    ValueType, Tokenizer, Token, Error, ErrorPos,
+};
+
+fn parse(text: &str) -> Result<PropertyRule<'_>, Error> {
+    let mut t = Tokenizer::new(text);
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("property"));
+    PropertyRule::parse(&mut t)
+}
+
+fn syntax(text: &str) -> PropertySyntax<'_> {
+    PropertySyntax::parse(text).unwrap()
+}
+
+#[test]
+fn rule_1() {
+    let rule = parse("@property --angle { syntax: '<angle>'; inherits: false; initial-value: 0deg; }")
+        .unwrap();
+    assert_eq!(rule, PropertyRule {
+        name: "--angle",
+        syntax: PropertySyntax::Components(vec![
+            SyntaxComponent { name: SyntaxComponentName::Type(ValueType::Angle), multiplier: None },
+        ]),
+        inherits: false,
+        initial_value: Some("0deg"),
+    });
+}
+
+#[test]
+fn rule_2() {
+    let rule = parse("@property --x { syntax: \"*\"; inherits: true }").unwrap();
+    assert_eq!(rule.syntax, PropertySyntax::Universal);
+    assert!(rule.inherits);
+    assert_eq!(rule.initial_value, None);
+}
+
+#[test]
+fn rule_3() {
+    // Unknown descriptors are ignored and later ones win.
+    let mut t = Tokenizer::new("@property --c { syntax: '<number>'; foo: bar; syntax: '<color>'; \
+                                inherits: true; initial-value: rgb(0 0 0) } a {}");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("property"));
+    let rule = PropertyRule::parse(&mut t).unwrap();
+    assert_eq!(rule.initial_value, Some("rgb(0 0 0)"));
+    assert_eq!(t.parse_next().unwrap(), Token::TypeSelector("a"));
+}
+
+#[test]
+fn rule_4() {
+    // Keywords are ASCII case-insensitive.
+    assert!(parse("@property --x { syntax: '*'; inherits: TRUE }").unwrap().inherits);
+    assert!(!parse("@property --x { syntax: '*'; inherits: False }").unwrap().inherits);
+}
+
+#[test]
+fn rule_err_1() {
+    assert_eq!(parse("@property angle { syntax: '*'; inherits: true }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 11)));
+}
+
+#[test]
+fn rule_err_2() {
+    assert_eq!(parse("@property --a { inherits: true; initial-value: 1px }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 11)));
+    assert_eq!(parse("@property --a { syntax: '<length>'; initial-value: 1px }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 11)));
+    assert_eq!(parse("@property --a { syntax: '<length>'; inherits: false }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 11)));
+}
+
+#[test]
+fn rule_err_3() {
+    assert_eq!(parse("@property --a { syntax: <length>; inherits: true; initial-value: 1px }")
+                   .unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 25)));
+    assert_eq!(parse("@property --a { syntax: '<lenght>'; inherits: true; initial-value: 1px }")
+                   .unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 26)));
+    assert_eq!(parse("@property --a { syntax: '*'; inherits: yes }").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 40)));
+}
+
+#[test]
+fn rule_err_4() {
+    assert_eq!(parse("@property --a { syntax: '<length>'; inherits: true; initial-value: red }")
+                   .unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 68)));
+    // Not computationally independent.
+    assert_eq!(parse("@property --a { syntax: '<length>'; inherits: true; initial-value: 2em }")
+                   .unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 68)));
+    assert_eq!(parse("@property --a { syntax: '*'; inherits: true; initial-value: var(--b) }")
+                   .unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 61)));
+}
+
+#[test]
+fn syntax_1() {
+    assert_eq!(syntax(" <length> | auto | <color># | <integer>+ "), PropertySyntax::Components(vec![
+        SyntaxComponent { name: SyntaxComponentName::Type(ValueType::Length), multiplier: None },
+        SyntaxComponent { name: SyntaxComponentName::Ident("auto"), multiplier: None },
+        SyntaxComponent {
+            name: SyntaxComponentName::Type(ValueType::Color),
+            multiplier: Some(SyntaxMultiplier::CommaList),
+        },
+        SyntaxComponent {
+            name: SyntaxComponentName::Type(ValueType::Integer),
+            multiplier: Some(SyntaxMultiplier::SpaceList),
+        },
+    ]));
+}
+
+#[test]
+fn syntax_2() {
+    assert_eq!(syntax("<transform-list>"), PropertySyntax::Components(vec![
+        SyntaxComponent {
+            name: SyntaxComponentName::Type(ValueType::TransformFunction),
+            multiplier: Some(SyntaxMultiplier::SpaceList),
+        },
+    ]));
+}
+
+#[test]
+fn syntax_err_1() {
+    assert_eq!(PropertySyntax::parse("<length> <color>").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 10)));
+    assert_eq!(PropertySyntax::parse("<size>").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 1)));
+    assert_eq!(PropertySyntax::parse("auto | inherit").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 8)));
+    assert_eq!(PropertySyntax::parse("<transform-list>#").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 17)));
+    assert_eq!(PropertySyntax::parse("* | auto").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 3)));
+    assert_eq!(PropertySyntax::parse("<length> |").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 11)));
+}
+
+#[test]
+fn matches_1() {
+    let s = syntax("<length> | auto");
+    assert!(s.matches("10px"));
+    assert!(s.matches(" 0 "));
+    assert!(s.matches("calc(1em + 2px)"));
+    assert!(s.matches("auto"));
+    assert!(!s.matches("AUTO"));
+    assert!(!s.matches("10%"));
+    assert!(!s.matches("1px 2px"));
+}
+
+#[test]
+fn matches_2() {
+    let s = syntax("<color>#");
+    assert!(s.matches("red"));
+    assert!(s.matches("red, rgb(0 0 0), #fff"));
+    assert!(!s.matches("red green"));
+
+    let s = syntax("<length-percentage>+");
+    assert!(s.matches("1px 50%"));
+    assert!(!s.matches("1px, 50%"));
+}
+
+#[test]
+fn matches_3() {
+    assert!(syntax("<transform-list>").matches("rotate(45deg) translateX(10px)"));
+    assert!(!syntax("<transform-function>").matches("rotate(45deg) scale(2)"));
+    assert!(syntax("<url>").matches("url(a.png)"));
+    assert!(syntax("<resolution>").matches("2dppx"));
+    assert!(!syntax("<resolution>").matches("2px"));
+    assert!(syntax("<string> | <custom-ident>").matches("'a b'"));
+    assert!(syntax("<integer>").matches("3"));
+    assert!(!syntax("<integer>").matches("3.5"));
+    assert!(syntax("*").matches("anything { at } all"));
+}
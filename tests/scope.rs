@@ -0,0 +1,269 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    ScopeRule, SelectorList, Selector, SelectorComponent, Element, Namespaces, CascadePriority,
+    Tokenizer, Token, Error, ErrorPos,
+};
+
+struct NodeData {
+    name: &'static str,
+    class: &'static str,
+    parent: Option<usize>,
+    prev: Option<usize>,
+}
+
+struct Tree {
+    nodes: Vec<NodeData>,
+}
+
+impl Tree {
+    fn new() -> Tree {
+        Tree { nodes: Vec::new() }
+    }
+
+    /// Appends a node and returns its index.
+    fn add(&mut self, parent: Option<usize>, name: &'static str, class: &'static str) -> usize {
+        let prev = self.nodes.iter().rposition(|n| n.parent == parent && parent.is_some());
+        self.nodes.push(NodeData { name, class, parent, prev });
+        self.nodes.len() - 1
+    }
+
+    fn get(&self, idx: usize) -> Node<'_> {
+        Node { tree: self, idx }
+    }
+}
+
+#[derive(Clone,Copy)]
+struct Node<'t> {
+    tree: &'t Tree,
+    idx: usize,
+}
+
+impl<'t> PartialEq for Node<'t> {
+    fn eq(&self, other: &Node) -> bool {
+        self.idx == other.idx
+    }
+}
+
+impl<'t> Element for Node<'t> {
+    fn local_name(&self) -> &str {
+        self.tree.nodes[self.idx].name
+    }
+
+    fn namespace_uri(&self) -> Option<&str> {
+        None
+    }
+
+    fn attribute(&self, namespace_uri: Option<&str>, local_name: &str) -> Option<&str> {
+        if namespace_uri.is_none() && local_name == "class" {
+            return Some(self.tree.nodes[self.idx].class);
+        }
+
+        None
+    }
+
+    fn parent_element(&self) -> Option<Self> {
+        self.tree.nodes[self.idx].parent.map(|idx| self.tree.get(idx))
+    }
+
+    fn prev_sibling_element(&self) -> Option<Self> {
+        self.tree.nodes[self.idx].prev.map(|idx| self.tree.get(idx))
+    }
+}
+
+/// ```text
+/// body
+///   div.card                (1)
+///     img                   (2)
+///     div.content           (3)
+///       img                 (4)
+///       div.card            (5)
+///         img               (6)
+///   img                     (7)
+/// ```
+fn tree() -> Tree {
+    let mut t = Tree::new();
+    let body = t.add(None, "body", "");
+    let card = t.add(Some(body), "div", "card");
+    t.add(Some(card), "img", "");
+    let content = t.add(Some(card), "div", "content");
+    t.add(Some(content), "img", "");
+    let card2 = t.add(Some(content), "div", "card");
+    t.add(Some(card2), "img", "");
+    t.add(Some(body), "img", "");
+    t
+}
+
+fn selector(text: &str) -> Selector<'_> {
+    SelectorList::parse(text).unwrap().selectors.remove(0)
+}
+
+#[test]
+fn parse_1() {
+    let rule = ScopeRule::parse("(.card) to (.content)").unwrap();
+    assert_eq!(rule.root, Some(SelectorList::parse(".card").unwrap()));
+    assert_eq!(rule.limit, Some(SelectorList::parse(".content").unwrap()));
+}
+
+#[test]
+fn parse_2() {
+    let rule = ScopeRule::parse("( .a, #b > p )").unwrap();
+    assert_eq!(rule.root.unwrap().selectors.len(), 2);
+    assert_eq!(rule.limit, None);
+}
+
+#[test]
+fn parse_3() {
+    let rule = ScopeRule::parse("to (:scope > .x)").unwrap();
+    assert_eq!(rule.root, None);
+    assert_eq!(rule.limit.unwrap().selectors[0].components[0],
+               SelectorComponent::PseudoClass("scope", None));
+}
+
+#[test]
+fn parse_4() {
+    assert_eq!(ScopeRule::parse("").unwrap(), ScopeRule { root: None, limit: None });
+}
+
+#[test]
+fn parse_err_1() {
+    assert_eq!(ScopeRule::parse("(.a) from (.b)").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 6)));
+}
+
+#[test]
+fn parse_err_2() {
+    assert_eq!(ScopeRule::parse("(.a) to").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 8)));
+}
+
+#[test]
+fn parse_err_3() {
+    assert_eq!(ScopeRule::parse("(.a) to (.b %)").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 13)));
+}
+
+#[test]
+fn parse_err_4() {
+    assert_eq!(ScopeRule::parse("(.a) (.b)").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 6)));
+}
+
+#[test]
+fn proximity_1() {
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("(.card) to (.content)").unwrap();
+    let img = selector("img");
+
+    assert_eq!(rule.proximity(&img, &t.get(2), &ns, None), Some(1));
+    // Below the limit.
+    assert_eq!(rule.proximity(&img, &t.get(4), &ns, None), None);
+    // In the nested card scope.
+    assert_eq!(rule.proximity(&img, &t.get(6), &ns, None), Some(1));
+    // Outside of any scope.
+    assert_eq!(rule.proximity(&img, &t.get(7), &ns, None), None);
+}
+
+#[test]
+fn proximity_2() {
+    // Without a limit, the nearest root wins.
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("(.card)").unwrap();
+    let img = selector("img");
+
+    assert_eq!(rule.proximity(&img, &t.get(4), &ns, None), Some(2));
+    assert_eq!(rule.proximity(&img, &t.get(6), &ns, None), Some(1));
+}
+
+#[test]
+fn proximity_3() {
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("(.card)").unwrap();
+
+    // `:scope` matches the root itself.
+    assert_eq!(rule.proximity(&selector(":scope"), &t.get(1), &ns, None), Some(0));
+    assert_eq!(rule.proximity(&selector(":scope > img"), &t.get(2), &ns, None), Some(1));
+    assert_eq!(rule.proximity(&selector(":scope > img"), &t.get(4), &ns, None), None);
+    // Without `:scope`, the root itself can't match.
+    assert_eq!(rule.proximity(&selector(".card"), &t.get(1), &ns, None), None);
+    assert_eq!(rule.proximity(&selector(".card"), &t.get(5), &ns, None), Some(2));
+}
+
+#[test]
+fn proximity_4() {
+    // Ancestors outside of the scope don't match.
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("(.content)").unwrap();
+
+    assert_eq!(rule.proximity(&selector(".card img"), &t.get(6), &ns, None), Some(2));
+    assert_eq!(rule.proximity(&selector(".card img"), &t.get(4), &ns, None), None);
+    assert_eq!(rule.proximity(&selector("body img"), &t.get(4), &ns, None), None);
+}
+
+#[test]
+fn proximity_5() {
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("").unwrap();
+    let img = selector("img");
+
+    assert_eq!(rule.proximity(&img, &t.get(4), &ns, Some(&t.get(3))), Some(1));
+    assert_eq!(rule.proximity(&img, &t.get(2), &ns, Some(&t.get(3))), None);
+    assert_eq!(rule.proximity(&img, &t.get(7), &ns, None), Some(1));
+}
+
+#[test]
+fn proximity_6() {
+    // The limit can refer to the root with `:scope`.
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("(.card) to (:scope > .content)").unwrap();
+    assert_eq!(rule.proximity(&selector("img"), &t.get(4), &ns, None), None);
+    assert_eq!(rule.proximity(&selector("img"), &t.get(6), &ns, None), Some(1));
+}
+
+#[test]
+fn in_scope_1() {
+    let t = tree();
+    let ns = Namespaces::new();
+    let rule = ScopeRule::parse("(.card) to (.content)").unwrap();
+    assert!(rule.in_scope(&t.get(1), &t.get(1), &ns));
+    assert!(rule.in_scope(&t.get(2), &t.get(1), &ns));
+    assert!(!rule.in_scope(&t.get(3), &t.get(1), &ns));
+    assert!(!rule.in_scope(&t.get(7), &t.get(1), &ns));
+}
+
+#[test]
+fn cascade_1() {
+    let priority = |scope_proximity, specificity, source_order| CascadePriority {
+        important: false,
+        layer: 0,
+        scope_proximity,
+        specificity,
+        source_order,
+    };
+
+    // A closer scope wins over specificity and source order.
+    assert!(priority(Some(1), (0, 0, 1), 0) > priority(Some(2), (0, 1, 1), 1));
+    // Scoped wins over unscoped.
+    assert!(priority(Some(5), (0, 0, 1), 0) > priority(None, (0, 1, 1), 1));
+    assert!(priority(Some(1), (0, 0, 1), 0) < priority(Some(1), (0, 0, 1), 1));
+}
+
+#[test]
+fn tokenize_1() {
+    let mut t = Tokenizer::new("@scope (.card) to (.content) { :scope img { color: red } }");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("scope"));
+    let rule = ScopeRule::parse(t.parse_at_rule_prelude().unwrap()).unwrap();
+    assert!(rule.limit.is_some());
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+    assert_eq!(t.parse_next().unwrap(), Token::PseudoClass { selector: "scope", value: None });
+}
@@ -0,0 +1,398 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    Tokenizer, Token, Combinator, Error, ErrorPos,
+    Element, NamespaceConstraint, Namespaces, Attribute, AttributeOperator,
+};
+
+const SVG: &str = "http://www.w3.org/2000/svg";
+const XLINK: &str = "http://www.w3.org/1999/xlink";
+const XHTML: &str = "http://www.w3.org/1999/xhtml";
+
+macro_rules! test {
+    ($name:ident, $text:expr, $( $token:expr ),*) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new($text);
+            $(
+                assert_eq!(t.parse_next().unwrap(), $token);
+            )*
+            assert_eq!(t.parse_next().unwrap(), Token::EndOfStream);
+        }
+    };
+}
+
+macro_rules! test_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            let mut t = Tokenizer::new($text);
+            loop {
+                match t.parse_next() {
+                    Ok(Token::EndOfStream) => panic!("expected an error"),
+                    Ok(_) => {}
+                    Err(e) => {
+                        assert_eq!(e, $err);
+                        break;
+                    }
+                }
+            }
+        }
+    };
+}
+
+struct Node {
+    name: &'static str,
+    namespace: Option<&'static str>,
+    attributes: Vec<(Option<&'static str>, &'static str, &'static str)>,
+}
+
+impl Element for Node {
+    fn local_name(&self) -> &str {
+        self.name
+    }
+
+    fn namespace_uri(&self) -> Option<&str> {
+        self.namespace
+    }
+
+    fn attribute(&self, namespace_uri: Option<&str>, local_name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|a| a.0 == namespace_uri && a.1 == local_name)
+            .map(|a| a.2)
+    }
+
+    fn attribute_in_any_namespace(&self, local_name: &str) -> Option<&str> {
+        self.attributes.iter().find(|a| a.1 == local_name).map(|a| a.2)
+    }
+}
+
+fn node(name: &'static str, namespace: Option<&'static str>) -> Node {
+    Node { name, namespace, attributes: Vec::new() }
+}
+
+fn namespaces() -> Namespaces<'static> {
+    let mut ns = Namespaces::new();
+    ns.insert(Some("svg"), SVG);
+    ns.insert(Some("xlink"), XLINK);
+    ns
+}
+
+test!(tokenize_1, "svg|circle {}",
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Prefix("svg"), name: "circle" },
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_2, "*|* {}",
+    Token::QualifiedUniversalSelector(NamespaceConstraint::Any),
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_3, "|p {}",
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::None, name: "p" },
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_4, "svg|* {}",
+    Token::QualifiedUniversalSelector(NamespaceConstraint::Prefix("svg")),
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_5, "*|rect {}",
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Any, name: "rect" },
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_6, "svg|g > svg|rect, |p {}",
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Prefix("svg"), name: "g" },
+    Token::Combinator(Combinator::GreaterThan),
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Prefix("svg"), name: "rect" },
+    Token::Comma,
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::None, name: "p" },
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_7, "div |p {}",
+    Token::TypeSelector("div"),
+    Token::Combinator(Combinator::Space),
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::None, name: "p" },
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_8, "svg|a[xlink|href] {}",
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Prefix("svg"), name: "a" },
+    Token::AttributeSelector("xlink|href"),
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test!(tokenize_9, ".a { svg|rect { fill: red } }",
+    Token::ClassSelector("a"),
+    Token::BlockStart,
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Prefix("svg"), name: "rect" },
+    Token::BlockStart,
+    Token::Declaration("fill", "red"),
+    Token::BlockEnd,
+    Token::BlockEnd
+);
+
+test!(tokenize_10, "svg|circle.a:hover {}",
+    Token::QualifiedTypeSelector { namespace: NamespaceConstraint::Prefix("svg"), name: "circle" },
+    Token::ClassSelector("a"),
+    Token::PseudoClass { selector: "hover", value: None },
+    Token::BlockStart,
+    Token::BlockEnd
+);
+
+test_err!(tokenize_err_1, "|= {}", Error::UnknownToken(ErrorPos::new(1, 1)));
+
+test_err!(tokenize_err_2, "svg| {}", Error::UnknownToken(ErrorPos::new(1, 5)));
+
+#[test]
+fn tokenizer_namespaces() {
+    let mut t = Tokenizer::new("@namespace svg url(http://www.w3.org/2000/svg); svg|rect {}");
+    while t.parse_next().unwrap() != Token::BlockStart {}
+    assert_eq!(t.namespaces().uri("svg"), Some(SVG));
+    assert_eq!(t.namespaces().default_uri(), None);
+}
+
+#[test]
+fn attribute_1() {
+    assert_eq!(Attribute::parse("href").unwrap(), Attribute {
+        namespace: None,
+        name: "href",
+        operator: None,
+        case_insensitive: false,
+    });
+}
+
+#[test]
+fn attribute_2() {
+    assert_eq!(Attribute::parse("xlink|href").unwrap(), Attribute {
+        namespace: Some(NamespaceConstraint::Prefix("xlink")),
+        name: "href",
+        operator: None,
+        case_insensitive: false,
+    });
+}
+
+#[test]
+fn attribute_3() {
+    let attr = Attribute::parse("*|href^='#'").unwrap();
+    assert_eq!(attr.namespace, Some(NamespaceConstraint::Any));
+    assert_eq!(attr.operator, Some((AttributeOperator::Prefix, "#")));
+}
+
+#[test]
+fn attribute_4() {
+    let attr = Attribute::parse(" |lang |= en ").unwrap();
+    assert_eq!(attr.namespace, Some(NamespaceConstraint::None));
+    assert_eq!(attr.operator, Some((AttributeOperator::DashMatch, "en")));
+}
+
+#[test]
+fn attribute_5() {
+    let attr = Attribute::parse("lang|=en").unwrap();
+    assert_eq!(attr.namespace, None);
+    assert_eq!(attr.name, "lang");
+    assert_eq!(attr.operator, Some((AttributeOperator::DashMatch, "en")));
+}
+
+#[test]
+fn attribute_6() {
+    let attr = Attribute::parse("type=\"Text\" i").unwrap();
+    assert_eq!(attr.operator, Some((AttributeOperator::Equals, "Text")));
+    assert!(attr.case_insensitive);
+}
+
+#[test]
+fn attribute_err_1() {
+    assert_eq!(Attribute::parse("a=b x").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn attribute_err_2() {
+    assert_eq!(Attribute::parse("a b").unwrap_err(), Error::UnknownToken(ErrorPos::new(1, 3)));
+}
+
+#[test]
+fn match_type_1() {
+    let ns = namespaces();
+    let circle = node("circle", Some(SVG));
+    assert!(ns.matches_type(&circle, Some(NamespaceConstraint::Prefix("svg")), Some("circle")));
+    assert!(!ns.matches_type(&circle, Some(NamespaceConstraint::Prefix("xlink")), Some("circle")));
+    assert!(!ns.matches_type(&circle, Some(NamespaceConstraint::Prefix("svg")), Some("rect")));
+}
+
+#[test]
+fn match_type_2() {
+    let ns = namespaces();
+    let circle = node("circle", Some(SVG));
+    let p = node("p", None);
+    assert!(ns.matches_type(&circle, Some(NamespaceConstraint::Any), None));
+    assert!(ns.matches_type(&p, Some(NamespaceConstraint::Any), None));
+    assert!(!ns.matches_type(&circle, Some(NamespaceConstraint::None), None));
+    assert!(ns.matches_type(&p, Some(NamespaceConstraint::None), Some("p")));
+}
+
+#[test]
+fn match_type_3() {
+    // Without a default namespace, an unprefixed selector matches any namespace.
+    let ns = namespaces();
+    assert!(ns.matches_type(&node("circle", Some(SVG)), None, Some("circle")));
+    assert!(ns.matches_type(&node("p", None), None, Some("p")));
+}
+
+#[test]
+fn match_type_4() {
+    let mut ns = namespaces();
+    ns.insert(None, XHTML);
+    assert!(ns.matches_type(&node("p", Some(XHTML)), None, Some("p")));
+    assert!(!ns.matches_type(&node("p", None), None, Some("p")));
+    assert!(!ns.matches_type(&node("circle", Some(SVG)), None, None));
+}
+
+#[test]
+fn match_type_5() {
+    // Undeclared prefixes never match.
+    let ns = namespaces();
+    let circle = node("circle", Some(SVG));
+    assert!(!ns.matches_type(&circle, Some(NamespaceConstraint::Prefix("SVG")), None));
+}
+
+#[test]
+fn match_type_6() {
+    let mut ns = namespaces();
+    ns.insert(Some("svg"), XHTML);
+    assert!(!ns.matches_type(&node("circle", Some(SVG)), Some(NamespaceConstraint::Prefix("svg")), None));
+}
+
+#[test]
+fn match_type_7() {
+    // SVG names are case-sensitive, HTML names are not.
+    let ns = namespaces();
+    let gradient = node("linearGradient", Some(SVG));
+    assert!(ns.matches_type(&gradient, None, Some("linearGradient")));
+    assert!(!ns.matches_type(&gradient, None, Some("lineargradient")));
+    assert!(!ns.matches_type(&node("clipPath", None), None, Some("clippath")));
+    assert!(ns.matches_type(&node("div", Some(XHTML)), None, Some("DIV")));
+}
+
+#[test]
+fn match_attribute_1() {
+    let ns = namespaces();
+    let a = Node {
+        name: "a",
+        namespace: Some(SVG),
+        attributes: vec![(Some(XLINK), "href", "#target"), (None, "class", "x y")],
+    };
+
+    let check = |text| ns.matches_attribute(&a, &Attribute::parse(text).unwrap());
+    assert!(check("xlink|href"));
+    assert!(check("xlink|href^='#'"));
+    assert!(!check("href"));
+    assert!(!check("|href"));
+    assert!(check("*|href"));
+    assert!(!check("svg|href"));
+    assert!(!check("foo|href"));
+    assert!(check("class~=y"));
+    assert!(check("|class*=' '"));
+    assert!(!check("class=X"));
+    assert!(check("class='X Y' i"));
+}
+
+#[test]
+fn match_from_stylesheet() {
+    let text = "@namespace svg url(http://www.w3.org/2000/svg);
+                @namespace xlink 'http://www.w3.org/1999/xlink';
+                svg|a[xlink|href] {}";
+
+    let a = Node {
+        name: "a",
+        namespace: Some(SVG),
+        attributes: vec![(Some(XLINK), "href", "#x")],
+    };
+
+    let mut t = Tokenizer::new(text);
+    let mut matched = true;
+    loop {
+        match t.parse_next().unwrap() {
+            Token::QualifiedTypeSelector { namespace, name } => {
+                matched &= t.namespaces().matches_type(&a, Some(namespace), Some(name));
+            }
+            Token::AttributeSelector(text) => {
+                let attr = Attribute::parse(text).unwrap();
+                matched &= t.namespaces().matches_attribute(&a, &attr);
+            }
+            Token::BlockStart => break,
+            _ => {}
+        }
+    }
+
+    assert!(matched);
+}
+
+#[test]
+fn selector_list_1() {
+    use azul_simplecss::{SelectorList, SelectorComponent};
+
+    let list = SelectorList::parse("svg|a[xlink|href] > .b, *|*:not(#x)::before").unwrap();
+    assert_eq!(list.selectors.len(), 2);
+    assert_eq!(list.selectors[0].components, vec![
+        SelectorComponent::Type(Some(NamespaceConstraint::Prefix("svg")), "a"),
+        SelectorComponent::Attribute(Attribute::parse("xlink|href").unwrap()),
+        SelectorComponent::Combinator(Combinator::GreaterThan),
+        SelectorComponent::Class("b"),
+    ]);
+    assert_eq!(list.selectors[1].components, vec![
+        SelectorComponent::Universal(Some(NamespaceConstraint::Any)),
+        SelectorComponent::PseudoClass("not", Some("#x")),
+        SelectorComponent::PseudoElement("before", None),
+    ]);
+}
+
+#[test]
+fn selector_list_err_1() {
+    use azul_simplecss::SelectorList;
+
+    assert_eq!(SelectorList::parse("a, ").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 4)));
+    assert_eq!(SelectorList::parse("a > > b").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 5)));
+    assert_eq!(SelectorList::parse("a[b c]").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn specificity_1() {
+    use azul_simplecss::SelectorList;
+
+    let spec = |text| SelectorList::parse(text).unwrap().selectors[0].specificity();
+    assert_eq!(spec("*"), (0, 0, 0));
+    assert_eq!(spec("div#a.b[c]:hover::before"), (1, 3, 2));
+    assert_eq!(spec(":is(#a, .b) p"), (1, 0, 1));
+    assert_eq!(spec(":where(#a) p"), (0, 0, 1));
+    assert_eq!(spec(":not(.a.b)"), (0, 2, 0));
+}
+
+#[test]
+fn selector_list_err_2() {
+    use azul_simplecss::SelectorList;
+
+    assert_eq!(SelectorList::parse(".list > li  ").unwrap().selectors.len(), 1);
+    assert_eq!(SelectorList::parse(".a { }").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 4)));
+    assert_eq!(SelectorList::parse("").unwrap_err(),
+               Error::UnexpectedEndOfStream(ErrorPos::new(1, 1)));
+}
@@ -0,0 +1,134 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{Shorthand, Error, ErrorPos};
+
+macro_rules! test {
+    ($name:ident, $shorthand:expr, $text:expr, $( $longhand:expr ),*) => {
+        #[test]
+        fn $name() {
+            let list = Shorthand::from_name($shorthand).unwrap().expand($text).unwrap();
+            let list: Vec<(&str, &str)> = list.iter().map(|(n, v)| (*n, v.as_str())).collect();
+            assert_eq!(list, vec![$( $longhand ),*]);
+        }
+    };
+}
+
+macro_rules! test_err {
+    ($name:ident, $shorthand:expr, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            let shorthand = Shorthand::from_name($shorthand).unwrap();
+            assert_eq!(shorthand.expand($text).unwrap_err(), $err);
+        }
+    };
+}
+
+test!(margin_1, "margin", "1px",
+    ("margin-top", "1px"), ("margin-right", "1px"),
+    ("margin-bottom", "1px"), ("margin-left", "1px"));
+
+test!(margin_2, "margin", "1px auto 3%",
+    ("margin-top", "1px"), ("margin-right", "auto"),
+    ("margin-bottom", "3%"), ("margin-left", "auto"));
+
+test!(padding_1, "padding", "1px 2px 3px calc(1em + 2px)",
+    ("padding-top", "1px"), ("padding-right", "2px"),
+    ("padding-bottom", "3px"), ("padding-left", "calc(1em + 2px)"));
+
+test!(inherit, "inset", "inherit",
+    ("top", "inherit"), ("right", "inherit"), ("bottom", "inherit"), ("left", "inherit"));
+
+test!(gap, "gap", "10px 5%",
+    ("row-gap", "10px"), ("column-gap", "5%"));
+
+test!(border_1, "border-top", "solid #f00",
+    ("border-top-width", "medium"), ("border-top-style", "solid"),
+    ("border-top-color", "#f00"));
+
+test!(border_2, "border", "1px dashed",
+    ("border-top-width", "1px"), ("border-right-width", "1px"),
+    ("border-bottom-width", "1px"), ("border-left-width", "1px"),
+    ("border-top-style", "dashed"), ("border-right-style", "dashed"),
+    ("border-bottom-style", "dashed"), ("border-left-style", "dashed"),
+    ("border-top-color", "currentcolor"), ("border-right-color", "currentcolor"),
+    ("border-bottom-color", "currentcolor"), ("border-left-color", "currentcolor"));
+
+test!(border_radius, "border-radius", "10px 5% / 20px",
+    ("border-top-left-radius", "10px 20px"), ("border-top-right-radius", "5% 20px"),
+    ("border-bottom-right-radius", "10px 20px"), ("border-bottom-left-radius", "5% 20px"));
+
+test!(flex_1, "flex", "none",
+    ("flex-grow", "0"), ("flex-shrink", "0"), ("flex-basis", "auto"));
+
+test!(flex_2, "flex", "2",
+    ("flex-grow", "2"), ("flex-shrink", "1"), ("flex-basis", "0%"));
+
+test!(flex_3, "flex", "1 0 0",
+    ("flex-grow", "1"), ("flex-shrink", "0"), ("flex-basis", "0"));
+
+test!(flex_4, "flex", "10em 2",
+    ("flex-grow", "2"), ("flex-shrink", "1"), ("flex-basis", "10em"));
+
+test!(font_1, "font", "italic bold 12px/1.5 \"Open Sans\", sans-serif",
+    ("font-style", "italic"), ("font-variant", "normal"), ("font-weight", "bold"),
+    ("font-stretch", "normal"), ("font-size", "12px"), ("line-height", "1.5"),
+    ("font-family", "\"Open Sans\", sans-serif"));
+
+test!(font_2, "font", "oblique 10deg condensed 600 small serif",
+    ("font-style", "oblique 10deg"), ("font-variant", "normal"), ("font-weight", "600"),
+    ("font-stretch", "condensed"), ("font-size", "small"), ("line-height", "normal"),
+    ("font-family", "serif"));
+
+test!(background_1, "background", "url(a.png) no-repeat center / cover, #fff",
+    ("background-color", "#fff"),
+    ("background-image", "url(a.png), none"),
+    ("background-position", "center, 0% 0%"),
+    ("background-size", "cover, auto"),
+    ("background-repeat", "no-repeat, repeat"),
+    ("background-attachment", "scroll, scroll"),
+    ("background-origin", "padding-box, padding-box"),
+    ("background-clip", "border-box, border-box"));
+
+test!(background_2, "background", "left 10px top / 50% auto content-box red",
+    ("background-color", "red"),
+    ("background-image", "none"),
+    ("background-position", "left 10px top"),
+    ("background-size", "50% auto"),
+    ("background-repeat", "repeat"),
+    ("background-attachment", "scroll"),
+    ("background-origin", "content-box"),
+    ("background-clip", "content-box"));
+
+test!(transition, "transition", "opacity 0.3s ease-in, transform 200ms 1s",
+    ("transition-property", "opacity, transform"),
+    ("transition-duration", "0.3s, 200ms"),
+    ("transition-timing-function", "ease-in, ease"),
+    ("transition-delay", "0s, 1s"));
+
+test!(animation, "animation", "spin 1s cubic-bezier(0.1, 0.7, 1, 0.1) infinite none",
+    ("animation-name", "spin"),
+    ("animation-duration", "1s"),
+    ("animation-timing-function", "cubic-bezier(0.1, 0.7, 1, 0.1)"),
+    ("animation-delay", "0s"),
+    ("animation-iteration-count", "infinite"),
+    ("animation-direction", "normal"),
+    ("animation-fill-mode", "none"),
+    ("animation-play-state", "running"));
+
+test_err!(invalid_1, "margin", "1px 2px 3px 4px 5px", Error::InvalidValue(ErrorPos::new(1, 17)));
+test_err!(invalid_2, "margin", "1px inherit", Error::InvalidValue(ErrorPos::new(1, 5)));
+test_err!(invalid_3, "border", "1px 2px", Error::InvalidValue(ErrorPos::new(1, 5)));
+test_err!(invalid_4, "font", "bold serif", Error::InvalidValue(ErrorPos::new(1, 6)));
+test_err!(invalid_5, "background", "red, url(a.png)", Error::InvalidValue(ErrorPos::new(1, 1)));
+test_err!(invalid_6, "padding", "var(--gap) 1px", Error::UnsupportedToken(ErrorPos::new(1, 1)));
+test_err!(invalid_7, "border", "10% solid red", Error::InvalidValue(ErrorPos::new(1, 1)));
+test_err!(invalid_8, "border-width", "1px 10%", Error::InvalidValue(ErrorPos::new(1, 5)));
+
+#[test]
+fn not_a_shorthand() {
+    assert_eq!(Shorthand::from_name("color"), None);
+}
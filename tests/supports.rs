@@ -0,0 +1,125 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{
+    SupportsCondition, SupportsFeature, Tokenizer, Token, Error, ErrorPos, validate_declaration,
+};
+
+macro_rules! test {
+    ($name:ident, $text:expr, $condition:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(SupportsCondition::parse($text).unwrap(), $condition);
+        }
+    };
+}
+
+macro_rules! test_err {
+    ($name:ident, $text:expr, $err:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(SupportsCondition::parse($text).unwrap_err(), $err);
+        }
+    };
+}
+
+macro_rules! test_eval {
+    ($name:ident, $text:expr, $result:expr) => {
+        #[test]
+        fn $name() {
+            let condition = SupportsCondition::parse($text).unwrap();
+            assert_eq!(condition.eval(&is_supported), $result);
+        }
+    };
+}
+
+fn decl<'a>(name: &'a str, value: &'a str) -> SupportsCondition<'a> {
+    SupportsCondition::Feature(SupportsFeature::Declaration(name, value))
+}
+
+fn selector(text: &str) -> SupportsCondition<'_> {
+    SupportsCondition::Feature(SupportsFeature::Selector(text))
+}
+
+fn is_supported(feature: SupportsFeature) -> bool {
+    match feature {
+        SupportsFeature::Declaration(name, value) => validate_declaration(name, value).is_ok(),
+        SupportsFeature::Selector(text) => !text.contains(":has("),
+    }
+}
+
+test!(parse_1, "(display: grid)", decl("display", "grid"));
+
+test!(parse_2, "( display :  grid  )", decl("display", "grid"));
+
+test!(parse_3, "selector(:has(a))", selector(":has(a)"));
+
+test!(parse_4, "(display: grid) and (not selector(:has(a)))",
+    SupportsCondition::And(vec![
+        decl("display", "grid"),
+        SupportsCondition::Not(Box::new(selector(":has(a)"))),
+    ])
+);
+
+test!(parse_5, "not (display: grid)",
+    SupportsCondition::Not(Box::new(decl("display", "grid")))
+);
+
+test!(parse_6, "(display: grid) or (display: flex) or (float: left)",
+    SupportsCondition::Or(vec![
+        decl("display", "grid"),
+        decl("display", "flex"),
+        decl("float", "left"),
+    ])
+);
+
+test!(parse_7, "((a: b) or (c: d)) and (e: f)",
+    SupportsCondition::And(vec![
+        SupportsCondition::Or(vec![decl("a", "b"), decl("c", "d")]),
+        decl("e", "f"),
+    ])
+);
+
+test!(parse_8, "(--foo: calc(1px + 2px))", decl("--foo", "calc(1px + 2px)"));
+
+test!(parse_9, "(content: \")\")", decl("content", "\")\""));
+
+test!(unknown_1, "(foo bar)", SupportsCondition::Unknown("(foo bar)"));
+
+test!(unknown_2, "font-tech(color-COLRv1)", SupportsCondition::Unknown("font-tech(color-COLRv1)"));
+
+test!(unknown_3, "(display:)", SupportsCondition::Unknown("(display:)"));
+
+test_err!(invalid_1, "(a: b) and (c: d) or (e: f)", Error::UnknownToken(ErrorPos::new(1, 19)));
+
+test_err!(invalid_2, "(a: b) (c: d)", Error::UnknownToken(ErrorPos::new(1, 8)));
+
+test_err!(invalid_3, "(a: b", Error::UnknownToken(ErrorPos::new(1, 6)));
+
+test_err!(invalid_4, "and (a: b)", Error::UnknownToken(ErrorPos::new(1, 4)));
+
+test_eval!(eval_1, "(display: grid)", true);
+
+test_eval!(eval_2, "(display: gird)", false);
+
+test_eval!(eval_3, "(display: grid) and (not selector(:has(a)))", true);
+
+test_eval!(eval_4, "selector(:has(a))", false);
+
+test_eval!(eval_5, "(colour: red) or (color: red)", true);
+
+test_eval!(eval_6, "not (foo bar)", true);
+
+test_eval!(eval_7, "(foo bar)", false);
+
+#[test]
+fn prelude_1() {
+    let mut t = Tokenizer::new("@supports (display: grid) and (gap: 1px) { p { color: red } }");
+    assert_eq!(t.parse_next().unwrap(), Token::AtRule("supports"));
+    let condition = SupportsCondition::parse(t.parse_at_rule_prelude().unwrap()).unwrap();
+    assert!(condition.eval(&is_supported));
+    assert_eq!(t.parse_next().unwrap(), Token::BlockStart);
+}
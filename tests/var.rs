@@ -0,0 +1,132 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+extern crate azul_simplecss;
+
+use azul_simplecss::{CustomProperties, Error, ErrorPos};
+
+fn props(list: &[(&str, &str)]) -> CustomProperties {
+    let mut p = CustomProperties::new();
+    for &(name, value) in list {
+        p.insert(name, value);
+    }
+    p
+}
+
+#[test]
+fn substitute_1() {
+    let p = props(&[("--gap", "4px")]);
+    assert_eq!(p.substitute("calc(100% - 2 * var(--gap))").unwrap(), "calc(100% - 2 * 4px)");
+}
+
+#[test]
+fn substitute_2() {
+    let p = props(&[]);
+    assert_eq!(p.substitute("var(--a, var(--b, 1px 2px)) solid").unwrap(), "1px 2px solid");
+}
+
+#[test]
+fn substitute_3() {
+    let p = props(&[("--a", "red")]);
+    assert_eq!(p.substitute("\"var(--a)\" VAR( --a )").unwrap(), "\"var(--a)\" red");
+}
+
+#[test]
+fn substitute_4() {
+    let p = props(&[]);
+    assert_eq!(p.substitute("1px var(--missing)").unwrap_err(),
+               Error::InvalidValue(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn substitute_5() {
+    let p = props(&[]);
+    assert_eq!(p.substitute("var(gap)").unwrap_err(),
+               Error::UnknownToken(ErrorPos::new(1, 5)));
+}
+
+#[test]
+fn cascade_1() {
+    let inherited = props(&[("--base", "10px"), ("--color", "red")]);
+    let p = CustomProperties::cascade(&inherited, vec![
+        ("--gap", "calc(var(--base) * 2)"),
+        ("--color", "blue"),
+    ]);
+
+    assert_eq!(p.get("--base"), Some("10px"));
+    assert_eq!(p.get("--gap"), Some("calc(10px * 2)"));
+    assert_eq!(p.get("--color"), Some("blue"));
+}
+
+#[test]
+fn cascade_2() {
+    let p = CustomProperties::cascade(&CustomProperties::new(), vec![
+        ("--a", "var(--b)"),
+        ("--b", "var(--c, 1px)"),
+        ("--c", "var(--a)"),
+        ("--d", "var(--a, 2px)"),
+        ("--e", "var(--d)"),
+    ]);
+
+    assert_eq!(p.get("--a"), None);
+    assert_eq!(p.get("--b"), None);
+    assert_eq!(p.get("--c"), None);
+    assert_eq!(p.get("--d"), Some("2px"));
+    assert_eq!(p.get("--e"), Some("2px"));
+}
+
+#[test]
+fn cascade_3() {
+    let inherited = props(&[("--a", "1px")]);
+    let p = CustomProperties::cascade(&inherited, vec![("--a", "var(--a)")]);
+    assert!(p.is_empty());
+}
+
+fn registration<'a>(text: &'a str) -> azul_simplecss::PropertyRule<'a> {
+    let mut t = azul_simplecss::Tokenizer::new(text);
+    t.parse_next().unwrap();
+    azul_simplecss::PropertyRule::parse(&mut t).unwrap()
+}
+
+#[test]
+fn cascade_registered_1() {
+    let regs = vec![
+        registration("@property --size { syntax: '<length>'; inherits: false; initial-value: 0px }"),
+        registration("@property --color { syntax: '<color>'; inherits: true; initial-value: black }"),
+    ];
+
+    let inherited = props(&[("--size", "10px"), ("--color", "red")]);
+    let p = CustomProperties::cascade_registered(&inherited, vec![], &regs);
+
+    // Non-inherited properties are reset to the initial value.
+    assert_eq!(p.get("--size"), Some("0px"));
+    assert_eq!(p.get("--color"), Some("red"));
+
+    let p = CustomProperties::cascade_registered(&CustomProperties::new(), vec![], &regs);
+    assert_eq!(p.get("--color"), Some("black"));
+}
+
+#[test]
+fn cascade_registered_2() {
+    let regs = vec![
+        registration("@property --size { syntax: '<length>'; inherits: false; initial-value: 0px }"),
+        registration("@property --color { syntax: '<color>'; inherits: true; initial-value: black }"),
+    ];
+
+    let inherited = props(&[("--color", "red")]);
+    let p = CustomProperties::cascade_registered(&inherited, vec![
+        ("--size", "calc(var(--base) * 2)"),
+        ("--base", "4px"),
+        ("--color", "10px"),
+        ("--other", "var(--size)"),
+    ], &regs);
+
+    assert_eq!(p.get("--size"), Some("calc(4px * 2)"));
+    // Invalid at computed-value time, so inherited.
+    assert_eq!(p.get("--color"), Some("red"));
+    assert_eq!(p.get("--other"), Some("calc(4px * 2)"));
+
+    let p = CustomProperties::cascade_registered(&inherited, vec![("--size", "red")], &regs);
+    assert_eq!(p.get("--size"), Some("0px"));
+}